@@ -1,11 +1,17 @@
 use color_eyre::Result;
 use std::path::PathBuf;
+use tempfile::TempDir;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use clap_verbosity_flag::{LogLevel, Verbosity, VerbosityFilter};
 use snakedown::{
     config::{Config, ConfigBuilder},
-    render::SSG,
+    export::{
+        coverage::{CoverageConfig, CoverageFormat},
+        report::ReportFormat,
+    },
+    init::Site,
+    render::{SSG, layout::Layout},
 };
 
 #[allow(dead_code)]
@@ -31,7 +37,7 @@ fn verbose_long_help() -> Option<&'static str> {
     }
 }
 
-pub fn resolve_runtime_config(args: Args) -> Result<Config> {
+pub fn resolve_runtime_config(args: BuildArgs) -> Result<Config> {
     let mut config_builder = ConfigBuilder::default();
 
     if let Some(config_file_path) = discover_config_file(args.config_file) {
@@ -45,13 +51,56 @@ pub fn resolve_runtime_config(args: Args) -> Result<Config> {
         .with_skip_undoc(if args.skip_undoc { Some(true) } else { None })
         .with_skip_private(if args.skip_private { Some(true) } else { None })
         .with_exclude(args.exclude)
-        .with_ssg(args.ssg);
+        .with_ssg(args.ssg)
+        .with_layout(args.layout)
+        .with_json_output(args.json)
+        .with_llms_txt(if args.llms_txt { Some(true) } else { None })
+        .with_search_index(if args.search_index { Some(true) } else { None })
+        .with_project_version(args.project_version)
+        .with_fail_on_error(if args.fail_on_error { Some(true) } else { None });
 
     config_builder = config_builder.merge(cli_args_builder);
 
     config_builder.build()
 }
 
+/// Like `resolve_runtime_config` but always renders the standalone html site, into a
+/// temporary directory unless an output directory was given. The temporary directory is
+/// removed when the returned guard is dropped.
+pub fn resolve_serve_config(serve_args: &ServeArgs) -> Result<(Config, Option<TempDir>)> {
+    let mut args = serve_args.build.clone();
+    let temp_dir = match args.output_dir {
+        Some(_) => None,
+        None => Some(
+            tempfile::Builder::new()
+                .prefix("snakedown-serve-")
+                .tempdir()?,
+        ),
+    };
+    if let Some(temp_dir) = &temp_dir {
+        args.output_dir = Some(temp_dir.path().to_path_buf());
+    }
+    args.ssg = Some(SSG::Html);
+    Ok((resolve_runtime_config(args)?, temp_dir))
+}
+
+/// Like `resolve_runtime_config`, with the threshold given on the command line taking
+/// precedence over the one in the configuration file
+pub fn resolve_coverage_config(coverage_args: &CoverageArgs) -> Result<Config> {
+    let mut config = resolve_runtime_config(BuildArgs {
+        pkg_path: coverage_args.pkg_path.clone(),
+        config_file: coverage_args.config_file.clone(),
+        exclude: coverage_args.exclude.clone(),
+        ..BuildArgs::default()
+    })?;
+    if coverage_args.fail_under.is_some() {
+        config.coverage = CoverageConfig {
+            fail_under: coverage_args.fail_under,
+        };
+    }
+    Ok(config)
+}
+
 pub fn discover_config_file(arg_config_path: Option<PathBuf>) -> Option<PathBuf> {
     let mut candidates = vec![];
 
@@ -67,11 +116,52 @@ pub fn discover_config_file(arg_config_path: Option<PathBuf>) -> Option<PathBuf>
 }
 
 #[derive(Parser)]
-#[command(version, about, long_about= None)]
+#[command(version, about, long_about= None, args_conflicts_with_subcommands = true)]
 pub struct Args {
     #[command(flatten)]
     pub verbose: Verbosity,
 
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// `snakedown <pkg> <out>` without a subcommand is short for `snakedown build <pkg> <out>`
+    #[command(flatten)]
+    pub build: BuildArgs,
+}
+
+impl Args {
+    /// The subcommand to run, `build` if none was given
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Build(self.build))
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Render the docs, this is what runs if no subcommand is given
+    Build(BuildArgs),
+    /// Extract the documentation without writing anything, failing if any module can't be parsed
+    Check(BuildArgs),
+    /// Write a configuration file for the package in the current directory, and optionally a site to render it into
+    Init(InitArgs),
+    /// Remove the files a build with the same settings writes
+    Clean(BuildArgs),
+    /// Report how many public modules, classes, functions and methods have a docstring
+    Coverage(CoverageArgs),
+    /// Report docstrings that don't match the signature they document
+    Lint(LintArgs),
+    /// Compare the public API of two versions of a package, for the changelog
+    Diff(DiffArgs),
+    /// Fetch, list and inspect the Sphinx inventories of other projects
+    Inventory(InventoryArgs),
+    /// Serve the rendered html docs locally, rebuilding and reloading them when the package changes
+    Serve(ServeArgs),
+    /// Render the docs, then keep the rendered pages up to date while the package changes
+    Watch(WatchArgs),
+}
+
+#[derive(clap::Args, Clone, Default)]
+pub struct BuildArgs {
     /// The path of the root of the package
     pub pkg_path: Option<PathBuf>,
 
@@ -97,6 +187,170 @@ pub struct Args {
     /// What format to render the front matter in, (zola, hugo, plain markdown, etc.)
     #[arg(short, long, value_enum)]
     pub ssg: Option<SSG>,
+
+    /// How to lay out the rendered pages: one page per module, the whole package on a single page or a page per class and function
+    #[arg(long, value_enum)]
+    pub layout: Option<Layout>,
+
+    /// Also write the extracted documentation of the whole package to this file as JSON
+    #[arg(long, value_name = "FILE")]
+    pub json: Option<PathBuf>,
+
+    /// Also write `llms.txt` and `llms-full.txt` digests of the API to the output directory
+    #[arg(long, default_value_t = false)]
+    pub llms_txt: bool,
+
+    /// Also write a search index of every object and a small search widget to the output directory
+    #[arg(long, default_value_t = false)]
+    pub search_index: bool,
+
+    /// The version of the package, written to `objects.inv`
+    #[arg(long, value_name = "VERSION")]
+    pub project_version: Option<String>,
+
+    /// Exit with an error if any module can't be processed, e.g. because of a syntax error
+    #[arg(long, default_value_t = false)]
+    pub fail_on_error: bool,
+
+    /// Also write a report of the modules processed, the files written, the objects skipped and any errors or warnings to this file
+    #[arg(long, value_name = "FILE")]
+    pub build_report: Option<PathBuf>,
+
+    /// The format of the build report
+    #[arg(long, value_enum, default_value_t = ReportFormat::Json)]
+    pub build_report_format: ReportFormat,
+}
+
+#[derive(clap::Args)]
+pub struct InitArgs {
+    /// Where to write the configuration file
+    #[arg(default_value = "snakedown.toml")]
+    pub path: PathBuf,
+
+    /// Overwrite the configuration file, and the files of the site, if they already exist
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
+
+    /// Also set up a minimal site of this static site generator, with the API docs as a section
+    #[arg(long, value_enum)]
+    pub site: Option<Site>,
+
+    /// Where to set up the site
+    #[arg(long, default_value = "docs")]
+    pub site_dir: PathBuf,
+}
+
+#[derive(clap::Args)]
+pub struct CoverageArgs {
+    /// The path of the root of the package
+    pub pkg_path: Option<PathBuf>,
+
+    /// The path to the configuration file
+    #[arg(long, short)]
+    pub config_file: Option<PathBuf>,
+
+    /// Any files that should be excluded, can be file or directories and specific multiple times but currently globs are not supported
+    #[arg(short, long)]
+    pub exclude: Option<Vec<PathBuf>>,
+
+    /// How to write the report
+    #[arg(long, value_enum, default_value_t = CoverageFormat::Text)]
+    pub format: CoverageFormat,
+
+    /// Write the report to this file instead of printing it
+    #[arg(long, value_name = "FILE")]
+    pub report: Option<PathBuf>,
+
+    /// Fail if less than this percentage of the public objects is documented
+    #[arg(long, value_name = "PERCENT")]
+    pub fail_under: Option<f64>,
+}
+
+#[derive(clap::Args)]
+pub struct LintArgs {
+    #[command(flatten)]
+    pub build: BuildArgs,
+
+    /// Exit with an error if any issues are found
+    #[arg(long, default_value_t = false)]
+    pub fail_on_issues: bool,
+}
+
+#[derive(clap::Args)]
+pub struct DiffArgs {
+    /// The old version, the root of the package or a JSON export of it
+    pub old: PathBuf,
+
+    /// The new version, the root of the package or a JSON export of it
+    pub new: PathBuf,
+
+    /// Files to leave out of both versions, like `--exclude` of `build`
+    #[arg(short, long)]
+    pub exclude: Vec<PathBuf>,
+
+    /// Write the markdown to this file instead of printing it
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Exit with an error if any of the changes are breaking
+    #[arg(long, default_value_t = false)]
+    pub fail_on_breaking: bool,
+}
+
+#[derive(clap::Args)]
+pub struct InventoryArgs {
+    /// The directory inventories are cached in, `$HOME/.snakedown/cache` by default
+    #[arg(long, global = true)]
+    pub cache_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: InventoryCommand,
+}
+
+#[derive(Subcommand)]
+pub enum InventoryCommand {
+    /// Download the inventory of a project into the cache
+    Fetch {
+        /// The name to cache the inventory under, e.g. `numpy`
+        name: String,
+        /// Where to download the inventory from, e.g. `https://numpy.org/doc/stable/objects.inv`
+        url: String,
+    },
+    /// List the names of the cached inventories
+    List,
+    /// List the objects in an inventory
+    Inspect {
+        /// The name the inventory was cached under, or the path of an `objects.inv` file
+        inventory: String,
+        /// Only list the objects whose name contains this
+        #[arg(long)]
+        filter: Option<String>,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    /// The output directory defaults to a temporary directory and the output is always html
+    #[command(flatten)]
+    pub build: BuildArgs,
+
+    /// The address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// The port to listen on
+    #[arg(long, short, default_value_t = 8000)]
+    pub port: u16,
+}
+
+#[derive(clap::Args)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub build: BuildArgs,
+
+    /// How many milliseconds the package has to be left alone before rebuilding
+    #[arg(long, default_value_t = 50)]
+    pub debounce: u64,
 }
 
 #[cfg(test)]
@@ -134,12 +388,208 @@ fn test_custom_log_level_interface() -> Result<()> {
     #[test]
     fn test_args_defaults() -> Result<()> {
         let args = Args::parse_from(["snakedown"]);
-        assert!(args.pkg_path.is_none());
-        assert!(args.output_dir.is_none());
-        assert!(!args.skip_undoc);
-        assert!(!args.skip_private);
-        assert!(args.exclude.is_none());
-        assert!(args.ssg.is_none());
+        assert!(args.build.pkg_path.is_none());
+        assert!(args.build.output_dir.is_none());
+        assert!(!args.build.skip_undoc);
+        assert!(!args.build.skip_private);
+        assert!(args.build.exclude.is_none());
+        assert!(args.build.ssg.is_none());
+        assert!(args.build.layout.is_none());
+        assert!(args.build.json.is_none());
+        assert!(!args.build.llms_txt);
+        assert!(!args.build.search_index);
+        assert!(matches!(args.into_command(), Command::Build(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_subcommand_matches_old_invocation() -> Result<()> {
+        for args in [
+            Args::parse_from(["snakedown", "src/pkg", "dist", "--skip-private"]),
+            Args::parse_from(["snakedown", "build", "src/pkg", "dist", "--skip-private"]),
+        ] {
+            let Command::Build(build_args) = args.into_command() else {
+                panic!("expected the build subcommand");
+            };
+            assert_eq!(build_args.pkg_path, Some(PathBuf::from("src/pkg")));
+            assert_eq!(build_args.output_dir, Some(PathBuf::from("dist")));
+            assert!(build_args.skip_private);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_report_flags() -> Result<()> {
+        let args = Args::parse_from([
+            "snakedown",
+            "build",
+            "src/pkg",
+            "--build-report",
+            "report.sarif",
+            "--build-report-format",
+            "sarif",
+        ]);
+        let Command::Build(build_args) = args.into_command() else {
+            panic!("expected the build subcommand");
+        };
+        assert_eq!(build_args.build_report, Some(PathBuf::from("report.sarif")));
+        assert_eq!(build_args.build_report_format, ReportFormat::Sarif);
+        assert!(!build_args.fail_on_error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_clean_and_init_subcommands() -> Result<()> {
+        let Command::Check(build_args) =
+            Args::parse_from(["snakedown", "check", "src/pkg"]).into_command()
+        else {
+            panic!("expected the check subcommand");
+        };
+        assert_eq!(build_args.pkg_path, Some(PathBuf::from("src/pkg")));
+
+        assert!(matches!(
+            Args::parse_from(["snakedown", "clean", "src/pkg", "dist"]).into_command(),
+            Command::Clean(_)
+        ));
+
+        let Command::Init(init_args) = Args::parse_from(["snakedown", "init"]).into_command()
+        else {
+            panic!("expected the init subcommand");
+        };
+        assert_eq!(init_args.path, PathBuf::from("snakedown.toml"));
+        assert!(!init_args.force);
+        assert_eq!(init_args.site, None);
+
+        let Command::Init(init_args) =
+            Args::parse_from(["snakedown", "init", "--site", "hugo", "--site-dir", "site"])
+                .into_command()
+        else {
+            panic!("expected the init subcommand");
+        };
+        assert_eq!(init_args.site, Some(Site::Hugo));
+        assert_eq!(init_args.site_dir, PathBuf::from("site"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_coverage_subcommand() -> Result<()> {
+        let args = Args::parse_from([
+            "snakedown",
+            "coverage",
+            "src/pkg",
+            "--format",
+            "junit",
+            "--report",
+            "coverage.xml",
+            "--fail-under",
+            "87.5",
+        ]);
+        let Command::Coverage(coverage_args) = args.into_command() else {
+            panic!("expected the coverage subcommand");
+        };
+        assert_eq!(coverage_args.format, CoverageFormat::Junit);
+        assert_eq!(coverage_args.report, Some(PathBuf::from("coverage.xml")));
+
+        let config = resolve_coverage_config(&coverage_args)?;
+        assert_eq!(config.coverage.fail_under, Some(87.5));
+        assert_eq!(config.pkg_path, PathBuf::from("src/pkg"));
+
+        // coverage doesn't render anything, so the options of the build don't apply
+        assert!(Args::try_parse_from(["snakedown", "coverage", "src/pkg", "--llms-txt"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_subcommand() -> Result<()> {
+        let args = Args::parse_from([
+            "snakedown",
+            "diff",
+            "old.json",
+            "src/pkg",
+            "--output",
+            "CHANGES.md",
+        ]);
+        let Command::Diff(diff_args) = args.into_command() else {
+            panic!("expected the diff subcommand");
+        };
+        assert_eq!(diff_args.old, PathBuf::from("old.json"));
+        assert_eq!(diff_args.new, PathBuf::from("src/pkg"));
+        assert_eq!(diff_args.output, Some(PathBuf::from("CHANGES.md")));
+        assert!(diff_args.exclude.is_empty());
+        assert!(!diff_args.fail_on_breaking);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_subcommand() -> Result<()> {
+        let args = Args::parse_from(["snakedown", "lint", "src/pkg", "--fail-on-issues"]);
+        let Command::Lint(lint_args) = args.into_command() else {
+            panic!("expected the lint subcommand");
+        };
+        assert_eq!(lint_args.build.pkg_path, Some(PathBuf::from("src/pkg")));
+        assert!(lint_args.fail_on_issues);
+        Ok(())
+    }
+
+    #[test]
+    fn test_inventory_subcommand() -> Result<()> {
+        let args = Args::parse_from([
+            "snakedown",
+            "inventory",
+            "inspect",
+            "numpy",
+            "--filter",
+            "ndarray",
+            "--cache-dir",
+            "cache",
+        ]);
+        let Command::Inventory(inventory_args) = args.into_command() else {
+            panic!("expected the inventory subcommand");
+        };
+        assert_eq!(inventory_args.cache_dir, Some(PathBuf::from("cache")));
+        let InventoryCommand::Inspect { inventory, filter } = inventory_args.command else {
+            panic!("expected inventory inspect");
+        };
+        assert_eq!(inventory, "numpy");
+        assert_eq!(filter.as_deref(), Some("ndarray"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_serve_subcommand() -> Result<()> {
+        let args = Args::parse_from(["snakedown", "serve", "src/pkg", "--port", "1111"]);
+        let Command::Serve(serve_args) = args.into_command() else {
+            panic!("expected the serve subcommand");
+        };
+        assert_eq!(serve_args.build.pkg_path, Some(PathBuf::from("src/pkg")));
+        assert_eq!(serve_args.host, "127.0.0.1");
+        assert_eq!(serve_args.port, 1111);
+
+        let (config, temp_dir) = resolve_serve_config(&serve_args)?;
+        assert_eq!(config.ssg, SSG::Html);
+        assert_eq!(config.pkg_path, PathBuf::from("src/pkg"));
+        let Some(temp_dir) = temp_dir else {
+            panic!("expected a temporary output directory");
+        };
+        assert_eq!(config.output_dir, temp_dir.path());
+
+        // the temporary directory doesn't outlive the server
+        drop(temp_dir);
+        assert!(!config.output_dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_subcommand() -> Result<()> {
+        let args = Args::parse_from(["snakedown", "watch", "src/pkg", "content/api"]);
+        let Command::Watch(watch_args) = args.into_command() else {
+            panic!("expected the watch subcommand");
+        };
+        assert_eq!(watch_args.debounce, 50);
+
+        let config = resolve_runtime_config(watch_args.build)?;
+        assert_eq!(config.pkg_path, PathBuf::from("src/pkg"));
+        assert_eq!(config.output_dir, PathBuf::from("content/api"));
         Ok(())
     }
 
@@ -157,9 +607,15 @@ fn test_args_all_flags() -> Result<()> {
             "path/to/exclude2",
             "--ssg",
             "markdown",
+            "--layout",
+            "single-page",
             "-v",
             "-v",
         ]);
+        // Verbosity should be INFO with -v -v (test indirectly)
+        let level = args.verbose.log_level();
+        assert_eq!(level, Some(Level::Info));
+        let args = args.build;
         assert_eq!(args.pkg_path, Some(PathBuf::from("src/pkg")));
         assert_eq!(args.output_dir, Some(PathBuf::from("dist")));
         assert!(args.skip_undoc);
@@ -171,17 +627,15 @@ fn test_args_all_flags() -> Result<()> {
                 PathBuf::from("path/to/exclude2")
             ])
         );
-        // Verbosity should be INFO with -v -v (test indirectly)
-        let level = args.verbose.log_level();
-        assert_eq!(level, Some(Level::Info));
         assert_eq!(args.ssg, Some(SSG::Markdown));
+        assert_eq!(args.layout, Some(Layout::SinglePage));
         Ok(())
     }
 
     #[test]
     fn test_args_exclude_short_flag() -> Result<()> {
         let args = Args::parse_from(["mybin", "-e", "excluded"]);
-        assert_eq!(args.exclude, Some(vec![PathBuf::from("excluded")]));
+        assert_eq!(args.build.exclude, Some(vec![PathBuf::from("excluded")]));
         Ok(())
     }
 }
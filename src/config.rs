@@ -5,10 +5,20 @@
     io::{Read, Write},
     path::{Path, PathBuf},
 };
+use toml_edit::DocumentMut;
 
+use crate::export::coverage::CoverageConfig;
 use crate::render::{
     SSG,
-    formats::{Renderer, md::MdRenderer, zola::ZolaRenderer},
+    diagrams::{ClassDiagramConfig, DiagramConfig},
+    formats::{
+        Renderer, html::HtmlRenderer, md::MdRenderer, rst::RstRenderer, template::TemplateRenderer,
+        zola::ZolaRenderer,
+    },
+    imports::ImportGraphConfig,
+    layout::Layout,
+    metadata::FrontMatterConfig,
+    source::SourceLinkConfig,
 };
 
 pub struct Config {
@@ -17,10 +27,86 @@ pub struct Config {
     pub skip_undoc: bool,
     pub skip_private: bool,
     pub exclude: Vec<PathBuf>,
+    pub ssg: SSG,
+    pub layout: Layout,
     pub renderer: Box<dyn Renderer>,
+    pub json_output: Option<PathBuf>,
+    pub llms_txt: bool,
+    pub search_index: bool,
+    /// the version `objects.inv` is written for
+    pub project_version: Option<String>,
+    /// whether modules that can't be processed fail the build
+    pub fail_on_error: bool,
+    pub source_links: Option<SourceLinkConfig>,
+    pub diagrams: DiagramConfig,
+    pub coverage: CoverageConfig,
 }
 
-#[derive(Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+const CONFIG_FILE_HEADER: &str =
+    "# snakedown configuration, settings given on the command line take precedence\n";
+
+/// What the settings of `ConfigBuilder` do, `to_file` writes these above them
+const SETTING_COMMENTS: [(&str, &str); 18] = [
+    ("output_dir", "where to write the rendered docs"),
+    (
+        "pkg_path",
+        "the root of the python package to document, the directory with its `__init__.py`",
+    ),
+    (
+        "skip_undoc",
+        "leave out functions, classes and modules without a docstring",
+    ),
+    (
+        "skip_private",
+        "leave out functions, classes and modules whose name starts with `_`",
+    ),
+    (
+        "exclude",
+        "files and directories of the package to leave out",
+    ),
+    ("ssg", "the output format: Markdown, Zola, Html or Rst"),
+    (
+        "layout",
+        "Tree for a page per module, SinglePage for one page or PerObject for a page per class and function",
+    ),
+    (
+        "json_output",
+        "also write the extracted documentation to this file as JSON",
+    ),
+    ("llms_txt", "also write `llms.txt` and `llms-full.txt`"),
+    (
+        "search_index",
+        "also write a search index and a search widget",
+    ),
+    (
+        "project_version",
+        "the version of the package, written to `objects.inv`",
+    ),
+    (
+        "fail_on_error",
+        "fail the build if any module can't be processed, e.g. because of a syntax error",
+    ),
+    (
+        "templates_dir",
+        "a directory of templates overriding the built in ones",
+    ),
+    ("front_matter", "extra front matter fields for zola pages"),
+    ("source_links", "link every object to its source code"),
+    (
+        "class_diagrams",
+        "draw the inheritance of the classes of every module",
+    ),
+    (
+        "import_graph",
+        "draw the imports between the modules on the page of the root package",
+    ),
+    (
+        "coverage",
+        "settings of `snakedown coverage`, e.g. the lowest documentation coverage that passes",
+    ),
+];
+
+#[derive(Default, Serialize, Deserialize, PartialEq, Debug)]
 pub struct ConfigBuilder {
     output_dir: Option<PathBuf>,
     pkg_path: Option<PathBuf>,
@@ -28,9 +114,35 @@ pub struct ConfigBuilder {
     skip_private: Option<bool>,
     exclude: Option<Vec<PathBuf>>,
     ssg: Option<SSG>,
+    layout: Option<Layout>,
+    json_output: Option<PathBuf>,
+    llms_txt: Option<bool>,
+    search_index: Option<bool>,
+    project_version: Option<String>,
+    fail_on_error: Option<bool>,
+    templates_dir: Option<PathBuf>,
+    front_matter: Option<FrontMatterConfig>,
+    source_links: Option<SourceLinkConfig>,
+    class_diagrams: Option<ClassDiagramConfig>,
+    import_graph: Option<ImportGraphConfig>,
+    coverage: Option<CoverageConfig>,
 }
 
 impl ConfigBuilder {
+    /// The settings `build` falls back to, spelled out so they can be written to a new
+    /// configuration file
+    pub fn defaults() -> Self {
+        ConfigBuilder {
+            output_dir: Some(PathBuf::from("_build")),
+            pkg_path: Some(PathBuf::from(".")),
+            skip_undoc: Some(true),
+            skip_private: Some(false),
+            ssg: Some(SSG::Markdown),
+            layout: Some(Layout::default()),
+            fail_on_error: Some(false),
+            ..Default::default()
+        }
+    }
     pub fn with_output_dir(mut self, output_dir: Option<PathBuf>) -> Self {
         if output_dir.is_some() {
             self.output_dir = output_dir;
@@ -79,11 +191,118 @@ pub fn with_ssg(mut self, ssg: Option<SSG>) -> Self {
         }
         self
     }
+    pub fn with_layout(mut self, layout: Option<Layout>) -> Self {
+        if layout.is_some() {
+            self.layout = layout;
+        }
+        self
+    }
+    pub fn with_json_output(mut self, json_output: Option<PathBuf>) -> Self {
+        if json_output.is_some() {
+            self.json_output = json_output;
+        }
+        self
+    }
+    pub fn with_llms_txt(mut self, llms_txt: Option<bool>) -> Self {
+        if llms_txt.is_some() {
+            self.llms_txt = llms_txt;
+        }
+        self
+    }
+    pub fn with_search_index(mut self, search_index: Option<bool>) -> Self {
+        if search_index.is_some() {
+            self.search_index = search_index;
+        }
+        self
+    }
+    pub fn with_project_version(mut self, project_version: Option<String>) -> Self {
+        if project_version.is_some() {
+            self.project_version = project_version;
+        }
+        self
+    }
+    pub fn with_fail_on_error(mut self, fail_on_error: Option<bool>) -> Self {
+        if fail_on_error.is_some() {
+            self.fail_on_error = fail_on_error;
+        }
+        self
+    }
+    pub fn with_templates_dir(mut self, templates_dir: Option<PathBuf>) -> Self {
+        if templates_dir.is_some() {
+            self.templates_dir = templates_dir;
+        }
+        self
+    }
+    pub fn with_front_matter(mut self, front_matter: Option<FrontMatterConfig>) -> Self {
+        if front_matter.is_some() {
+            self.front_matter = front_matter;
+        }
+        self
+    }
+    pub fn with_source_links(mut self, source_links: Option<SourceLinkConfig>) -> Self {
+        if source_links.is_some() {
+            self.source_links = source_links;
+        }
+        self
+    }
+    pub fn with_class_diagrams(mut self, class_diagrams: Option<ClassDiagramConfig>) -> Self {
+        if class_diagrams.is_some() {
+            self.class_diagrams = class_diagrams;
+        }
+        self
+    }
+    pub fn with_import_graph(mut self, import_graph: Option<ImportGraphConfig>) -> Self {
+        if import_graph.is_some() {
+            self.import_graph = import_graph;
+        }
+        self
+    }
+    pub fn with_coverage(mut self, coverage: Option<CoverageConfig>) -> Self {
+        if coverage.is_some() {
+            self.coverage = coverage;
+        }
+        self
+    }
     pub fn build(self) -> Result<Config> {
-        let renderer: Box<dyn Renderer> = match self.ssg {
-            Some(SSG::Markdown) | None => Box::new(MdRenderer::new()),
-            Some(SSG::Zola) => Box::new(ZolaRenderer::new()),
+        let ssg = self.ssg.unwrap_or(SSG::Markdown);
+        let mut renderer: Box<dyn Renderer> = match ssg {
+            SSG::Markdown => Box::new(MdRenderer::new()),
+            SSG::Zola => Box::new(
+                ZolaRenderer::new()
+                    .with_front_matter(self.front_matter.clone().unwrap_or_default()),
+            ),
+            SSG::Html => Box::new(HtmlRenderer::new()),
+            SSG::Rst => Box::new(RstRenderer::new()),
         };
+        if ssg != SSG::Zola && self.front_matter.as_ref().is_some_and(|f| !f.is_empty()) {
+            tracing::warn!("front matter fields are only supported for zola output, ignoring them");
+        }
+        let diagrams = DiagramConfig {
+            class_diagrams: self.class_diagrams,
+            import_graph: self.import_graph,
+        };
+        let mut layout = self.layout.unwrap_or_default();
+        if layout != Layout::Tree && matches!(ssg, SSG::Html | SSG::Rst) {
+            tracing::warn!(
+                "{ssg} output does not support the {layout} layout, using {}",
+                Layout::Tree
+            );
+            layout = Layout::Tree;
+        }
+        if let Some(templates_dir) = &self.templates_dir {
+            match (ssg, layout) {
+                (SSG::Html | SSG::Rst, _) => {
+                    tracing::warn!("templates are not supported for {ssg} output, ignoring them");
+                }
+                // the other layouts don't render the page of a module as a whole
+                (SSG::Markdown | SSG::Zola, Layout::Tree) => {
+                    renderer = Box::new(TemplateRenderer::new(renderer, Some(templates_dir))?);
+                }
+                (SSG::Markdown | SSG::Zola, _) => {
+                    tracing::warn!("templates are not used for the {layout} layout, ignoring them");
+                }
+            }
+        }
 
         Ok(Config {
             output_dir: self.output_dir.unwrap_or(PathBuf::from("_build")),
@@ -91,12 +310,32 @@ pub fn build(self) -> Result<Config> {
             skip_undoc: self.skip_undoc.unwrap_or(true),
             skip_private: self.skip_private.unwrap_or(false),
             exclude: self.exclude.unwrap_or_default(),
+            ssg,
+            layout,
             renderer,
+            json_output: self.json_output,
+            llms_txt: self.llms_txt.unwrap_or(false),
+            search_index: self.search_index.unwrap_or(false),
+            project_version: self.project_version,
+            fail_on_error: self.fail_on_error.unwrap_or(false),
+            source_links: self.source_links,
+            diagrams,
+            coverage: self.coverage.unwrap_or_default(),
         })
     }
 
+    /// Writes the settings that are set to `path`, each with a comment explaining it
     pub fn to_file(&self, path: &Path) -> Result<()> {
-        let serialized = toml::to_string(&self)?;
+        let mut document: DocumentMut = toml::to_string(&self)?.parse()?;
+        for (key, comment) in SETTING_COMMENTS {
+            let prefix = format!("\n# {comment}\n");
+            if let Some(table) = document.get_mut(key).and_then(|item| item.as_table_mut()) {
+                table.decor_mut().set_prefix(prefix);
+            } else if let Some(mut key) = document.as_table_mut().key_mut(key) {
+                key.leaf_decor_mut().set_prefix(prefix);
+            }
+        }
+        let serialized = format!("{CONFIG_FILE_HEADER}{document}");
         let mut file = File::create(path)?;
         file.write_all(serialized.as_bytes())?;
         Ok(())
@@ -123,6 +362,54 @@ pub fn merge(mut self, other: ConfigBuilder) -> Self {
             self.ssg = other.ssg
         }
 
+        if other.layout.is_some() {
+            self.layout = other.layout
+        }
+
+        if other.json_output.is_some() {
+            self.json_output = other.json_output
+        }
+
+        if other.llms_txt.is_some() {
+            self.llms_txt = other.llms_txt
+        }
+
+        if other.search_index.is_some() {
+            self.search_index = other.search_index
+        }
+
+        if other.project_version.is_some() {
+            self.project_version = other.project_version
+        }
+
+        if other.fail_on_error.is_some() {
+            self.fail_on_error = other.fail_on_error
+        }
+
+        if other.templates_dir.is_some() {
+            self.templates_dir = other.templates_dir
+        }
+
+        if other.front_matter.is_some() {
+            self.front_matter = other.front_matter
+        }
+
+        if other.source_links.is_some() {
+            self.source_links = other.source_links
+        }
+
+        if other.class_diagrams.is_some() {
+            self.class_diagrams = other.class_diagrams
+        }
+
+        if other.import_graph.is_some() {
+            self.import_graph = other.import_graph
+        }
+
+        if other.coverage.is_some() {
+            self.coverage = other.coverage
+        }
+
         if let Some(v) = other.exclude {
             self.exclude_paths(v)
         }
@@ -143,12 +430,36 @@ pub fn from_path(path: &Path) -> Result<ConfigBuilder> {
 mod test {
     use std::path::PathBuf;
 
-    use crate::render::SSG;
+    use crate::export::coverage::CoverageConfig;
+    use crate::render::{SSG, imports::ImportGraphConfig, layout::Layout};
 
     use super::ConfigBuilder;
     use assert_fs::TempDir;
     use color_eyre::Result;
 
+    #[test]
+    fn defaults_round_trip_through_a_file() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("snakedown.toml");
+        ConfigBuilder::defaults().to_file(&path)?;
+        assert_eq!(ConfigBuilder::from_path(&path)?, ConfigBuilder::defaults());
+        let written = std::fs::read_to_string(&path)?;
+        assert!(
+            written.contains("\n# where to write the rendered docs\noutput_dir = \"_build\"\n")
+        );
+
+        let from_defaults = ConfigBuilder::defaults().build()?;
+        let from_empty = ConfigBuilder::default().build()?;
+        assert_eq!(from_defaults.output_dir, from_empty.output_dir);
+        assert_eq!(from_defaults.pkg_path, from_empty.pkg_path);
+        assert_eq!(from_defaults.skip_undoc, from_empty.skip_undoc);
+        assert_eq!(from_defaults.skip_private, from_empty.skip_private);
+        assert_eq!(from_defaults.ssg, from_empty.ssg);
+        assert_eq!(from_defaults.layout, from_empty.layout);
+        assert_eq!(from_defaults.fail_on_error, from_empty.fail_on_error);
+        Ok(())
+    }
+
     #[test]
     fn empty_builder_creates_valid_config() -> Result<()> {
         let config = ConfigBuilder::default().build();
@@ -156,6 +467,137 @@ fn empty_builder_creates_valid_config() -> Result<()> {
         Ok(())
     }
 
+    #[test]
+    fn missing_templates_dir_uses_defaults() -> Result<()> {
+        let config = ConfigBuilder::default()
+            .with_templates_dir(Some(PathBuf::from("does/not/exist")))
+            .build();
+        assert!(config.is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn templates_are_not_loaded_for_a_single_page() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        std::fs::write(tmp_dir.join("module.jinja"), "{% if %}")?;
+        let with_layout = |layout| {
+            ConfigBuilder::default()
+                .with_templates_dir(Some(tmp_dir.to_path_buf()))
+                .with_layout(Some(layout))
+                .build()
+        };
+
+        assert!(with_layout(Layout::Tree).is_err());
+        assert!(with_layout(Layout::SinglePage).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn front_matter_from_file() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(
+            &path,
+            r#"
+ssg = "Zola"
+
+[front_matter.default]
+weight = 10
+
+[front_matter.package.extra]
+kind = "{kind}"
+"#,
+        )?;
+
+        let builder = ConfigBuilder::from_path(&path)?;
+        let front_matter = builder.front_matter.clone().unwrap_or_default();
+        assert_eq!(
+            front_matter.default.get("weight"),
+            Some(&toml::Value::Integer(10))
+        );
+        assert!(front_matter.module.is_empty());
+        assert!(builder.build().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn source_links_from_file() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(
+            &path,
+            r#"
+[source_links]
+url = "https://github.com/{repo}/blob/{rev}/{path}#L{start}-L{end}"
+repo = "savente93/snakedown"
+"#,
+        )?;
+
+        let config = ConfigBuilder::from_path(&path)?.build()?;
+        let source_links = config.source_links.unwrap_or_default();
+        assert_eq!(source_links.repo.as_deref(), Some("savente93/snakedown"));
+        assert_eq!(source_links.rev, None);
+        Ok(())
+    }
+
+    #[test]
+    fn class_diagrams_from_file() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(&path, "[class_diagrams]\nmethods = true\n")?;
+
+        let config = ConfigBuilder::from_path(&path)?.build()?;
+        assert!(config.diagrams.class_diagrams.is_some_and(|c| c.methods));
+        Ok(())
+    }
+
+    #[test]
+    fn import_graph_from_file() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(&path, "[import_graph]\n")?;
+
+        let config = ConfigBuilder::from_path(&path)?.build()?;
+        assert_eq!(
+            config.diagrams.import_graph,
+            Some(ImportGraphConfig { external: false })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_threshold_from_file_can_be_overridden() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(&path, "[coverage]\nfail_under = 80.0\n")?;
+
+        let config = ConfigBuilder::from_path(&path)?.build()?;
+        assert_eq!(config.coverage.fail_under, Some(80.0));
+
+        let config = ConfigBuilder::from_path(&path)?
+            .merge(ConfigBuilder::default().with_coverage(Some(CoverageConfig {
+                fail_under: Some(95.0),
+            })))
+            .build()?;
+        assert_eq!(config.coverage.fail_under, Some(95.0));
+        Ok(())
+    }
+
+    #[test]
+    fn single_page_falls_back_to_tree_for_html() -> Result<()> {
+        let config = ConfigBuilder::default()
+            .with_ssg(Some(SSG::Html))
+            .with_layout(Some(Layout::SinglePage))
+            .build()?;
+        assert_eq!(config.layout, Layout::Tree);
+
+        let config = ConfigBuilder::default()
+            .with_layout(Some(Layout::SinglePage))
+            .build()?;
+        assert_eq!(config.layout, Layout::SinglePage);
+        Ok(())
+    }
+
     #[test]
     fn config_round_trip() -> Result<()> {
         let mut builder = ConfigBuilder::default()
@@ -190,7 +632,14 @@ fn config_merge_other_takes_precident() -> Result<()> {
             .with_skip_private(Some(false))
             .with_pkg_path(Some(PathBuf::from("pkg")))
             .with_exclude(Some(vec![PathBuf::from("qwert")]))
-            .with_ssg(Some(SSG::Zola));
+            .with_ssg(Some(SSG::Zola))
+            .with_layout(Some(Layout::SinglePage))
+            .with_json_output(Some(PathBuf::from("api.json")))
+            .with_llms_txt(Some(true))
+            .with_search_index(Some(true))
+            .with_project_version(Some("1.2.0".to_string()))
+            .with_fail_on_error(Some(true))
+            .with_templates_dir(Some(PathBuf::from("templates")));
 
         let expected = ConfigBuilder::default()
             .with_pkg_path(Some(PathBuf::from("pkg")))
@@ -204,7 +653,14 @@ fn config_merge_other_takes_precident() -> Result<()> {
                 PathBuf::from("qwert"),
             ]))
             .with_skip_undoc(Some(true))
-            .with_ssg(Some(SSG::Zola));
+            .with_ssg(Some(SSG::Zola))
+            .with_layout(Some(Layout::SinglePage))
+            .with_json_output(Some(PathBuf::from("api.json")))
+            .with_llms_txt(Some(true))
+            .with_search_index(Some(true))
+            .with_project_version(Some("1.2.0".to_string()))
+            .with_fail_on_error(Some(true))
+            .with_templates_dir(Some(PathBuf::from("templates")));
 
         let computed = first.merge(second).merge(third);
         assert_eq!(expected, computed);
@@ -0,0 +1,482 @@
+//! What changed in the public API between two versions of a package: modules, functions,
+//! classes and methods that were added or removed, and changes to their signatures. Every
+//! change is classified as breaking if code using the old version may stop working.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    parsing::{
+        class::ClassDocumentation,
+        function::{FunctionDocumentation, Parameter, ParameterKind},
+        module::ModuleDocumentation,
+        package::PackageDocumentation,
+    },
+    render::{metadata::PageMetadata, qualify},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiChange {
+    /// the qualified name of the object that changed
+    pub object: String,
+    pub description: String,
+    pub breaking: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApiDiff {
+    pub changes: Vec<ApiChange>,
+}
+
+/// decorators that change how a function is called
+const CALLING_DECORATORS: [&str; 3] = ["property", "staticmethod", "classmethod"];
+
+impl ApiDiff {
+    pub fn new(old: &PackageDocumentation, new: &PackageDocumentation) -> Self {
+        let mut diff = ApiDiff::default();
+        let old_modules = public_modules(old);
+        let new_modules = public_modules(new);
+        for (name, old_module) in &old_modules {
+            match new_modules.get(name) {
+                Some(new_module) => diff.modules(name, old_module, new_module),
+                None => diff.push(name, "module removed", true),
+            }
+        }
+        for name in new_modules.keys().filter(|n| !old_modules.contains_key(*n)) {
+            diff.push(name, "module added", false);
+        }
+        diff
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    /// A section for the changelog, breaking changes first
+    pub fn to_markdown(&self) -> String {
+        if self.changes.is_empty() {
+            return "No changes to the public API.\n".to_string();
+        }
+        let mut out = String::from("## API changes\n");
+        for (title, breaking) in [("Breaking", true), ("Non-breaking", false)] {
+            let mut changes = self
+                .changes
+                .iter()
+                .filter(|change| change.breaking == breaking)
+                .peekable();
+            if changes.peek().is_none() {
+                continue;
+            }
+            out.push_str(&format!("\n### {title}\n\n"));
+            for change in changes {
+                out.push_str(&format!("- `{}`: {}\n", change.object, change.description));
+            }
+        }
+        out
+    }
+
+    fn push(&mut self, object: &str, description: impl Into<String>, breaking: bool) {
+        self.changes.push(ApiChange {
+            object: object.to_string(),
+            description: description.into(),
+            breaking,
+        });
+    }
+
+    fn modules(&mut self, name: &str, old: &ModuleDocumentation, new: &ModuleDocumentation) {
+        let old_functions = by_name(&old.functions, |f| &f.name);
+        let new_functions = by_name(&new.functions, |f| &f.name);
+        self.functions(name, &old_functions, &new_functions, "function");
+
+        let old_classes = by_name(&old.classes, |c| &c.name);
+        let new_classes = by_name(&new.classes, |c| &c.name);
+        for (class_name, old_class) in &old_classes {
+            let qualified_name = qualify(Some(name), class_name);
+            match new_classes.get(class_name) {
+                Some(new_class) => self.classes(&qualified_name, old_class, new_class),
+                None => self.push(&qualified_name, "class removed", true),
+            }
+        }
+        for class_name in new_classes.keys().filter(|n| !old_classes.contains_key(*n)) {
+            self.push(&qualify(Some(name), class_name), "class added", false);
+        }
+    }
+
+    fn classes(&mut self, name: &str, old: &ClassDocumentation, new: &ClassDocumentation) {
+        for base in old.bases.iter().filter(|b| !new.bases.contains(b)) {
+            self.push(name, format!("no longer inherits from `{base}`"), true);
+        }
+        for base in new.bases.iter().filter(|b| !old.bases.contains(b)) {
+            self.push(name, format!("now inherits from `{base}`"), false);
+        }
+        let old_methods = by_name(&old.methods, |m| &m.name);
+        let new_methods = by_name(&new.methods, |m| &m.name);
+        self.functions(name, &old_methods, &new_methods, "method");
+    }
+
+    fn functions(
+        &mut self,
+        prefix: &str,
+        old: &BTreeMap<&str, &FunctionDocumentation>,
+        new: &BTreeMap<&str, &FunctionDocumentation>,
+        kind: &str,
+    ) {
+        for (name, old_function) in old {
+            let qualified_name = qualify(Some(prefix), name);
+            match new.get(name) {
+                Some(new_function) => self.signature(&qualified_name, old_function, new_function),
+                None => self.push(&qualified_name, format!("{kind} removed"), true),
+            }
+        }
+        for name in new.keys().filter(|n| !old.contains_key(*n)) {
+            self.push(&qualify(Some(prefix), name), format!("{kind} added"), false);
+        }
+    }
+
+    fn signature(&mut self, name: &str, old: &FunctionDocumentation, new: &FunctionDocumentation) {
+        if old.is_async != new.is_async {
+            let description = if new.is_async {
+                "is now async"
+            } else {
+                "is no longer async"
+            };
+            self.push(name, description, true);
+        }
+        for decorator in CALLING_DECORATORS {
+            let decorated = |f: &FunctionDocumentation| f.decorators.iter().any(|d| d == decorator);
+            match (decorated(old), decorated(new)) {
+                (true, false) => self.push(name, format!("no longer a {decorator}"), true),
+                (false, true) => self.push(name, format!("now a {decorator}"), true),
+                _ => (),
+            }
+        }
+
+        for old_param in &old.args {
+            let Some(new_param) = new.args.iter().find(|p| p.name == old_param.name) else {
+                self.push(
+                    name,
+                    format!("parameter `{}` removed", display_param(old_param)),
+                    true,
+                );
+                continue;
+            };
+            self.parameter(name, old_param, new_param);
+        }
+        for new_param in new
+            .args
+            .iter()
+            .filter(|p| !old.args.iter().any(|o| o.name == p.name))
+        {
+            let variadic = matches!(
+                new_param.kind,
+                ParameterKind::VarPositional | ParameterKind::VarKeyword
+            );
+            match &new_param.default {
+                _ if variadic => self.push(
+                    name,
+                    format!("parameter `{}` added", display_param(new_param)),
+                    false,
+                ),
+                Some(default) => self.push(
+                    name,
+                    format!(
+                        "parameter `{}` added with default `{default}`",
+                        new_param.name
+                    ),
+                    false,
+                ),
+                None => self.push(
+                    name,
+                    format!("required parameter `{}` added", new_param.name),
+                    true,
+                ),
+            }
+        }
+
+        // passing arguments by position breaks if the positions change
+        let positional = |f: &FunctionDocumentation| -> Vec<String> {
+            f.args
+                .iter()
+                .filter(|p| is_positional(p.kind))
+                .map(|p| p.name.clone())
+                .collect()
+        };
+        let (old_order, new_order) = (positional(old), positional(new));
+        let kept_old: Vec<&String> = old_order.iter().filter(|n| new_order.contains(n)).collect();
+        let kept_new: Vec<&String> = new_order.iter().filter(|n| old_order.contains(n)).collect();
+        if kept_old != kept_new {
+            self.push(
+                name,
+                format!(
+                    "positional parameters reordered from `{}` to `{}`",
+                    join(&kept_old),
+                    join(&kept_new)
+                ),
+                true,
+            );
+        } else {
+            // a parameter inserted in front of a kept one shifts it, calls pass it by position
+            for (old_position, param) in old_order.iter().enumerate() {
+                let new_position = new_order.iter().position(|n| n == param);
+                if let Some(new_position) = new_position.filter(|&p| p != old_position) {
+                    self.push(
+                        name,
+                        format!(
+                            "positional parameter `{param}` moved from position {} to {}",
+                            old_position + 1,
+                            new_position + 1
+                        ),
+                        true,
+                    );
+                }
+            }
+        }
+
+        if old.return_type != new.return_type {
+            self.push(
+                name,
+                format!(
+                    "return annotation changed from {} to {}",
+                    display_annotation(&old.return_type),
+                    display_annotation(&new.return_type)
+                ),
+                false,
+            );
+        }
+    }
+
+    fn parameter(&mut self, name: &str, old: &Parameter, new: &Parameter) {
+        let param = &new.name;
+        if old.kind != new.kind {
+            // loosening how a parameter can be passed keeps every existing call working
+            let breaking = !(new.kind == ParameterKind::PositionalOrKeyword
+                && matches!(
+                    old.kind,
+                    ParameterKind::PositionalOnly | ParameterKind::KeywordOnly
+                ));
+            self.push(
+                name,
+                format!("parameter `{param}` is now {}", kind_description(new.kind)),
+                breaking,
+            );
+        }
+        match (&old.default, &new.default) {
+            (Some(_), None) => self.push(
+                name,
+                format!("default of `{param}` removed, it is now required"),
+                true,
+            ),
+            (None, Some(default)) => self.push(
+                name,
+                format!("default of `{param}` added: `{default}`"),
+                false,
+            ),
+            // callers relying on the default get something else
+            (Some(old_default), Some(new_default)) if old_default != new_default => self.push(
+                name,
+                format!("default of `{param}` changed from `{old_default}` to `{new_default}`"),
+                true,
+            ),
+            _ => (),
+        }
+        if old.annotation != new.annotation {
+            self.push(
+                name,
+                format!(
+                    "annotation of `{param}` changed from {} to {}",
+                    display_annotation(&old.annotation),
+                    display_annotation(&new.annotation)
+                ),
+                false,
+            );
+        }
+    }
+}
+
+/// names starting with `_` are private, except for dunder names like `__init__`
+fn is_public(name: &str) -> bool {
+    !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"))
+}
+
+fn public_modules(package: &PackageDocumentation) -> BTreeMap<String, &ModuleDocumentation> {
+    package
+        .modules
+        .iter()
+        .filter_map(|module| Some((PageMetadata::from(module).title?, module)))
+        .filter(|(name, _)| name.split('.').all(|part| !part.starts_with('_')))
+        .collect()
+}
+
+fn by_name<'a, T>(items: &'a [T], name: impl Fn(&'a T) -> &'a String) -> BTreeMap<&'a str, &'a T> {
+    items
+        .iter()
+        .map(|item| (name(item).as_str(), item))
+        .filter(|(name, _)| is_public(name))
+        .collect()
+}
+
+fn is_positional(kind: ParameterKind) -> bool {
+    matches!(
+        kind,
+        ParameterKind::PositionalOnly | ParameterKind::PositionalOrKeyword
+    )
+}
+
+fn kind_description(kind: ParameterKind) -> &'static str {
+    match kind {
+        ParameterKind::PositionalOnly => "positional only",
+        ParameterKind::PositionalOrKeyword => "positional or keyword",
+        ParameterKind::VarPositional => "variadic positional",
+        ParameterKind::KeywordOnly => "keyword only",
+        ParameterKind::VarKeyword => "variadic keyword",
+    }
+}
+
+fn display_param(param: &Parameter) -> String {
+    match param.kind {
+        ParameterKind::VarPositional => format!("*{}", param.name),
+        ParameterKind::VarKeyword => format!("**{}", param.name),
+        _ => param.name.clone(),
+    }
+}
+
+fn display_annotation(annotation: &Option<String>) -> String {
+    match annotation {
+        Some(annotation) => format!("`{annotation}`"),
+        None => "none".to_string(),
+    }
+}
+
+fn join(names: &[&String]) -> String {
+    names
+        .iter()
+        .map(|name| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::ApiDiff;
+    use crate::parsing::{
+        module::extract_module_documentation, package::PackageDocumentation,
+        utils::parse_python_str,
+    };
+
+    fn package(source: &str) -> Result<PackageDocumentation> {
+        let program = parse_python_str(source)?;
+        let mut module = extract_module_documentation(
+            &program,
+            Some("api".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        module.path = Some("pkg/api.py".into());
+        Ok(PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![module],
+        })
+    }
+
+    #[test]
+    fn test_signature_changes() -> Result<()> {
+        let old = package(
+            r#"
+def send(url, retries=3, *, timeout: int = 10, verbose=False):
+    pass
+
+def gone():
+    pass
+
+def _private():
+    pass
+
+class Client(Base):
+    def __init__(self, host, port=80, /):
+        pass
+
+    def fetch(self, a, b):
+        pass
+"#,
+        )?;
+        let new = package(
+            r#"
+def send(url, retries=5, *, timeout: float = 10, proxy, **kwargs):
+    pass
+
+async def added():
+    pass
+
+def _private(x):
+    pass
+
+class Client(Base, Mixin):
+    def __init__(self, host, port=80):
+        pass
+
+    def fetch(self, b, a):
+        pass
+"#,
+        )?;
+        let diff = ApiDiff::new(&old, &new);
+        assert!(diff.is_breaking());
+        assert_eq!(
+            diff.to_markdown(),
+            r#"## API changes
+
+### Breaking
+
+- `pkg.api.gone`: function removed
+- `pkg.api.send`: default of `retries` changed from `3` to `5`
+- `pkg.api.send`: parameter `verbose` removed
+- `pkg.api.send`: required parameter `proxy` added
+- `pkg.api.Client.fetch`: positional parameters reordered from `self, a, b` to `self, b, a`
+
+### Non-breaking
+
+- `pkg.api.send`: annotation of `timeout` changed from `int` to `float`
+- `pkg.api.send`: parameter `**kwargs` added
+- `pkg.api.added`: function added
+- `pkg.api.Client`: now inherits from `Mixin`
+- `pkg.api.Client.__init__`: parameter `self` is now positional or keyword
+- `pkg.api.Client.__init__`: parameter `host` is now positional or keyword
+- `pkg.api.Client.__init__`: parameter `port` is now positional or keyword
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_inserted_positional_parameter() -> Result<()> {
+        let old = package("def f(a, b=2):\n    pass\n")?;
+        let new = package("def f(a, x=1, b=2):\n    pass\n")?;
+        let diff = ApiDiff::new(&old, &new);
+        assert!(diff.is_breaking());
+        assert_eq!(
+            diff.to_markdown(),
+            r#"## API changes
+
+### Breaking
+
+- `pkg.api.f`: positional parameter `b` moved from position 2 to 3
+
+### Non-breaking
+
+- `pkg.api.f`: parameter `x` added with default `1`
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_changes() -> Result<()> {
+        let old = package("def send(url):\n    pass\n")?;
+        let diff = ApiDiff::new(&old, &old);
+        assert!(!diff.is_breaking());
+        assert_eq!(diff.to_markdown(), "No changes to the public API.\n");
+        Ok(())
+    }
+}
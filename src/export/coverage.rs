@@ -0,0 +1,431 @@
+//! How many of the public modules, classes, functions and methods of a package have a
+//! docstring. Everything is read from the source, nothing is imported. Objects whose name
+//! starts with `_` are private and don't count, dunder methods like `__init__` included.
+
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    export::escape_xml,
+    parsing::{
+        class::ClassDocumentation, function::FunctionDocumentation, module::ModuleDocumentation,
+        package::PackageDocumentation,
+    },
+    render::{metadata::PageMetadata, qualify},
+};
+
+/// The `[coverage]` section of `snakedown.toml`
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoverageConfig {
+    /// the lowest total coverage in percent that still passes
+    pub fail_under: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum CoverageFormat {
+    #[default]
+    Text,
+    Json,
+    /// one test case per object, for CI systems that show JUnit test results
+    Junit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageKind {
+    Module,
+    Class,
+    Function,
+    Method,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CoverageItem {
+    pub qualified_name: String,
+    pub kind: CoverageKind,
+    pub documented: bool,
+    /// the line the object is defined on
+    pub line: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ModuleCoverage {
+    pub name: String,
+    /// relative to the directory containing the root package
+    pub path: Option<PathBuf>,
+    pub items: Vec<CoverageItem>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Count {
+    pub documented: usize,
+    pub total: usize,
+}
+
+impl Count {
+    fn of<'a>(items: impl IntoIterator<Item = &'a CoverageItem>) -> Self {
+        items
+            .into_iter()
+            .fold(Count::default(), |count, item| Count {
+                documented: count.documented + usize::from(item.documented),
+                total: count.total + 1,
+            })
+    }
+
+    /// an empty package is fully documented
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.documented as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub modules: Vec<ModuleCoverage>,
+}
+
+impl CoverageReport {
+    /// `package` has to be extracted with its undocumented objects, otherwise
+    /// everything is documented
+    pub fn new(package: &PackageDocumentation) -> Self {
+        Self {
+            modules: package.modules.iter().filter_map(module_coverage).collect(),
+        }
+    }
+
+    pub fn total(&self) -> Count {
+        Count::of(self.modules.iter().flat_map(|m| &m.items))
+    }
+
+    pub fn count(&self, kind: CoverageKind) -> Count {
+        Count::of(
+            self.modules
+                .iter()
+                .flat_map(|m| &m.items)
+                .filter(|item| item.kind == kind),
+        )
+    }
+
+    /// `Some` with the total coverage if it is below `fail_under`
+    pub fn below(&self, config: &CoverageConfig) -> Option<f64> {
+        let percent = self.total().percent();
+        config
+            .fail_under
+            .is_some_and(|fail_under| percent < fail_under)
+            .then_some(percent)
+    }
+
+    pub fn render(&self, format: CoverageFormat) -> Result<String> {
+        Ok(match format {
+            CoverageFormat::Text => self.to_text(),
+            CoverageFormat::Json => self.to_json()?,
+            CoverageFormat::Junit => self.to_junit(),
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let width = self
+            .modules
+            .iter()
+            .map(|m| m.name.len())
+            .chain(["functions".len()])
+            .max()
+            .unwrap_or_default();
+        let line = |name: &str, count: Count| {
+            format!(
+                "{name:<width$}  {:>9}  {:>6.1}%\n",
+                format!("{}/{}", count.documented, count.total),
+                count.percent()
+            )
+        };
+
+        let mut out = String::new();
+        for module in &self.modules {
+            out.push_str(&line(&module.name, Count::of(&module.items)));
+        }
+        out.push('\n');
+        for (name, kind) in [
+            ("modules", CoverageKind::Module),
+            ("classes", CoverageKind::Class),
+            ("functions", CoverageKind::Function),
+            ("methods", CoverageKind::Method),
+        ] {
+            out.push_str(&line(name, self.count(kind)));
+        }
+        out.push_str(&line("total", self.total()));
+
+        let mut missing = self.missing().peekable();
+        if missing.peek().is_some() {
+            out.push_str("\nmissing docstrings:\n");
+        }
+        for (module, item) in missing {
+            out.push_str(&format!(
+                "  {} {}\n",
+                location(module, item),
+                item.qualified_name
+            ));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let count = |count: Count| {
+            json!({
+                "documented": count.documented,
+                "total": count.total,
+                "percent": count.percent(),
+            })
+        };
+        let modules: Vec<_> = self
+            .modules
+            .iter()
+            .map(|module| {
+                json!({
+                    "name": module.name,
+                    "path": module.path,
+                    "coverage": count(Count::of(&module.items)),
+                    "items": module.items,
+                })
+            })
+            .collect();
+        let report = json!({
+            "modules": modules,
+            "kinds": {
+                "module": count(self.count(CoverageKind::Module)),
+                "class": count(self.count(CoverageKind::Class)),
+                "function": count(self.count(CoverageKind::Function)),
+                "method": count(self.count(CoverageKind::Method)),
+            },
+            "total": count(self.total()),
+        });
+        let mut out = serde_json::to_string_pretty(&report)?;
+        out.push('\n');
+        Ok(out)
+    }
+
+    /// A test suite per module, with a failing test case for every missing docstring
+    pub fn to_junit(&self) -> String {
+        let total = self.total();
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuites name=\"docstring coverage\" tests=\"{}\" failures=\"{}\">\n",
+            total.total,
+            total.total - total.documented
+        ));
+        for module in &self.modules {
+            let count = Count::of(&module.items);
+            out.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+                escape_xml(&module.name),
+                count.total,
+                count.total - count.documented
+            ));
+            for item in &module.items {
+                let test_case = format!(
+                    "    <testcase classname=\"{}\" name=\"{}\"",
+                    escape_xml(&module.name),
+                    escape_xml(&item.qualified_name)
+                );
+                if item.documented {
+                    out.push_str(&format!("{test_case}/>\n"));
+                } else {
+                    out.push_str(&format!(
+                        "{test_case}>\n      <failure message=\"missing docstring\">{}</failure>\n    </testcase>\n",
+                        escape_xml(&location(module, item))
+                    ));
+                }
+            }
+            out.push_str("  </testsuite>\n");
+        }
+        out.push_str("</testsuites>\n");
+        out
+    }
+
+    fn missing(&self) -> impl Iterator<Item = (&ModuleCoverage, &CoverageItem)> {
+        self.modules.iter().flat_map(|module| {
+            module
+                .items
+                .iter()
+                .filter(|item| !item.documented)
+                .map(move |item| (module, item))
+        })
+    }
+}
+
+/// `path:line`, or just the name of the module if its path isn't known
+fn location(module: &ModuleCoverage, item: &CoverageItem) -> String {
+    match &module.path {
+        Some(path) => format!("{}:{}", path.display(), item.line.unwrap_or(1)),
+        None => module.name.clone(),
+    }
+}
+
+fn is_public(name: &str) -> bool {
+    !name.starts_with('_')
+}
+
+fn module_coverage(module: &ModuleDocumentation) -> Option<ModuleCoverage> {
+    let name = PageMetadata::from(module).title?;
+    let mut items = vec![CoverageItem {
+        qualified_name: name.clone(),
+        kind: CoverageKind::Module,
+        documented: module.docstring.is_some(),
+        line: Some(1),
+    }];
+    for function in module.functions.iter().filter(|f| is_public(&f.name)) {
+        items.push(function_item(&name, function, CoverageKind::Function));
+    }
+    for class in module.classes.iter().filter(|c| is_public(&c.name)) {
+        items.extend(class_items(&name, class));
+    }
+    Some(ModuleCoverage {
+        name,
+        path: module.path.clone(),
+        items,
+    })
+}
+
+fn class_items(module_name: &str, class: &ClassDocumentation) -> Vec<CoverageItem> {
+    let qualified_name = qualify(Some(module_name), &class.name);
+    let mut items = vec![CoverageItem {
+        qualified_name: qualified_name.clone(),
+        kind: CoverageKind::Class,
+        documented: class.docstring.is_some(),
+        line: class.lines.map(|lines| lines.start),
+    }];
+    for method in class.methods.iter().filter(|m| is_public(&m.name)) {
+        items.push(function_item(&qualified_name, method, CoverageKind::Method));
+    }
+    items
+}
+
+fn function_item(
+    prefix: &str,
+    function: &FunctionDocumentation,
+    kind: CoverageKind,
+) -> CoverageItem {
+    CoverageItem {
+        qualified_name: qualify(Some(prefix), &function.name),
+        kind,
+        documented: function.docstring.is_some(),
+        line: function.lines.map(|lines| lines.start),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{CoverageConfig, CoverageKind, CoverageReport};
+    use crate::parsing::{
+        module::extract_located_module_documentation, package::PackageDocumentation,
+        utils::parse_python_str,
+    };
+
+    fn test_report() -> Result<CoverageReport> {
+        let source = r#""""The api."""
+
+def documented():
+    """Does things."""
+
+def undocumented():
+    pass
+
+def _private():
+    pass
+
+class Client:
+    """A client."""
+
+    def __init__(self):
+        pass
+
+    def send(self):
+        pass
+"#;
+        let program = parse_python_str(source)?;
+        let mut module = extract_located_module_documentation(
+            &program,
+            source,
+            Some("api".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        module.path = Some("pkg/api.py".into());
+        Ok(CoverageReport::new(&PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![module],
+        }))
+    }
+
+    #[test]
+    fn test_counts_public_objects() -> Result<()> {
+        let report = test_report()?;
+        assert_eq!(report.count(CoverageKind::Module).documented, 1);
+        assert_eq!(report.count(CoverageKind::Function).total, 2);
+        assert_eq!(report.count(CoverageKind::Method).total, 1);
+        assert_eq!(report.total().documented, 3);
+        assert_eq!(report.total().total, 5);
+
+        assert_eq!(
+            report.below(&CoverageConfig {
+                fail_under: Some(80.0)
+            }),
+            Some(60.0)
+        );
+        assert_eq!(
+            report.below(&CoverageConfig {
+                fail_under: Some(60.0)
+            }),
+            None
+        );
+        assert_eq!(report.below(&CoverageConfig::default()), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_report() -> Result<()> {
+        assert_eq!(
+            test_report()?.to_text(),
+            r#"pkg.api          3/5    60.0%
+
+modules          1/1   100.0%
+classes          1/1   100.0%
+functions        1/2    50.0%
+methods          0/1     0.0%
+total            3/5    60.0%
+
+missing docstrings:
+  pkg/api.py:6 pkg.api.undocumented
+  pkg/api.py:18 pkg.api.Client.send
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_junit_report() -> Result<()> {
+        let junit = test_report()?.to_junit();
+        assert!(
+            junit.contains("<testsuites name=\"docstring coverage\" tests=\"5\" failures=\"2\">\n")
+        );
+        assert!(
+            junit.contains("    <testcase classname=\"pkg.api\" name=\"pkg.api.documented\"/>\n")
+        );
+        assert!(
+            junit
+                .contains("      <failure message=\"missing docstring\">pkg/api.py:18</failure>\n")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::Write;
+
+use color_eyre::Result;
+use flate2::{Compression, write::ZlibEncoder};
+
+use crate::{
+    export::search::{ObjectKind, search_entries},
+    parsing::package::PackageDocumentation,
+    render::{formats::Renderer, layout::Layout},
+};
+
+pub const OBJECTS_INV_NAME: &str = "objects.inv";
+
+/// Renders a sphinx inventory (version 2) of every object in the package so other
+/// projects can link to it with intersphinx. Locations point to where `renderer` serves
+/// the pages it writes in `layout`, the same urls as in the search index, see `export::search`.
+pub fn render_objects_inv<R: Renderer>(
+    package: &PackageDocumentation,
+    version: &str,
+    layout: Layout,
+    renderer: &R,
+) -> Result<Vec<u8>> {
+    let mut out = format!(
+        "# Sphinx inventory version 2\n# Project: {}\n# Version: {version}\n# The remainder of this file is compressed using zlib.\n",
+        package.name
+    )
+    .into_bytes();
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    for entry in search_entries(package, layout, renderer) {
+        let (role, priority) = match entry.kind {
+            ObjectKind::Module => ("module", 0),
+            ObjectKind::Function => ("function", 1),
+            ObjectKind::Class => ("class", 1),
+            ObjectKind::Method => ("method", 1),
+            ObjectKind::Attribute => ("attribute", 1),
+            ObjectKind::Data => ("data", 1),
+        };
+        // sphinx abbreviates a location ending in the name of the object with `$`
+        let location = match entry.url.strip_suffix(&entry.qualified_name) {
+            Some(stripped) => format!("{stripped}$"),
+            None => entry.url,
+        };
+        writeln!(
+            encoder,
+            "{} py:{role} {priority} {location} -",
+            entry.qualified_name
+        )?;
+    }
+    out.extend(encoder.finish()?);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::BufReader;
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::render_objects_inv;
+    use crate::{
+        parsing::{
+            module::extract_module_documentation, package::PackageDocumentation,
+            sphinx::inv_file::parse_objects_inv, utils::parse_python_str,
+        },
+        render::{
+            formats::{html::HtmlRenderer, zola::ZolaRenderer},
+            layout::Layout,
+        },
+    };
+
+    #[test]
+    fn test_objects_inv_round_trip() -> Result<()> {
+        let program = parse_python_str(
+            r#"
+def connect(): pass
+
+class Client:
+    def get(self): pass
+
+TIMEOUT = 3
+"#,
+        )?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some("net".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        mod_doc.path = Some("pkg/net.py".into());
+        let package = PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![mod_doc],
+        };
+
+        let inv = render_objects_inv(&package, "1.0", Layout::Tree, &HtmlRenderer::new())?;
+        let refs = parse_objects_inv(BufReader::new(&inv[..]))?;
+
+        assert_eq!(
+            refs.iter()
+                .map(|r| (r.name.as_str(), r.location.display().to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("pkg.net", "net.html".to_string()),
+                ("pkg.net.connect", "net.html#$".to_string()),
+                ("pkg.net.Client", "net.html#$".to_string()),
+                ("pkg.net.Client.get", "net.html#$".to_string()),
+                ("pkg.net.TIMEOUT", "net.html#$".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_objects_inv_links_to_zola_urls() -> Result<()> {
+        let program = parse_python_str("def connect(): pass\n")?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some("net".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        mod_doc.path = Some("pkg/net.py".into());
+        let package = PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![mod_doc],
+        };
+
+        let inv = render_objects_inv(&package, "1.2.0", Layout::Tree, &ZolaRenderer::new())?;
+        assert!(
+            inv.starts_with(b"# Sphinx inventory version 2\n# Project: pkg\n# Version: 1.2.0\n")
+        );
+        let refs = parse_objects_inv(BufReader::new(&inv[..]))?;
+
+        assert_eq!(
+            refs.iter()
+                .map(|r| (r.name.as_str(), r.location.display().to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("pkg.net", "net/".to_string()),
+                ("pkg.net.connect", "net/#$".to_string()),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use std::{fs::File, io::Read, path::Path};
+
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::parsing::package::PackageDocumentation;
+
+/// Version of the JSON format. This is bumped whenever a change is made to the
+/// structure of the export that could break consumers.
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+/// The JSON Schema (draft 2020-12) describing the export, also published as
+/// `schema/snakedown-ir.v1.schema.json` in the repository.
+pub const JSON_SCHEMA: &str = include_str!("../../schema/snakedown-ir.v1.schema.json");
+
+pub const JSON_SCHEMA_ID: &str =
+    "https://raw.githubusercontent.com/savente93/snakedown/main/schema/snakedown-ir.v1.schema.json";
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonExport {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub format_version: u32,
+    pub generator: String,
+    pub package: PackageDocumentation,
+}
+
+impl JsonExport {
+    pub fn new(package: PackageDocumentation) -> Self {
+        Self {
+            schema: JSON_SCHEMA_ID.to_string(),
+            format_version: JSON_FORMAT_VERSION,
+            generator: format!("snakedown {}", env!("CARGO_PKG_VERSION")),
+            package,
+        }
+    }
+}
+
+pub fn render_json(package: PackageDocumentation) -> Result<String> {
+    let mut out = serde_json::to_string_pretty(&JsonExport::new(package))?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Reads a JSON export back in.
+/// # Errors
+/// errors if the file cannot be read, is not valid JSON or was written
+/// with an unsupported format version
+pub fn read_json_export(path: &Path) -> Result<JsonExport> {
+    let mut contents = String::new();
+    let mut file = File::open(path)?;
+    file.read_to_string(&mut contents)?;
+    let export: JsonExport = serde_json::from_str(&contents)?;
+    if export.format_version != JSON_FORMAT_VERSION {
+        return Err(eyre!(
+            "{} was written with json format version {} but only version {} is supported",
+            path.display(),
+            export.format_version,
+            JSON_FORMAT_VERSION
+        ));
+    }
+    Ok(export)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{export_json, extract_package_documentation};
+
+    #[test]
+    fn schema_is_valid_json() -> Result<()> {
+        let schema: serde_json::Value = serde_json::from_str(JSON_SCHEMA)?;
+        assert_eq!(schema["$id"], JSON_SCHEMA_ID);
+        Ok(())
+    }
+
+    #[test]
+    fn json_export_round_trip() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let out_file = temp_dir.path().join("api.json");
+
+        let (package, errored) = extract_package_documentation(
+            &PathBuf::from("tests/test_pkg"),
+            true,
+            true,
+            vec![
+                PathBuf::from("test_pkg/excluded_file.py"),
+                PathBuf::from("test_pkg/excluded_module"),
+            ],
+            None,
+        )?;
+        assert!(errored.is_empty());
+        export_json(&package, &out_file)?;
+
+        let export = read_json_export(&out_file)?;
+        assert_eq!(export.format_version, JSON_FORMAT_VERSION);
+        assert_eq!(export.package.name, "test_pkg");
+
+        let module_names = export
+            .package
+            .modules
+            .iter()
+            .map(|m| match (&m.prefix, &m.name) {
+                (Some(prefix), Some(name)) => format!("{prefix}.{name}"),
+                (_, name) => name.clone().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            module_names,
+            vec![
+                "test_pkg",
+                "test_pkg.bar",
+                "test_pkg.foo",
+                "test_pkg.sub1",
+                "test_pkg.sub1.mid",
+                "test_pkg.sub1.sub2",
+                "test_pkg.sub1.sub2.one",
+                "test_pkg.sub1.sub2.two",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn json_export_rejects_unknown_version() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let path = temp_dir.path().join("api.json");
+        let mut export = serde_json::to_value(JsonExport::new(PackageDocumentation::default()))?;
+        export["format_version"] = serde_json::Value::from(JSON_FORMAT_VERSION + 1);
+        std::fs::write(&path, serde_json::to_string(&export)?)?;
+
+        assert!(read_json_export(&path).is_err());
+        Ok(())
+    }
+}
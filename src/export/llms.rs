@@ -0,0 +1,233 @@
+//! Plain text digests of the package for code assistants, see <https://llmstxt.org>.
+//! `llms.txt` links to the page of every module, `llms-full.txt` lists every signature
+//! together with the first sentence of its docstring.
+
+use crate::{
+    parsing::{
+        attribute::AttributeDocumentation, function::FunctionDocumentation,
+        package::PackageDocumentation,
+    },
+    render::{
+        anchors::object_link,
+        format_declaration, format_signature,
+        formats::Renderer,
+        layout::{Layout, module_page},
+        link_target,
+        metadata::{PageMetadata, summarize},
+    },
+};
+
+pub const LLMS_TXT_NAME: &str = "llms.txt";
+pub const LLMS_FULL_TXT_NAME: &str = "llms-full.txt";
+
+/// The index, a link to the page of every module as written by `renderer` in `layout`
+pub fn render_llms_txt<R: Renderer>(
+    package: &PackageDocumentation,
+    layout: Layout,
+    renderer: &R,
+) -> String {
+    let mut out = render_title(package);
+    out.push_str("\n## Modules\n\n");
+    for module in &package.modules {
+        let Some(title) = PageMetadata::from(module).title else {
+            continue;
+        };
+        let Some(page) = module_page(module, &package.name, layout, renderer) else {
+            tracing::warn!("leaving {title} out of {LLMS_TXT_NAME}, it has no path");
+            continue;
+        };
+        let page = link_target(&page);
+        // on a single page only the package itself is at the top
+        let page = if layout == Layout::SinglePage && title != package.name {
+            object_link(&page, &title)
+        } else {
+            page
+        };
+        out.push_str(&format!("- [{title}]({page})"));
+        if let Some(summary) = summarize(module.docstring.as_deref()) {
+            out.push_str(&format!(": {summary}"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Every module with the signatures of its functions, classes and attributes
+pub fn render_llms_full_txt(package: &PackageDocumentation) -> String {
+    let mut out = render_title(package);
+    for module in &package.modules {
+        let Some(title) = PageMetadata::from(module).title else {
+            continue;
+        };
+        out.push_str(&format!("\n## {title}\n"));
+        if let Some(summary) = summarize(module.docstring.as_deref()) {
+            out.push_str(&format!("\n{summary}\n"));
+        }
+
+        let mut items = vec![];
+        for fn_docs in &module.functions {
+            items.push(function_item(fn_docs, 0));
+        }
+        for class_docs in &module.classes {
+            items.push(item(
+                &format!("class {}", class_docs.name),
+                class_docs.docstring.as_deref(),
+                0,
+            ));
+            for method in &class_docs.methods {
+                items.push(function_item(method, 1));
+            }
+            for attr_docs in &class_docs.attributes {
+                items.push(attribute_item(attr_docs, 1));
+            }
+        }
+        for attr_docs in &module.attributes {
+            items.push(attribute_item(attr_docs, 0));
+        }
+        if !items.is_empty() {
+            out.push('\n');
+            out.push_str(&items.concat());
+        }
+    }
+    out
+}
+
+/// the name of the package and the first sentence of its docstring as a blockquote
+fn render_title(package: &PackageDocumentation) -> String {
+    let mut out = format!("# {}\n", package.name);
+    let root_summary = package
+        .modules
+        .iter()
+        .find(|m| PageMetadata::from(*m).title.as_deref() == Some(&package.name))
+        .and_then(|m| summarize(m.docstring.as_deref()));
+    if let Some(summary) = root_summary {
+        out.push_str(&format!("\n> {summary}\n"));
+    }
+    out
+}
+
+fn item(code: &str, docstring: Option<&str>, depth: usize) -> String {
+    let mut out = format!("{}- `{code}`", "  ".repeat(depth));
+    if let Some(summary) = summarize(docstring) {
+        out.push_str(&format!(": {summary}"));
+    }
+    out.push('\n');
+    out
+}
+
+fn function_item(fn_docs: &FunctionDocumentation, depth: usize) -> String {
+    let keyword = if fn_docs.is_async { "async def" } else { "def" };
+    item(
+        &format!("{keyword} {}", format_signature(fn_docs)),
+        fn_docs.docstring.as_deref(),
+        depth,
+    )
+}
+
+fn attribute_item(attr_docs: &AttributeDocumentation, depth: usize) -> String {
+    item(
+        &format_declaration(attr_docs),
+        attr_docs.docstring.as_deref(),
+        depth,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{render_llms_full_txt, render_llms_txt};
+    use crate::{
+        parsing::{
+            module::extract_module_documentation, package::PackageDocumentation,
+            utils::parse_python_str,
+        },
+        render::{formats::md::MdRenderer, layout::Layout},
+    };
+
+    fn test_package() -> Result<PackageDocumentation> {
+        let root = parse_python_str(r#""""Talks to the network. Quickly.""""#)?;
+        let mut root_doc =
+            extract_module_documentation(&root, Some("pkg".to_string()), None, false, false);
+        root_doc.path = Some("pkg/__init__.py".into());
+
+        let net = parse_python_str(
+            r#""""Connections."""
+
+async def connect(host: str) -> Client:
+    """Opens a connection. Or fails."""
+
+class Client:
+    """A connection."""
+
+    def get(self, path):
+        """Fetches path."""
+
+TIMEOUT: int = 3
+"""seconds to wait"""
+"#,
+        )?;
+        let mut net_doc = extract_module_documentation(
+            &net,
+            Some("net".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        net_doc.path = Some("pkg/net.py".into());
+
+        Ok(PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![root_doc, net_doc],
+        })
+    }
+
+    #[test]
+    fn test_llms_txt_links_module_pages() -> Result<()> {
+        let package = test_package()?;
+        assert_eq!(
+            render_llms_txt(&package, Layout::Tree, &MdRenderer::new()),
+            r"# pkg
+
+> Talks to the network.
+
+## Modules
+
+- [pkg](_index.md): Talks to the network.
+- [pkg.net](net.md): Connections.
+"
+        );
+        assert!(
+            render_llms_txt(&package, Layout::SinglePage, &MdRenderer::new())
+                .contains("- [pkg.net](pkg.md#pkg.net): Connections.\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_llms_full_txt_lists_signatures() -> Result<()> {
+        let package = test_package()?;
+        assert_eq!(
+            render_llms_full_txt(&package),
+            r"# pkg
+
+> Talks to the network.
+
+## pkg
+
+Talks to the network.
+
+## pkg.net
+
+Connections.
+
+- `async def connect(host: str) -> Client`: Opens a connection.
+- `class Client`: A connection.
+  - `def get(self, path)`: Fetches path.
+- `TIMEOUT: int = 3`: seconds to wait
+"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+pub mod coverage;
+pub mod inventory;
+pub mod json;
+pub mod llms;
+pub mod report;
+pub mod search;
+
+/// Escapes `text` for xml, in content as well as in attribute values. These are the five
+/// entities xml predefines, html ones like `&#39;` aren't guaranteed to be understood.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::escape_xml;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"a<'b'> & "c""#),
+            "a&lt;&apos;b&apos;&gt; &amp; &quot;c&quot;"
+        );
+    }
+}
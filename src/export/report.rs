@@ -0,0 +1,443 @@
+//! A machine readable account of a build for CI: the modules that were processed, the files
+//! that were written, everything that was left out and why, and the errors and warnings
+//! found along the way. Written as plain JSON or as SARIF, which code scanning tools show
+//! next to the code.
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use color_eyre::{Report, Result};
+use serde::Serialize;
+use serde_json::json;
+use strum::Display;
+
+use crate::{
+    parsing::{module::SkipReason, package::PackageDocumentation, utils::SyntaxError},
+    render::{metadata::PageMetadata, qualify},
+};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    /// SARIF 2.1.0, the skipped objects are in the properties of the run
+    Sarif,
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+/// The rules diagnostics are reported under, with a short description of each
+const RULES: [(&str, &str); 3] = [
+    ("syntax-error", "The module is not valid python"),
+    ("module-error", "The module could not be processed"),
+    ("duplicate-all", "`__all__` is assigned more than once"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub level: Level,
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    /// the line of the source the diagnostic is on, for showing it in context
+    #[serde(skip)]
+    pub source_line: Option<String>,
+}
+
+impl Diagnostic {
+    /// The reason `path` could not be documented, located in the source for syntax errors
+    pub fn from_failure(path: &Path, error: &Report) -> Self {
+        match SyntaxError::from_report(error) {
+            Some(syntax_error) => Self {
+                rule: "syntax-error".to_string(),
+                level: Level::Error,
+                path: path.to_path_buf(),
+                line: Some(syntax_error.line),
+                column: Some(syntax_error.column),
+                message: syntax_error.message.clone(),
+                source_line: syntax_error.source_line.clone(),
+            },
+            None => Self {
+                rule: "module-error".to_string(),
+                level: Level::Error,
+                path: path.to_path_buf(),
+                line: None,
+                column: None,
+                message: error.to_string(),
+                source_line: None,
+            },
+        }
+    }
+
+    /// `path:line:column`, as far as they are known
+    pub fn location(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{line}:{column}", self.path.display()),
+            (Some(line), None) => format!("{}:{line}", self.path.display()),
+            _ => self.path.display().to_string(),
+        }
+    }
+
+    /// The diagnostic the way compilers show them, with the line it is on and a caret
+    /// under the column:
+    ///
+    /// ```text
+    /// error[syntax-error]: Got unexpected token :
+    ///  --> src/pkg/broken.py:1:7
+    ///   |
+    /// 1 | def f(:
+    ///   |       ^
+    /// ```
+    pub fn render(&self) -> String {
+        let mut out = format!("{}[{}]: {}\n", self.level, self.rule, self.message);
+        let Some((line, code)) = self.line.zip(self.source_line.as_deref()) else {
+            out.push_str(&format!(" --> {}\n", self.location()));
+            return out;
+        };
+
+        let width = line.to_string().len();
+        let gutter = " ".repeat(width);
+        out.push_str(&format!("{gutter}--> {}\n", self.location()));
+        out.push_str(&format!("{gutter} |\n"));
+        out.push_str(&format!("{line} | {code}\n"));
+        if let Some(column) = self.column {
+            // keep tabs so the caret lines up however wide they are shown
+            let indent: String = code
+                .chars()
+                .take(column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            out.push_str(&format!("{gutter} | {indent}^\n"));
+        }
+        out
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SkippedItem {
+    pub path: PathBuf,
+    /// the qualified name of the function, class or attribute, `None` if the whole module
+    /// or package at `path` was skipped
+    pub object: Option<String>,
+    pub line: Option<usize>,
+    pub reason: SkipReason,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BuildReport {
+    /// the source files that were documented
+    pub modules: Vec<PathBuf>,
+    pub outputs: Vec<PathBuf>,
+    pub skipped: Vec<SkippedItem>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl BuildReport {
+    /// `package` was extracted from `pkg_path`, leaving out `skipped_paths` and failing on
+    /// `failures`
+    pub fn new(
+        package: &PackageDocumentation,
+        pkg_path: &Path,
+        skipped_paths: &[(PathBuf, SkipReason)],
+        failures: &[(PathBuf, Report)],
+        outputs: Vec<PathBuf>,
+    ) -> Self {
+        let mut report = BuildReport {
+            outputs,
+            ..BuildReport::default()
+        };
+        report
+            .skipped
+            .extend(skipped_paths.iter().map(|(path, reason)| SkippedItem {
+                path: path.clone(),
+                object: None,
+                line: None,
+                reason: *reason,
+            }));
+
+        for module in &package.modules {
+            let (Some(module_name), Some(rel_path)) =
+                (PageMetadata::from(module).title, module.relative_path())
+            else {
+                continue;
+            };
+            let path = pkg_path.join(rel_path);
+            report
+                .skipped
+                .extend(module.skipped.iter().map(|skipped| SkippedItem {
+                    path: path.clone(),
+                    object: Some(qualify(Some(&module_name), &skipped.name)),
+                    line: skipped.line,
+                    reason: skipped.reason,
+                }));
+            report
+                .diagnostics
+                .extend(module.warnings.iter().map(|warning| Diagnostic {
+                    rule: warning.rule.to_string(),
+                    level: Level::Warning,
+                    path: path.clone(),
+                    line: warning.line,
+                    column: None,
+                    message: warning.message.clone(),
+                    source_line: None,
+                }));
+            report.modules.push(path);
+        }
+
+        report.diagnostics.extend(
+            failures
+                .iter()
+                .map(|(path, error)| Diagnostic::from_failure(path, error)),
+        );
+        report
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Sarif => self.to_sarif(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let mut out = serde_json::to_string_pretty(self)?;
+        out.push('\n');
+        Ok(out)
+    }
+
+    pub fn to_sarif(&self) -> Result<String> {
+        let rules: Vec<_> = RULES
+            .iter()
+            .map(|(id, description)| json!({"id": id, "shortDescription": {"text": description}}))
+            .collect();
+        let results: Vec<_> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let mut region = json!({"startLine": diagnostic.line.unwrap_or(1)});
+                if let Some(column) = diagnostic.column {
+                    region["startColumn"] = json!(column);
+                }
+                json!({
+                    "ruleId": diagnostic.rule,
+                    "level": diagnostic.level,
+                    "message": {"text": diagnostic.message},
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {"uri": uri(&diagnostic.path)},
+                            "region": region,
+                        }
+                    }],
+                })
+            })
+            .collect();
+        let artifacts: Vec<_> = self
+            .modules
+            .iter()
+            .map(|path| json!({"location": {"uri": uri(path)}, "roles": ["analysisTarget"]}))
+            .chain(
+                self.outputs
+                    .iter()
+                    .map(|path| json!({"location": {"uri": uri(path)}, "roles": ["resultFile"]})),
+            )
+            .collect();
+
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "artifacts": artifacts,
+                "results": results,
+                "properties": {"skipped": self.skipped},
+            }],
+        });
+        let mut out = serde_json::to_string_pretty(&sarif)?;
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+/// SARIF wants URIs, which always use `/`
+fn uri(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf};
+
+    use assert_fs::TempDir;
+    use color_eyre::{Result, eyre::eyre};
+    use pretty_assertions::assert_eq;
+    use serde_json::Value;
+
+    use super::{BuildReport, Diagnostic, Level};
+    use crate::parsing::{
+        module::{SkipReason, extract_located_module_documentation},
+        package::PackageDocumentation,
+        utils::parse_python_str,
+    };
+
+    fn test_report(root: &std::path::Path) -> Result<BuildReport> {
+        let source = r#""""The api."""
+
+__all__ = ["send"]
+__all__ = ["send", "fetch"]
+
+def send():
+    """Sends."""
+
+def _private():
+    """Hidden."""
+"#;
+        let program = parse_python_str(source)?;
+        let mut module = extract_located_module_documentation(
+            &program,
+            source,
+            Some("api".to_string()),
+            Some("pkg".to_string()),
+            true,
+            true,
+        );
+        module.path = Some("pkg/api.py".into());
+        let package = PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![module],
+        };
+
+        let broken = root.join("broken.py");
+        fs::write(&broken, "def f(:\n    pass\n")?;
+        let Err(error) = parse_python_str("def f(:\n    pass\n") else {
+            panic!("expected a syntax error");
+        };
+
+        Ok(BuildReport::new(
+            &package,
+            &PathBuf::from("src/pkg"),
+            &[(PathBuf::from("src/pkg/_internal"), SkipReason::Private)],
+            &[(broken, error)],
+            vec![PathBuf::from("docs/api/pkg/api.md")],
+        ))
+    }
+
+    #[test]
+    fn test_report_contents() -> Result<()> {
+        let root = TempDir::new()?;
+        let report = test_report(root.path())?;
+
+        assert_eq!(report.modules, vec![PathBuf::from("src/pkg/api.py")]);
+        assert_eq!(
+            report
+                .skipped
+                .iter()
+                .map(|s| (s.object.clone(), s.reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, SkipReason::Private),
+                (Some("pkg.api._private".to_string()), SkipReason::Private),
+            ]
+        );
+        assert_eq!(
+            report.diagnostics[0],
+            Diagnostic {
+                rule: "duplicate-all".to_string(),
+                level: Level::Warning,
+                path: PathBuf::from("src/pkg/api.py"),
+                line: Some(4),
+                column: None,
+                message: "__all__ was defined multiple times.".to_string(),
+                source_line: None,
+            }
+        );
+        let syntax_error = &report.diagnostics[1];
+        assert_eq!(syntax_error.rule, "syntax-error");
+        assert_eq!(syntax_error.level, Level::Error);
+        assert_eq!(syntax_error.line, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_diagnostic_with_snippet() -> Result<()> {
+        let root = TempDir::new()?;
+        let path = root.path().join("broken.py");
+        let diagnostic = Diagnostic {
+            rule: "syntax-error".to_string(),
+            level: Level::Error,
+            path: path.clone(),
+            line: Some(2),
+            column: Some(4),
+            message: "unexpected token".to_string(),
+            source_line: Some("\tx = (1,".to_string()),
+        };
+        assert_eq!(
+            diagnostic.render(),
+            format!(
+                "error[syntax-error]: unexpected token\n --> {}:2:4\n  |\n2 | \tx = (1,\n  | \t  ^\n",
+                path.display()
+            )
+        );
+
+        let Err(error) = parse_python_str("import os\ndef f(:\n") else {
+            panic!("expected a syntax error");
+        };
+        // what was parsed is shown, even if the file changed since
+        fs::write(&path, "import os\n\ndef f(): pass\n")?;
+        let diagnostic = Diagnostic::from_failure(&path, &error);
+        assert_eq!(diagnostic.rule, "syntax-error");
+        assert_eq!(diagnostic.location(), format!("{}:2:7", path.display()));
+        assert!(diagnostic.render().contains("2 | def f(:\n"));
+
+        let missing = Diagnostic::from_failure(
+            &root.path().join("missing.py"),
+            &eyre!("No such file or directory"),
+        );
+        assert_eq!(missing.rule, "module-error");
+        assert_eq!(
+            missing.render(),
+            format!(
+                "error[module-error]: {}\n --> {}\n",
+                missing.message,
+                missing.path.display()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sarif_report() -> Result<()> {
+        let root = TempDir::new()?;
+        let sarif: Value = serde_json::from_str(&test_report(root.path())?.to_sarif()?)?;
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["results"][0]["ruleId"], "duplicate-all");
+        assert_eq!(run["results"][0]["level"], "warning");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/pkg/api.py"
+        );
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            4
+        );
+        assert_eq!(run["results"][1]["level"], "error");
+        assert_eq!(run["artifacts"][1]["roles"][0], "resultFile");
+        assert_eq!(run["properties"]["skipped"][1]["reason"], "private");
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+// Searches the index written by snakedown next to this script, see `export::search`.
+// Expects an `<input id="snakedown-search">` and a `<ul id="snakedown-search-results">`.
+(function () {
+  "use strict";
+
+  var MAX_RESULTS = 20;
+  var base = new URL(".", document.currentScript.src);
+  var entries = null;
+
+  function score(entry, query) {
+    var name = entry.name.toLowerCase();
+    var qualified = entry.qualified_name.toLowerCase();
+    if (name === query || qualified === query) {
+      return 0;
+    }
+    if (name.startsWith(query)) {
+      return 1;
+    }
+    if (qualified.endsWith("." + query) || qualified.indexOf(query) !== -1) {
+      return 2;
+    }
+    if (entry.summary && entry.summary.toLowerCase().indexOf(query) !== -1) {
+      return 3;
+    }
+    return -1;
+  }
+
+  function search(query) {
+    query = query.trim().toLowerCase();
+    if (!entries || query === "") {
+      return [];
+    }
+    return entries
+      .map(function (entry) {
+        return { entry: entry, score: score(entry, query) };
+      })
+      .filter(function (match) {
+        return match.score >= 0;
+      })
+      .sort(function (a, b) {
+        return (
+          a.score - b.score ||
+          a.entry.qualified_name.length - b.entry.qualified_name.length
+        );
+      })
+      .slice(0, MAX_RESULTS)
+      .map(function (match) {
+        return match.entry;
+      });
+  }
+
+  function render(results, list) {
+    list.replaceChildren();
+    results.forEach(function (entry) {
+      var item = document.createElement("li");
+      var link = document.createElement("a");
+      link.href = new URL(entry.url, base).href;
+      link.textContent = entry.qualified_name;
+      var kind = document.createElement("span");
+      kind.className = "kind";
+      kind.textContent = " " + entry.kind;
+      item.append(link, kind);
+      if (entry.summary) {
+        var summary = document.createElement("p");
+        summary.textContent = entry.summary;
+        item.append(summary);
+      }
+      list.append(item);
+    });
+  }
+
+  function init() {
+    var input = document.getElementById("snakedown-search");
+    var list = document.getElementById("snakedown-search-results");
+    if (!input || !list) {
+      return;
+    }
+    fetch(new URL("search_index.json", base))
+      .then(function (response) {
+        return response.json();
+      })
+      .then(function (index) {
+        entries = index;
+        render(search(input.value), list);
+      });
+    input.addEventListener("input", function () {
+      render(search(input.value), list);
+    });
+  }
+
+  if (document.readyState === "loading") {
+    document.addEventListener("DOMContentLoaded", init);
+  } else {
+    init();
+  }
+})();
@@ -0,0 +1,313 @@
+//! A search index with an entry for every documented object, so searching for a method lands
+//! on the method rather than on the page of its module. The index is a JSON array that can be
+//! fed to lunr/elasticlunr as is, or used with the bundled widget:
+//!
+//! ```html
+//! <input type="search" id="snakedown-search" placeholder="Search the API">
+//! <ul id="snakedown-search-results"></ul>
+//! <script src="snakedown-search.js"></script>
+//! ```
+//!
+//! The widget expects the index next to itself and resolves urls relative to its own location.
+
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    parsing::{
+        class::ClassDocumentation, module::ModuleDocumentation, package::PackageDocumentation,
+    },
+    render::{
+        anchors::object_link,
+        formats::Renderer,
+        layout::{Layout, module_page, object_page_path},
+        link_target,
+        metadata::{PageMetadata, summarize},
+        qualify,
+    },
+};
+
+pub const SEARCH_INDEX_NAME: &str = "search_index.json";
+pub const SEARCH_WIDGET_NAME: &str = "snakedown-search.js";
+/// bundled into the binary like the stylesheet of the html output
+pub const SEARCH_WIDGET: &str = include_str!("search.js");
+
+/// the same roles as in `objects.inv`, see `export::inventory`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectKind {
+    Module,
+    Function,
+    Class,
+    Method,
+    Attribute,
+    /// an attribute of a module
+    Data,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchEntry {
+    pub name: String,
+    pub kind: ObjectKind,
+    pub qualified_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// relative to the root of the output, including the anchor of the object
+    pub url: String,
+}
+
+pub fn render_search_index<R: Renderer>(
+    package: &PackageDocumentation,
+    layout: Layout,
+    renderer: &R,
+) -> Result<String> {
+    let mut out = serde_json::to_string(&search_entries(package, layout, renderer))?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Every object of the package, linking to where `renderer` puts it in `layout`
+pub fn search_entries<R: Renderer>(
+    package: &PackageDocumentation,
+    layout: Layout,
+    renderer: &R,
+) -> Vec<SearchEntry> {
+    package
+        .modules
+        .iter()
+        .flat_map(|module| module_entries(module, &package.name, layout, renderer))
+        .collect()
+}
+
+fn module_entries<R: Renderer>(
+    module: &ModuleDocumentation,
+    package_name: &str,
+    layout: Layout,
+    renderer: &R,
+) -> Vec<SearchEntry> {
+    let (Some(title), Some(page)) = (
+        PageMetadata::from(module).title,
+        module_page(module, package_name, layout, renderer),
+    ) else {
+        tracing::warn!("leaving a module without a name or path out of the search index");
+        return vec![];
+    };
+    let prefix = Some(title.as_str());
+    let page_url = renderer.page_url(&link_target(&page));
+    let page_dir = page.parent().unwrap_or(Path::new("")).to_path_buf();
+    // the pages of classes and functions in the per object layout
+    let own_page = |name: &str| -> Option<PathBuf> {
+        if layout != Layout::PerObject {
+            return None;
+        }
+        object_page_path(module, name, renderer).map(|p| page_dir.join(p))
+    };
+
+    let mut entries = vec![SearchEntry {
+        name: module.name.clone().unwrap_or_else(|| title.clone()),
+        kind: ObjectKind::Module,
+        qualified_name: title.clone(),
+        summary: summarize(module.docstring.as_deref()),
+        url: if layout == Layout::SinglePage && title != package_name {
+            object_link(&page_url, &title)
+        } else {
+            page_url.clone()
+        },
+    }];
+
+    for fn_docs in &module.functions {
+        let qualified_name = qualify(prefix, &fn_docs.name);
+        let url = match own_page(&fn_docs.name) {
+            Some(path) => renderer.page_url(&link_target(&path)),
+            None => object_link(&page_url, &qualified_name),
+        };
+        entries.push(SearchEntry {
+            name: fn_docs.name.clone(),
+            kind: ObjectKind::Function,
+            qualified_name,
+            summary: summarize(fn_docs.docstring.as_deref()),
+            url,
+        });
+    }
+    for class_docs in &module.classes {
+        let qualified_name = qualify(prefix, &class_docs.name);
+        let (url, members_page) = match own_page(&class_docs.name) {
+            Some(path) => {
+                let url = renderer.page_url(&link_target(&path));
+                (url.clone(), url)
+            }
+            None => (object_link(&page_url, &qualified_name), page_url.clone()),
+        };
+        entries.push(SearchEntry {
+            name: class_docs.name.clone(),
+            kind: ObjectKind::Class,
+            qualified_name: qualified_name.clone(),
+            summary: summarize(class_docs.docstring.as_deref()),
+            url,
+        });
+        entries.extend(member_entries(class_docs, &qualified_name, &members_page));
+    }
+    for attr_docs in &module.attributes {
+        let qualified_name = qualify(prefix, &attr_docs.name);
+        entries.push(SearchEntry {
+            name: attr_docs.name.clone(),
+            kind: ObjectKind::Data,
+            url: object_link(&page_url, &qualified_name),
+            qualified_name,
+            summary: summarize(attr_docs.docstring.as_deref()),
+        });
+    }
+    entries
+}
+
+/// the methods and attributes of a class documented on the page at `page_url`
+fn member_entries(
+    class_docs: &ClassDocumentation,
+    class_name: &str,
+    page_url: &str,
+) -> Vec<SearchEntry> {
+    let methods = class_docs.methods.iter().map(|method| {
+        (
+            &method.name,
+            ObjectKind::Method,
+            method.docstring.as_deref(),
+        )
+    });
+    let attributes = class_docs.attributes.iter().map(|attr_docs| {
+        (
+            &attr_docs.name,
+            ObjectKind::Attribute,
+            attr_docs.docstring.as_deref(),
+        )
+    });
+    methods
+        .chain(attributes)
+        .map(|(name, kind, docstring)| {
+            let qualified_name = qualify(Some(class_name), name);
+            SearchEntry {
+                name: name.clone(),
+                kind,
+                url: object_link(page_url, &qualified_name),
+                qualified_name,
+                summary: summarize(docstring),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{ObjectKind, search_entries};
+    use crate::{
+        parsing::{
+            module::extract_module_documentation, package::PackageDocumentation,
+            utils::parse_python_str,
+        },
+        render::{
+            formats::{md::MdRenderer, zola::ZolaRenderer},
+            layout::Layout,
+        },
+    };
+
+    fn test_package() -> Result<PackageDocumentation> {
+        let program = parse_python_str(
+            r#""""Networking."""
+
+class Client:
+    """Talks to servers."""
+
+    def fetch(self, url):
+        """Fetches url. Eventually."""
+
+TIMEOUT = 3
+"#,
+        )?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some("net".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        mod_doc.path = Some("pkg/net.py".into());
+        Ok(PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![mod_doc],
+        })
+    }
+
+    fn urls(package: &PackageDocumentation, layout: Layout, zola: bool) -> Vec<(String, String)> {
+        let entries = if zola {
+            search_entries(package, layout, &ZolaRenderer::new())
+        } else {
+            search_entries(package, layout, &MdRenderer::new())
+        };
+        entries
+            .into_iter()
+            .map(|e| (e.qualified_name, e.url))
+            .collect()
+    }
+
+    #[test]
+    fn test_search_entries() -> Result<()> {
+        let package = test_package()?;
+        let entries = search_entries(&package, Layout::Tree, &MdRenderer::new());
+        let fetch = &entries[2];
+        assert_eq!(fetch.name, "fetch");
+        assert_eq!(fetch.kind, ObjectKind::Method);
+        assert_eq!(fetch.summary.as_deref(), Some("Fetches url."));
+
+        assert_eq!(
+            urls(&package, Layout::Tree, false),
+            vec![
+                ("pkg.net".to_string(), "net.md".to_string()),
+                (
+                    "pkg.net.Client".to_string(),
+                    "net.md#pkg.net.Client".to_string()
+                ),
+                (
+                    "pkg.net.Client.fetch".to_string(),
+                    "net.md#pkg.net.Client.fetch".to_string()
+                ),
+                (
+                    "pkg.net.TIMEOUT".to_string(),
+                    "net.md#pkg.net.TIMEOUT".to_string()
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_urls_follow_layout() -> Result<()> {
+        let package = test_package()?;
+        assert_eq!(
+            urls(&package, Layout::PerObject, true),
+            vec![
+                ("pkg.net".to_string(), "net/".to_string()),
+                ("pkg.net.Client".to_string(), "net/client/".to_string()),
+                (
+                    "pkg.net.Client.fetch".to_string(),
+                    "net/client/#pkg.net.Client.fetch".to_string()
+                ),
+                (
+                    "pkg.net.TIMEOUT".to_string(),
+                    "net/#pkg.net.TIMEOUT".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            urls(&package, Layout::SinglePage, false)[1],
+            (
+                "pkg.net.Client".to_string(),
+                "pkg.md#pkg.net.Client".to_string()
+            )
+        );
+        Ok(())
+    }
+}
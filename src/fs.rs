@@ -8,7 +8,7 @@
 
 use color_eyre::eyre::{OptionExt, Result, eyre};
 
-use crate::render::translate_filename;
+use crate::parsing::module::SkipReason;
 
 /// determines whether given path is a Python module
 /// i.e. a file with a .py extension
@@ -210,7 +210,11 @@ pub fn get_subpackages(pkg_path: &Path) -> Result<Vec<PathBuf>> {
 pub struct PackageIndex {
     pub module_paths: Vec<PathBuf>,
     pub package_paths: Vec<PathBuf>,
+    /// the direct sub modules and sub packages (by their `__init__.py`) of every package,
+    /// relative to that package
     pub sub_module_index: HashMap<PathBuf, Vec<PathBuf>>,
+    /// the modules and packages left out of the walk, packages without their contents
+    pub skipped: Vec<(PathBuf, SkipReason)>,
 }
 
 /// will walk the provided path and index all the subpackages and modules
@@ -223,11 +227,18 @@ pub fn walk_package(
 ) -> Result<PackageIndex> {
     let mut modules = vec![];
     let mut sub_packages = vec![];
-
-    for entry in WalkDir::new(pkg_path)
-        .into_iter()
-        .filter_entry(|e| should_include(e.path(), skip_private, &exclude))
-    {
+    let mut skipped = vec![];
+
+    for entry in WalkDir::new(pkg_path).into_iter().filter_entry(|e| {
+        match skip_reason(e.path(), skip_private, &exclude) {
+            Ok(()) => true,
+            Err(Some(reason)) => {
+                skipped.push((e.path().to_path_buf(), reason));
+                false
+            }
+            Err(None) => false,
+        }
+    }) {
         let module_or_package = entry?;
         let module_or_package_path = module_or_package.path();
         if is_python_module(module_or_package_path)? {
@@ -258,7 +269,6 @@ pub fn walk_package(
         let child_pkgs = sub_packages
             .iter()
             .filter(|p| p.starts_with(sub_pkg) && p.components().count() == pkg_component_count + 1)
-            .cloned()
             .map(|p| p.join("__init__.py"))
             .collect::<Vec<_>>();
 
@@ -268,7 +278,6 @@ pub fn walk_package(
         subs = subs
             .into_iter()
             .filter_map(|p| p.strip_prefix(sub_pkg).ok().map(|s| s.to_path_buf()))
-            .map(|p| translate_filename(&p).to_path_buf())
             .collect();
         let _entry = sub_modules.insert(sub_pkg.clone(), subs);
     }
@@ -277,20 +286,30 @@ pub fn walk_package(
         module_paths: modules,
         package_paths: sub_packages,
         sub_module_index: sub_modules,
+        skipped,
     })
 }
 
 fn should_include(path: &Path, skip_private: bool, excluded: &[PathBuf]) -> bool {
+    skip_reason(path, skip_private, excluded).is_ok()
+}
+
+/// `Err` with the reason if `path` is left out, `None` if it isn't python to begin with
+fn skip_reason(
+    path: &Path,
+    skip_private: bool,
+    excluded: &[PathBuf],
+) -> std::result::Result<(), Option<SkipReason>> {
     if !(is_python_package(path).unwrap_or(false) || is_python_module(path).unwrap_or(false)) {
         tracing::info!(
             "Skipping {} because it is not a python module or package",
             &path.display()
         );
-        return false;
+        return Err(None);
     }
     if is_private_module(path) && skip_private {
         tracing::info!("Skipping {} because it is is private", &path.display());
-        return false;
+        return Err(Some(SkipReason::Private));
     }
     if excluded
         .iter()
@@ -300,10 +319,10 @@ fn should_include(path: &Path, skip_private: bool, excluded: &[PathBuf]) -> bool
             "Skipping {} because it was explicitly excluded",
             &path.display()
         );
-        return false;
+        return Err(Some(SkipReason::Excluded));
     }
 
-    true
+    Ok(())
 }
 
 #[cfg(test)]
@@ -479,6 +498,32 @@ fn walk_package_finds_packages_and_modules() -> Result<()> {
         .collect();
 
         assert_eq!(index.module_paths, expected_sub_modules);
+        assert!(index.skipped.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn walk_package_records_skipped_paths() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let root_pkg_path = temp_dir.join("test");
+        let sub_pkg_path = root_pkg_path.join("a");
+        create_empty_python_package_on_disk(&root_pkg_path)?;
+        create_empty_python_package_on_disk(&sub_pkg_path)?;
+        let _ = File::create(sub_pkg_path.join("foo.py"))?;
+        let _ = File::create(root_pkg_path.join("_private.py"))?;
+        let _ = File::create(root_pkg_path.join("README.md"))?;
+
+        let mut index = walk_package(&root_pkg_path, true, vec![PathBuf::from("test/a")])?;
+        index.skipped.sort();
+
+        assert_eq!(
+            index.skipped,
+            vec![
+                (root_pkg_path.join("_private.py"), SkipReason::Private),
+                (sub_pkg_path, SkipReason::Excluded),
+            ]
+        );
+        assert_eq!(index.module_paths, vec![root_pkg_path.join("__init__.py")]);
         Ok(())
     }
 
@@ -1,11 +1,13 @@
 use std::{
     env,
-    fs::{create_dir_all, exists},
-    path::PathBuf,
+    fs::{create_dir_all, exists, read_dir},
+    path::{Path, PathBuf},
 };
 
 use color_eyre::Result;
 
+use crate::parsing::sphinx::inv_file::parse_objects_inv_file;
+
 /// `$HOME/.snakedown/cache` if $HOME exists and `./.shakedown/cache` else
 // TODO: start tying different combinations of XDG_CACHE_HOME and APPDIR where appropriate
 pub fn get_cache_path() -> PathBuf {
@@ -31,6 +33,42 @@ pub fn init_cache(maybe_cache_path: Option<PathBuf>) -> Result<PathBuf> {
     Ok(cache_path)
 }
 
+/// where the sphinx inventory of `project_name` is cached
+pub fn cached_inventory_path(cache_path: &Path, project_name: &str) -> PathBuf {
+    cache_path
+        .join("sphinx")
+        .join(project_name.to_lowercase())
+        .with_extension("inv")
+}
+
+/// the names of all projects with a cached sphinx inventory, sorted
+pub fn cached_inventories(cache_path: &Path) -> Result<Vec<String>> {
+    let sphinx_dir = cache_path.join("sphinx");
+    if !exists(&sphinx_dir)? {
+        return Ok(vec![]);
+    }
+    let mut names = vec![];
+    for entry in read_dir(sphinx_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "inv") {
+            continue;
+        }
+        if let Some(name) = path.file_stem() {
+            names.push(name.to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// the name and location of every object in the sphinx inventory at `path`
+pub fn inventory_objects(path: &Path) -> Result<Vec<(String, PathBuf)>> {
+    Ok(parse_objects_inv_file(path)?
+        .into_iter()
+        .map(|object| (object.name, object.location))
+        .collect())
+}
+
 #[cfg(test)]
 mod test {
     use std::{
@@ -41,7 +79,7 @@ mod test {
     use color_eyre::Result;
     use tempfile::TempDir;
 
-    use crate::indexing::cache::init_cache;
+    use crate::indexing::cache::{cached_inventories, cached_inventory_path, init_cache};
 
     #[test]
     fn init_cache_tmp_dir() -> Result<()> {
@@ -79,4 +117,19 @@ fn init_cache_doesnt_touch_existing_dir() -> Result<()> {
         }
         Ok(())
     }
+
+    #[test]
+    fn cached_inventories_are_listed_by_name() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let cache_path = tmp_dir.path().join("cache");
+        assert!(cached_inventories(&cache_path)?.is_empty());
+
+        init_cache(Some(cache_path.clone()))?;
+        File::create(cached_inventory_path(&cache_path, "NumPy"))?;
+        File::create(cached_inventory_path(&cache_path, "attrs"))?;
+        File::create(cache_path.join("sphinx").join("notes.txt"))?;
+
+        assert_eq!(cached_inventories(&cache_path)?, vec!["attrs", "numpy"]);
+        Ok(())
+    }
 }
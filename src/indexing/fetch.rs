@@ -2,7 +2,7 @@
 
 use reqwest::blocking::Response;
 
-use crate::indexing::cache::init_cache;
+use crate::indexing::cache::{cached_inventory_path, init_cache};
 use color_eyre::Result;
 
 pub fn cache_remote_objects_inv(
@@ -26,10 +26,7 @@ fn create_object_inv_cache(
     project_name: String,
     maybe_cache_path: Option<PathBuf>,
 ) -> Result<()> {
-    let cache_path = init_cache(maybe_cache_path)?
-        .join("sphinx")
-        .join(PathBuf::from(project_name.to_lowercase()))
-        .with_extension("inv");
+    let cache_path = cached_inventory_path(&init_cache(maybe_cache_path)?, &project_name);
 
     let mut file = File::create(cache_path)?;
 
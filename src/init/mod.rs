@@ -0,0 +1,255 @@
+//! Setting up snakedown in an existing python project: finding the package to document
+//! and scaffolding a minimal Zola or Hugo site with the API docs as one of its sections.
+
+use std::{
+    fs::{File, create_dir_all, read_dir, read_to_string},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use color_eyre::Result;
+use strum::Display;
+
+use crate::render::SSG;
+
+/// The static site generators `scaffold_site` can set up, both use the zola output
+#[derive(Clone, Copy, Debug, Display, ValueEnum, PartialEq, Eq)]
+pub enum Site {
+    Zola,
+    Hugo,
+}
+
+impl Site {
+    /// The renderer for the API section of the site. Hugo reads the `+++` TOML front matter
+    /// and the `_index.md` section pages of the zola output as is.
+    pub fn ssg(self) -> SSG {
+        match self {
+            Site::Zola | Site::Hugo => SSG::Zola,
+        }
+    }
+}
+
+/// The section of the site the API docs are rendered into
+pub const API_SECTION: &str = "api";
+
+const ZOLA_INDEX_TEMPLATE: &str = include_str!("zola/index.html");
+const ZOLA_SECTION_TEMPLATE: &str = include_str!("zola/section.html");
+const ZOLA_PAGE_TEMPLATE: &str = include_str!("zola/page.html");
+const HUGO_BASEOF_LAYOUT: &str = include_str!("hugo/baseof.html");
+const HUGO_LIST_LAYOUT: &str = include_str!("hugo/list.html");
+const HUGO_SINGLE_LAYOUT: &str = include_str!("hugo/single.html");
+
+/// The name of the distribution in the `pyproject.toml` of `root`, from either the
+/// `[project]` or the `[tool.poetry]` table
+pub fn pyproject_name(root: &Path) -> Option<String> {
+    let pyproject: toml::Table = read_to_string(root.join("pyproject.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    let name = pyproject
+        .get("project")
+        .and_then(|project| project.get("name"))
+        .or_else(|| {
+            pyproject
+                .get("tool")
+                .and_then(|tool| tool.get("poetry"))
+                .and_then(|poetry| poetry.get("name"))
+        })?;
+    name.as_str().map(str::to_string)
+}
+
+/// The package of the project in `root`, relative to `root`. Both the `src/` and the flat
+/// layout are recognised. The package named in `pyproject.toml` wins, otherwise a package
+/// is only picked if it is the only one.
+pub fn detect_package(root: &Path) -> Option<PathBuf> {
+    let bases = [PathBuf::from("src"), PathBuf::new()];
+    if let Some(name) = pyproject_name(root) {
+        // distributions are named `my-pkg` or `My.Pkg`, the package is still `my_pkg`
+        let import_name = name.to_lowercase().replace(['-', '.'], "_");
+        let found = bases
+            .iter()
+            .map(|base| base.join(&import_name))
+            .find(|candidate| root.join(candidate).join("__init__.py").is_file());
+        if found.is_some() {
+            return found;
+        }
+        tracing::warn!("found no package for {name} from pyproject.toml");
+    }
+
+    for base in &bases {
+        let mut packages = packages_in(&root.join(base));
+        if packages.len() == 1 {
+            return packages.pop().map(|package| base.join(package));
+        }
+    }
+    None
+}
+
+/// the names of the python packages directly in `dir`, leaving out tests and docs
+fn packages_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("__init__.py").is_file())
+        .filter_map(|path| Some(path.file_name()?.to_string_lossy().to_string()))
+        .filter(|name| {
+            !name.starts_with('.') && !["tests", "test", "docs"].contains(&name.as_str())
+        })
+        .collect()
+}
+
+/// Writes a minimal `site` site titled `title` to `site_dir` and returns the directory the
+/// API docs should be rendered to. Existing files are only replaced if `force` is set.
+pub fn scaffold_site(site: Site, site_dir: &Path, title: &str, force: bool) -> Result<PathBuf> {
+    let quoted_title = toml::Value::String(title.to_string());
+    let files = match site {
+        Site::Zola => vec![
+            (
+                PathBuf::from("config.toml"),
+                format!(
+                    "base_url = \"https://example.com\"\ntitle = {quoted_title}\ncompile_sass = false\nbuild_search_index = false\n"
+                ),
+            ),
+            (
+                PathBuf::from("templates/index.html"),
+                ZOLA_INDEX_TEMPLATE.to_string(),
+            ),
+            (
+                PathBuf::from("templates/section.html"),
+                ZOLA_SECTION_TEMPLATE.to_string(),
+            ),
+            (
+                PathBuf::from("templates/page.html"),
+                ZOLA_PAGE_TEMPLATE.to_string(),
+            ),
+        ],
+        Site::Hugo => vec![
+            (
+                PathBuf::from("hugo.toml"),
+                // the docstrings can contain html, which hugo drops by default
+                format!(
+                    "baseURL = \"https://example.com/\"\ntitle = {quoted_title}\n\n[markup.goldmark.renderer]\nunsafe = true\n"
+                ),
+            ),
+            (
+                PathBuf::from("layouts/_default/baseof.html"),
+                HUGO_BASEOF_LAYOUT.to_string(),
+            ),
+            (
+                PathBuf::from("layouts/_default/list.html"),
+                HUGO_LIST_LAYOUT.to_string(),
+            ),
+            (
+                PathBuf::from("layouts/_default/single.html"),
+                HUGO_SINGLE_LAYOUT.to_string(),
+            ),
+        ],
+    };
+    let home_page =
+        format!("+++\ntitle = {quoted_title}\n+++\n\nSee the [API reference](./{API_SECTION}/).\n");
+
+    for (rel_path, contents) in files
+        .into_iter()
+        .chain([(PathBuf::from("content/_index.md"), home_page)])
+    {
+        let path = site_dir.join(rel_path);
+        if path.exists() && !force {
+            tracing::warn!("{} already exists, leaving it alone", path.display());
+            continue;
+        }
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        tracing::info!("writing {}", path.display());
+        let mut file = File::create(path)?;
+        file.write_all(contents.as_bytes())?;
+    }
+
+    Ok(site_dir.join("content").join(API_SECTION))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf};
+
+    use assert_fs::TempDir;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{Site, detect_package, pyproject_name, scaffold_site};
+
+    fn write_package(root: &std::path::Path, rel_path: &str) -> Result<()> {
+        fs::create_dir_all(root.join(rel_path))?;
+        fs::write(root.join(rel_path).join("__init__.py"), "")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_src_layout_from_pyproject() -> Result<()> {
+        let root = TempDir::new()?;
+        fs::write(
+            root.path().join("pyproject.toml"),
+            "[project]\nname = \"My-Pkg\"\nversion = \"0.1.0\"\n",
+        )?;
+        write_package(root.path(), "src/my_pkg")?;
+        write_package(root.path(), "src/vendored")?;
+
+        assert_eq!(pyproject_name(root.path()), Some("My-Pkg".to_string()));
+        assert_eq!(
+            detect_package(root.path()),
+            Some(PathBuf::from("src/my_pkg"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_flat_layout_without_pyproject() -> Result<()> {
+        let root = TempDir::new()?;
+        write_package(root.path(), "pkg")?;
+        write_package(root.path(), "tests")?;
+        fs::create_dir(root.path().join("docs"))?;
+
+        assert_eq!(detect_package(root.path()), Some(PathBuf::from("pkg")));
+
+        write_package(root.path(), "other")?;
+        assert_eq!(detect_package(root.path()), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaffold_zola_site() -> Result<()> {
+        let root = TempDir::new()?;
+        let site_dir = root.path().join("docs");
+        fs::create_dir_all(site_dir.join("templates"))?;
+        fs::write(site_dir.join("templates/page.html"), "mine")?;
+
+        let api_dir = scaffold_site(Site::Zola, &site_dir, "my \"pkg\"", false)?;
+        assert_eq!(api_dir, site_dir.join("content/api"));
+        let config: toml::Table = fs::read_to_string(site_dir.join("config.toml"))?.parse()?;
+        assert_eq!(
+            config.get("title").and_then(toml::Value::as_str),
+            Some("my \"pkg\"")
+        );
+        assert!(site_dir.join("templates/index.html").is_file());
+        assert!(site_dir.join("content/_index.md").is_file());
+        assert_eq!(
+            fs::read_to_string(site_dir.join("templates/page.html"))?,
+            "mine"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaffold_hugo_site() -> Result<()> {
+        let root = TempDir::new()?;
+        let api_dir = scaffold_site(Site::Hugo, root.path(), "pkg", false)?;
+        assert_eq!(api_dir, root.path().join("content/api"));
+        assert!(root.path().join("hugo.toml").is_file());
+        assert!(root.path().join("layouts/_default/list.html").is_file());
+        Ok(())
+    }
+}
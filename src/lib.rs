@@ -1,101 +1,733 @@
 pub mod config;
+pub mod diff;
+pub mod export;
 pub mod fs;
 pub mod indexing;
+pub mod init;
+pub mod lint;
 pub mod parsing;
 pub mod render;
+pub mod serve;
+pub mod watch;
 
-use std::fs::{File, create_dir_all};
+use std::collections::BTreeSet;
+use std::fs::{File, create_dir_all, read_dir, read_to_string, remove_dir, remove_file};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use crate::config::Config;
+use crate::fs::PackageIndex;
 pub use crate::fs::{get_module_name, get_package_modules, walk_package};
+use crate::parsing::module::{ModuleDocumentation, ModuleReference};
+use crate::parsing::package::PackageDocumentation;
 use crate::render::formats::Renderer;
 pub use crate::render::render_module;
 
-use color_eyre::Result;
+use color_eyre::{Report, Result};
+use diff::ApiDiff;
+use export::inventory::{OBJECTS_INV_NAME, render_objects_inv};
+use export::json::{read_json_export, render_json};
+use export::llms::{LLMS_FULL_TXT_NAME, LLMS_TXT_NAME, render_llms_full_txt, render_llms_txt};
+use export::report::{BuildReport, Diagnostic};
+use export::search::{SEARCH_INDEX_NAME, SEARCH_WIDGET, SEARCH_WIDGET_NAME, render_search_index};
 use fs::get_python_prefix;
-use parsing::module::extract_module_documentation;
-use parsing::utils::parse_python_file;
+use parsing::module::{extract_located_module_documentation, extract_module_documentation};
+use parsing::utils::{parse_python_file, parse_python_str};
+use render::diagrams::add_diagrams;
+use render::formats::html::render_html_site;
+use render::imports::{
+    IMPORT_GRAPH_DOT_NAME, IMPORT_GRAPH_MERMAID_NAME, ImportGraph, ImportGraphConfig,
+};
+use render::layout::{
+    Layout, ModulePages, module_page, object_page_path, render_module_pages, render_single_page,
+};
+use render::metadata::summarize;
+use render::source::SourceLinker;
 use render::translate_filename;
+use watch::SourceChanges;
 
-pub fn render_docs<R: Renderer>(
-    pkg_path: &Path,
-    out_path: &Path,
-    skip_private: bool,
-    skip_undoc: bool,
-    exclude: Vec<PathBuf>,
-    renderer: &R,
-) -> Result<Vec<PathBuf>> {
-    let root = pkg_path;
-    let root_pkg_path = get_module_name(pkg_path)?;
-    let mut errored = vec![];
+/// What a build wrote and which modules it could not process
+#[derive(Debug, Default)]
+pub struct BuildOutput {
+    /// the documentation the pages were rendered from, for the exports to share
+    pub package: PackageDocumentation,
+    /// every file the build wrote
+    pub written: Vec<PathBuf>,
+    /// the modules that could not be processed, with the error each of them failed with
+    pub failures: Vec<(PathBuf, Report)>,
+    /// what was documented, left out and warned about, without the written files
+    report: BuildReport,
+}
+
+impl BuildOutput {
+    fn new(
+        package: PackageDocumentation,
+        pkg_path: &Path,
+        pkg_index: &PackageIndex,
+        failures: Vec<(PathBuf, Report)>,
+    ) -> Self {
+        let report = BuildReport::new(&package, pkg_path, &pkg_index.skipped, &failures, vec![]);
+        Self {
+            package,
+            written: vec![],
+            failures,
+            report,
+        }
+    }
+
+    /// Counts the module at `path` as failed, e.g. because its page could not be rendered
+    fn add_failure(&mut self, path: PathBuf, error: Report) {
+        tracing::debug!("could not render {}: {error}", path.display());
+        self.report.modules.retain(|module| module != &path);
+        self.report
+            .diagnostics
+            .push(Diagnostic::from_failure(&path, &error));
+        self.failures.push((path, error));
+    }
+
+    /// The build report, see `export::report`, with every written file as an output
+    pub fn build_report(&self) -> BuildReport {
+        BuildReport {
+            outputs: self.written.clone(),
+            ..self.report.clone()
+        }
+    }
+}
+
+/// Renders a page per module of the package `config` points at, see `render::render_module`
+pub fn render_docs(config: &Config, source_linker: Option<&SourceLinker>) -> Result<BuildOutput> {
+    render_pages(config, source_linker, false)
+}
+
+/// Like `render_docs` but every class and function gets its own page next to the page of
+/// its module, see `render::layout::render_module_pages`
+pub fn render_per_object_docs(
+    config: &Config,
+    source_linker: Option<&SourceLinker>,
+) -> Result<BuildOutput> {
+    render_pages(config, source_linker, true)
+}
+
+fn render_pages(
+    config: &Config,
+    source_linker: Option<&SourceLinker>,
+    per_object: bool,
+) -> Result<BuildOutput> {
+    let pkg_path = &config.pkg_path;
+    let out_path = &config.output_dir;
+    let renderer = &config.renderer;
 
     tracing::info!("indexing package at {}", &pkg_path.display());
-    let pkg_index = walk_package(pkg_path, skip_private, exclude)?;
+    let pkg_index = walk_package(pkg_path, config.skip_private, config.exclude.clone())?;
 
     tracing::info!("Creating directories");
 
-    for sub_pkg in pkg_index.package_paths {
+    for sub_pkg in &pkg_index.package_paths {
         tracing::debug!("Creating directory: {}", &sub_pkg.display());
-        let rel_write_path = sub_pkg.strip_prefix(root)?;
+        let rel_write_path = sub_pkg.strip_prefix(pkg_path)?;
         let full_write_path = out_path.join(rel_write_path);
         create_dir_all(&full_write_path)?;
     }
     tracing::info!("done creating directories");
 
-    for sub_module in pkg_index.module_paths {
-        tracing::info!("creating documentation for {}", &sub_module.display());
-        let rel_write_path = sub_module.strip_prefix(root)?;
-        let rel_python_path = Path::new(&root_pkg_path).join(rel_write_path);
-        let full_write_path = out_path.join(rel_write_path);
-        let prefix = get_python_prefix(&rel_python_path)?;
-        let parsed = parse_python_file(&sub_module);
-        match parsed {
-            Ok(contents) => {
-                tracing::debug!("correctly parsed file {}", &sub_module.display());
-                tracing::debug!("extracting documentation...");
-                let module_name = get_module_name(&sub_module).ok();
-                let documentation = {
-                    // extra scope is so docs doesn't have to be mutable for the
-                    // whole rest of the function
-                    let mut tmp_docs = extract_module_documentation(
-                        &contents,
-                        module_name,
-                        prefix,
-                        skip_private,
-                        skip_undoc,
-                    );
-                    if sub_module.ends_with("__init__.py") {
-                        if let Some(dir) = sub_module.parent() {
-                            tmp_docs.with_sub_modules(
-                                pkg_index.sub_module_index.get(&dir.to_path_buf()),
-                            );
-                        }
+    // diagrams need every module of the package before any of them can be rendered
+    let (mut package, failures) = document_package_with_failures(
+        pkg_path,
+        &pkg_index,
+        config.skip_private,
+        config.skip_undoc,
+        source_linker,
+    )?;
+    add_diagrams(&mut package, &config.diagrams);
+
+    let mut output = BuildOutput::new(package, pkg_path, &pkg_index, failures);
+    for documentation in output.package.modules.clone() {
+        let Some(rel_path) = documentation.relative_path() else {
+            tracing::warn!("skipping module without a known path");
+            continue;
+        };
+        match module_pages(documentation, renderer, per_object) {
+            Ok(pages) => {
+                output
+                    .written
+                    .extend(write_module_pages(&rel_path, pages, out_path, renderer)?);
+            }
+            Err(e) => output.add_failure(pkg_path.join(rel_path), e),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Renders the page of a module, and the pages of its objects if `per_object`
+fn module_pages<R: Renderer>(
+    documentation: ModuleDocumentation,
+    renderer: &R,
+    per_object: bool,
+) -> Result<ModulePages> {
+    tracing::debug!("rendering documentation...");
+    if per_object {
+        Ok(render_module_pages(documentation, renderer))
+    } else {
+        Ok(ModulePages {
+            index: renderer.render_module_page(documentation)?,
+            objects: vec![],
+        })
+    }
+}
+
+/// Writes the pages of the module at `rel_path`, relative to the root package, to where
+/// they go in `out_path`. Returns the written pages.
+fn write_module_pages<R: Renderer>(
+    rel_path: &Path,
+    pages: ModulePages,
+    out_path: &Path,
+    renderer: &R,
+) -> Result<Vec<PathBuf>> {
+    let new_write_path = translate_filename(&out_path.join(rel_path), renderer);
+    tracing::debug!(
+        "writing rendered documentation too {}",
+        &new_write_path.display()
+    );
+    let mut written = vec![write_file(new_write_path.clone(), &pages.index)?];
+
+    // the directories for the pages of objects aren't in the package index,
+    // they only exist if the module has any classes or functions
+    let page_dir = new_write_path.parent().unwrap_or(out_path);
+    for (rel_path, object_page) in pages.objects {
+        let object_write_path = page_dir.join(rel_path);
+        if let Some(dir) = object_write_path.parent() {
+            create_dir_all(dir)?;
+        }
+        written.push(write_file(object_write_path, &object_page)?);
+    }
+    Ok(written)
+}
+
+/// Writes `contents` to `path`, returning `path` for the files a build wrote
+fn write_file(path: PathBuf, contents: impl AsRef<[u8]>) -> Result<PathBuf> {
+    let mut file = File::create(&path)?;
+    file.write_all(contents.as_ref())?;
+    Ok(path)
+}
+
+/// Rewrites only the pages `changes` affect: those of the changed and added modules, and
+/// those of the packages listing them. Removed modules lose their page, and in the per object
+/// layout the pages of their objects. Anything drawn from the whole package, like diagrams,
+/// is not updated.
+pub fn render_changed_docs(
+    config: &Config,
+    source_linker: Option<&SourceLinker>,
+    changes: &SourceChanges,
+) -> Result<Vec<(PathBuf, Report)>> {
+    let pkg_path = &config.pkg_path;
+    let out_path = &config.output_dir;
+    let renderer = &config.renderer;
+    let per_object = config.layout == Layout::PerObject;
+    let pkg_index = walk_package(pkg_path, config.skip_private, config.exclude.clone())?;
+    let root_pkg_path = get_module_name(pkg_path)?;
+
+    for removed in &changes.removed {
+        let rel_write_path = removed.strip_prefix(pkg_path)?;
+        let write_path = translate_filename(&out_path.join(rel_write_path), renderer);
+        if write_path.is_file() {
+            tracing::info!("removing {}", write_path.display());
+            remove_file(write_path)?;
+        }
+        if per_object {
+            remove_object_pages(removed, pkg_path, out_path, renderer)?;
+        }
+    }
+
+    let mut affected = BTreeSet::new();
+    for changed in changes.modified.iter().chain(&changes.added) {
+        affected.insert(changed.clone());
+    }
+    // packages list the summaries of their children
+    for changed in changes
+        .modified
+        .iter()
+        .chain(&changes.added)
+        .chain(&changes.removed)
+    {
+        if let Some(parent) = parent_package_init(changed) {
+            affected.insert(parent);
+        }
+    }
+
+    let mut failures = vec![];
+    // anything excluded or private stays out, just like in a full build
+    for sub_module in affected
+        .into_iter()
+        .filter(|p| pkg_index.module_paths.contains(p))
+    {
+        tracing::info!("updating documentation of {}", &sub_module.display());
+        match document_module(
+            &sub_module,
+            pkg_path,
+            &root_pkg_path,
+            &pkg_index,
+            config.skip_private,
+            config.skip_undoc,
+            source_linker,
+        ) {
+            Ok(documentation) => match module_pages(documentation, renderer, per_object) {
+                Ok(pages) => {
+                    let rel_path = sub_module.strip_prefix(pkg_path)?;
+                    if let Some(dir) = rel_path.parent() {
+                        create_dir_all(out_path.join(dir))?;
                     }
-                    tmp_docs
-                };
-                tracing::debug!("rendering documentation...");
-                let rendered = render_module(documentation, &renderer);
-                let new_write_path = translate_filename(&full_write_path);
-                tracing::debug!(
-                    "writing rendered documentation too {}",
-                    &new_write_path.display()
-                );
-                let mut file = File::create(new_write_path)?;
-                file.write_all(rendered.as_bytes())?;
+                    write_module_pages(rel_path, pages, out_path, renderer)?;
+                }
+                Err(e) => {
+                    tracing::debug!("could not render {}: {e}", &sub_module.display());
+                    failures.push((sub_module, e));
+                }
+            },
+            Err(e) => {
+                tracing::debug!("could not process {}: {e}", &sub_module.display());
+                failures.push((sub_module, e));
             }
+        }
+    }
+
+    Ok(failures)
+}
+
+/// Removes the pages of the classes and functions of the `removed` module in the per object
+/// layout, see `render::layout::render_module_pages` for where they are
+fn remove_object_pages<R: Renderer>(
+    removed: &Path,
+    pkg_path: &Path,
+    out_path: &Path,
+    renderer: &R,
+) -> Result<()> {
+    let rel_path = removed.strip_prefix(pkg_path)?;
+    let (source_dir, object_dir) = if removed.ends_with("__init__.py") {
+        let rel_dir = rel_path.parent().unwrap_or(Path::new(""));
+        (pkg_path.join(rel_dir), out_path.join(rel_dir))
+    } else {
+        let rel_dir = rel_path.with_extension("");
+        (pkg_path.join(&rel_dir), out_path.join(rel_dir))
+    };
+    // a package keeps the pages of its sub modules in the same directory, they are only all
+    // stale if the package is gone. The same goes for a module with a package of its name.
+    if source_dir.exists() || object_dir == out_path || !object_dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in read_dir(&object_dir)? {
+        let page = entry?.path();
+        if page.is_file() && page.extension() == Some(renderer.file_extension().as_ref()) {
+            tracing::info!("removing {}", page.display());
+            remove_file(&page)?;
+        }
+    }
+    if read_dir(&object_dir)?.next().is_none() {
+        remove_dir(&object_dir)?;
+    }
+    Ok(())
+}
+
+/// the `__init__.py` of the package containing `module`, for a package that is the package
+/// one level up
+fn parent_package_init(module: &Path) -> Option<PathBuf> {
+    let dir = module.parent()?;
+    let pkg_dir = if module.ends_with("__init__.py") {
+        dir.parent()?
+    } else {
+        dir
+    };
+    Some(pkg_dir.join("__init__.py"))
+}
+
+/// Extracts the documentation of every module in the package without rendering it.
+/// Modules are sorted by their path on disk so the result is stable across runs.
+/// Returns the documentation together with the paths of all modules that could not be parsed.
+pub fn extract_package_documentation(
+    pkg_path: &Path,
+    skip_private: bool,
+    skip_undoc: bool,
+    exclude: Vec<PathBuf>,
+    source_linker: Option<&SourceLinker>,
+) -> Result<(PackageDocumentation, Vec<PathBuf>)> {
+    tracing::info!("indexing package at {}", &pkg_path.display());
+    let pkg_index = walk_package(pkg_path, skip_private, exclude)?;
+    document_package(
+        pkg_path,
+        &pkg_index,
+        skip_private,
+        skip_undoc,
+        source_linker,
+    )
+}
+
+/// Like `extract_package_documentation`, keeping the error each module failed with
+pub fn extract_package_documentation_with_failures(
+    pkg_path: &Path,
+    skip_private: bool,
+    skip_undoc: bool,
+    exclude: Vec<PathBuf>,
+    source_linker: Option<&SourceLinker>,
+) -> Result<(PackageDocumentation, Vec<(PathBuf, Report)>)> {
+    tracing::info!("indexing package at {}", &pkg_path.display());
+    let pkg_index = walk_package(pkg_path, skip_private, exclude)?;
+    document_package_with_failures(
+        pkg_path,
+        &pkg_index,
+        skip_private,
+        skip_undoc,
+        source_linker,
+    )
+}
+
+fn document_package(
+    pkg_path: &Path,
+    pkg_index: &PackageIndex,
+    skip_private: bool,
+    skip_undoc: bool,
+    source_linker: Option<&SourceLinker>,
+) -> Result<(PackageDocumentation, Vec<PathBuf>)> {
+    let (package, failures) = document_package_with_failures(
+        pkg_path,
+        pkg_index,
+        skip_private,
+        skip_undoc,
+        source_linker,
+    )?;
+    Ok((
+        package,
+        failures.into_iter().map(|(path, _)| path).collect(),
+    ))
+}
+
+/// Like `document_package`, keeping the error each module failed with
+fn document_package_with_failures(
+    pkg_path: &Path,
+    pkg_index: &PackageIndex,
+    skip_private: bool,
+    skip_undoc: bool,
+    source_linker: Option<&SourceLinker>,
+) -> Result<(PackageDocumentation, Vec<(PathBuf, Report)>)> {
+    let root_pkg_path = get_module_name(pkg_path)?;
+    let mut failures = vec![];
+    let mut modules = vec![];
+
+    let mut module_paths = pkg_index.module_paths.clone();
+    module_paths.sort();
+
+    for sub_module in module_paths {
+        tracing::info!("extracting documentation for {}", &sub_module.display());
+        match document_module(
+            &sub_module,
+            pkg_path,
+            &root_pkg_path,
+            pkg_index,
+            skip_private,
+            skip_undoc,
+            source_linker,
+        ) {
+            Ok(documentation) => modules.push(documentation),
+            // the caller decides how to report the failures
             Err(e) => {
-                tracing::error!(
-                    "The following error occurred while processing {}: {}",
-                    &sub_module.display(),
-                    e
-                );
-                errored.push(sub_module);
+                tracing::debug!("could not process {}: {e}", &sub_module.display());
+                failures.push((sub_module, e));
             }
         }
     }
 
-    Ok(errored)
+    Ok((
+        PackageDocumentation {
+            name: root_pkg_path,
+            modules,
+        },
+        failures,
+    ))
+}
+
+/// Renders the whole package into a single page `out_path/<package name>.<ext>`
+/// see `render::layout` for how modules are ordered and nested
+pub fn render_single_page_docs(
+    config: &Config,
+    source_linker: Option<&SourceLinker>,
+) -> Result<BuildOutput> {
+    let pkg_path = &config.pkg_path;
+    let out_path = &config.output_dir;
+    let renderer = &config.renderer;
+
+    tracing::info!("indexing package at {}", &pkg_path.display());
+    let pkg_index = walk_package(pkg_path, config.skip_private, config.exclude.clone())?;
+    let (mut package, failures) = document_package_with_failures(
+        pkg_path,
+        &pkg_index,
+        config.skip_private,
+        config.skip_undoc,
+        source_linker,
+    )?;
+    add_diagrams(&mut package, &config.diagrams);
+
+    create_dir_all(out_path)?;
+    let write_path = out_path.join(format!("{}.{}", package.name, renderer.file_extension()));
+    tracing::info!("writing single page to {}", &write_path.display());
+    let rendered = render_single_page(&package, renderer);
+    let mut output = BuildOutput::new(package, pkg_path, &pkg_index, failures);
+    output.written.push(write_file(write_path, rendered)?);
+
+    Ok(output)
+}
+
+/// Renders the package as a standalone static HTML site that needs no SSG or network access
+/// see `render::formats::html` for the layout
+pub fn render_html_docs(
+    config: &Config,
+    source_linker: Option<&SourceLinker>,
+) -> Result<BuildOutput> {
+    let pkg_path = &config.pkg_path;
+    let out_path = &config.output_dir;
+
+    tracing::info!("indexing package at {}", &pkg_path.display());
+    let pkg_index = walk_package(pkg_path, config.skip_private, config.exclude.clone())?;
+    let (mut package, failures) = document_package_with_failures(
+        pkg_path,
+        &pkg_index,
+        config.skip_private,
+        config.skip_undoc,
+        source_linker,
+    )?;
+    add_diagrams(&mut package, &config.diagrams);
+
+    let written = render_html_site(&package, pkg_path, &pkg_index, out_path)?;
+    let mut output = BuildOutput::new(package, pkg_path, &pkg_index, failures);
+    output.written = written;
+
+    Ok(output)
+}
+
+/// Writes `objects.inv` to the output directory of `config`, linking to the pages a build
+/// with `config` writes there, so other projects can link to them with intersphinx
+pub fn export_objects_inv(package: &PackageDocumentation, config: &Config) -> Result<Vec<PathBuf>> {
+    let out_path = &config.output_dir;
+    create_dir_all(out_path)?;
+    tracing::info!("writing {OBJECTS_INV_NAME}");
+    let version = config.project_version.as_deref().unwrap_or_default();
+    Ok(vec![write_file(
+        out_path.join(OBJECTS_INV_NAME),
+        render_objects_inv(package, version, config.layout, &config.renderer)?,
+    )?])
+}
+
+/// Writes the documentation of the whole package to `out_file` as a single JSON document
+/// see `export::json` for the format. Returns the written file.
+pub fn export_json(package: &PackageDocumentation, out_file: &Path) -> Result<Vec<PathBuf>> {
+    if let Some(parent) = out_file.parent() {
+        create_dir_all(parent)?;
+    }
+
+    tracing::info!("writing json export to {}", &out_file.display());
+    Ok(vec![write_file(
+        out_file.to_path_buf(),
+        render_json(package.clone())?,
+    )?])
+}
+
+/// Writes `llms.txt` and `llms-full.txt` to the output directory of `config`, linking to
+/// the pages a build with `config` writes there, see `export::llms` for the format
+pub fn export_llms_txt(package: &PackageDocumentation, config: &Config) -> Result<Vec<PathBuf>> {
+    let out_path = &config.output_dir;
+    create_dir_all(out_path)?;
+    tracing::info!("writing {LLMS_TXT_NAME} and {LLMS_FULL_TXT_NAME}");
+    Ok(vec![
+        write_file(
+            out_path.join(LLMS_TXT_NAME),
+            render_llms_txt(package, config.layout, &config.renderer),
+        )?,
+        write_file(
+            out_path.join(LLMS_FULL_TXT_NAME),
+            render_llms_full_txt(package),
+        )?,
+    ])
+}
+
+/// Writes the import graph of the package to `out_path` as DOT and as mermaid,
+/// see `render::imports`
+pub fn export_import_graph(
+    package: &PackageDocumentation,
+    out_path: &Path,
+    config: &ImportGraphConfig,
+) -> Result<Vec<PathBuf>> {
+    let graph = ImportGraph::new(package, config);
+
+    create_dir_all(out_path)?;
+    tracing::info!("writing {IMPORT_GRAPH_DOT_NAME} and {IMPORT_GRAPH_MERMAID_NAME}");
+    Ok(vec![
+        write_file(out_path.join(IMPORT_GRAPH_DOT_NAME), graph.to_dot())?,
+        write_file(out_path.join(IMPORT_GRAPH_MERMAID_NAME), graph.to_mermaid())?,
+    ])
+}
+
+/// Writes the search index and the search widget to the output directory of `config`,
+/// linking to the pages a build with `config` writes there, see `export::search`
+pub fn export_search_index(
+    package: &PackageDocumentation,
+    config: &Config,
+) -> Result<Vec<PathBuf>> {
+    let out_path = &config.output_dir;
+    create_dir_all(out_path)?;
+    tracing::info!("writing {SEARCH_INDEX_NAME} and {SEARCH_WIDGET_NAME}");
+    Ok(vec![
+        write_file(
+            out_path.join(SEARCH_INDEX_NAME),
+            render_search_index(package, config.layout, &config.renderer)?,
+        )?,
+        write_file(out_path.join(SEARCH_WIDGET_NAME), SEARCH_WIDGET)?,
+    ])
+}
+
+/// The public API of a package, read from its source or, for a `.json` file, from a JSON
+/// export of it. An export only has what was documented when it was written, so exports
+/// made with `skip_undoc` are missing the undocumented objects.
+pub fn load_api(
+    path: &Path,
+    exclude: Vec<PathBuf>,
+) -> Result<(PackageDocumentation, Vec<PathBuf>)> {
+    if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+        tracing::info!("reading json export {}", path.display());
+        return Ok((read_json_export(path)?.package, vec![]));
+    }
+    extract_package_documentation(path, true, false, exclude, None)
+}
+
+/// What changed in the public API from the package or export at `old` to the one at `new`,
+/// see `diff`
+pub fn diff_api(old: &Path, new: &Path, exclude: Vec<PathBuf>) -> Result<(ApiDiff, Vec<PathBuf>)> {
+    let (old_package, mut errored) = load_api(old, exclude.clone())?;
+    let (new_package, new_errored) = load_api(new, exclude)?;
+    errored.extend(new_errored);
+    Ok((ApiDiff::new(&old_package, &new_package), errored))
+}
+
+/// Removes the pages a build with `config` writes to its output directory, along with the
+/// `exports` next to them, e.g. `llms.txt`. Directories left empty are removed as well,
+/// anything else in the output directory is left alone. Returns the removed files.
+pub fn clean_docs(config: &Config, exports: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let out_path = &config.output_dir;
+    let (package, _) = extract_package_documentation(
+        &config.pkg_path,
+        config.skip_private,
+        config.skip_undoc,
+        config.exclude.clone(),
+        None,
+    )?;
+
+    let mut pages = output_pages(&package, out_path, config.layout, &config.renderer);
+    pages.extend(exports.iter().cloned());
+
+    let mut removed = vec![];
+    for page in pages.into_iter().filter(|p| p.is_file()) {
+        tracing::info!("removing {}", page.display());
+        remove_file(&page)?;
+        remove_empty_dirs(&page, out_path)?;
+        removed.push(page);
+    }
+
+    Ok(removed)
+}
+
+/// The pages `renderer` writes for `package` to `out_path` in `layout`
+fn output_pages<R: Renderer>(
+    package: &PackageDocumentation,
+    out_path: &Path,
+    layout: Layout,
+    renderer: &R,
+) -> BTreeSet<PathBuf> {
+    let mut pages = BTreeSet::new();
+    for module in &package.modules {
+        let Some(page) = module_page(module, &package.name, layout, renderer) else {
+            continue;
+        };
+        if layout == Layout::PerObject {
+            let page_dir = page.parent().unwrap_or(Path::new("")).to_path_buf();
+            let names = module
+                .classes
+                .iter()
+                .map(|c| &c.name)
+                .chain(module.functions.iter().map(|f| &f.name));
+            for name in names {
+                if let Some(object_page) = object_page_path(module, name, renderer) {
+                    pages.insert(out_path.join(&page_dir).join(object_page));
+                }
+            }
+        }
+        pages.insert(out_path.join(page));
+    }
+    pages
+}
+
+/// Removes the directories above `path` that are empty, up to and including `out_path`
+fn remove_empty_dirs(path: &Path, out_path: &Path) -> Result<()> {
+    for dir in path.ancestors().skip(1) {
+        if !dir.starts_with(out_path) || read_dir(dir)?.next().is_some() {
+            break;
+        }
+        remove_dir(dir)?;
+    }
+    Ok(())
+}
+
+fn document_module(
+    sub_module: &Path,
+    root: &Path,
+    root_pkg_path: &str,
+    pkg_index: &PackageIndex,
+    skip_private: bool,
+    skip_undoc: bool,
+    source_linker: Option<&SourceLinker>,
+) -> Result<ModuleDocumentation> {
+    let rel_write_path = sub_module.strip_prefix(root)?;
+    let rel_python_path = Path::new(root_pkg_path).join(rel_write_path);
+    let prefix = get_python_prefix(&rel_python_path)?;
+    let source = read_to_string(sub_module)?;
+    let contents = parse_python_str(&source)?;
+    tracing::debug!("correctly parsed file {}", &sub_module.display());
+    tracing::debug!("extracting documentation...");
+    let module_name = get_module_name(sub_module).ok();
+    let mut documentation = extract_located_module_documentation(
+        &contents,
+        &source,
+        module_name,
+        prefix,
+        skip_private,
+        skip_undoc,
+    );
+    documentation.path = Some(rel_python_path);
+    if let Some(linker) = source_linker {
+        linker.link_module(&mut documentation);
+    }
+    if sub_module.ends_with("__init__.py") {
+        if let Some(dir) = sub_module.parent() {
+            documentation.with_sub_modules(pkg_index.sub_module_index.get(dir).map(|subs| {
+                subs.iter()
+                    .map(|rel_path| reference_sub_module(dir, rel_path))
+                    .collect()
+            }));
+        }
+    }
+    Ok(documentation)
+}
+
+/// `rel_path` is relative to `pkg_dir`, as stored in the `sub_module_index`
+fn reference_sub_module(pkg_dir: &Path, rel_path: &Path) -> ModuleReference {
+    let full_path = pkg_dir.join(rel_path);
+    // a child that fails to parse is reported when it is documented itself
+    let docstring = parse_python_file(&full_path).ok().and_then(|contents| {
+        extract_module_documentation(&contents, None, None, false, false).docstring
+    });
+    ModuleReference {
+        name: get_module_name(rel_path).unwrap_or_else(|_| rel_path.display().to_string()),
+        path: rel_path.to_path_buf(),
+        summary: summarize(docstring.as_deref()),
+        is_package: rel_path.ends_with("__init__.py"),
+    }
 }
 
 #[cfg(test)]
@@ -103,9 +735,12 @@ mod test {
 
     use std::path::{Path, PathBuf};
 
-    use crate::render::formats::md::MdRenderer;
-
-    use crate::render_docs;
+    use crate::config::ConfigBuilder;
+    use crate::render::layout::Layout;
+    use crate::watch::SourceChanges;
+    use crate::{
+        clean_docs, export_objects_inv, render_changed_docs, render_docs, render_per_object_docs,
+    };
 
     use pretty_assertions::assert_eq;
     use std::collections::HashSet;
@@ -115,6 +750,15 @@ mod test {
     use color_eyre::eyre::{Result, WrapErr, eyre};
     use walkdir::WalkDir;
 
+    /// a markdown build of everything in `pkg_path` to `out_path`
+    fn config(pkg_path: &Path, out_path: &Path) -> ConfigBuilder {
+        ConfigBuilder::default()
+            .with_pkg_path(Some(pkg_path.to_path_buf()))
+            .with_output_dir(Some(out_path.to_path_buf()))
+            .with_skip_undoc(Some(false))
+            .with_skip_private(Some(false))
+    }
+
     /// Asserts that two directory trees are identical in structure and content.
     /// Reports all differences including missing files and content mismatches.
     pub fn assert_dir_trees_equal<P: AsRef<Path>>(dir1: P, dir2: P) {
@@ -194,13 +838,18 @@ fn compare_files(path1: &Path, path2: &Path) -> io::Result<()> {
         let mut file1 = fs::File::open(path1)?;
         let mut file2 = fs::File::open(path2)?;
 
-        let mut buf1 = String::new();
-        let mut buf2 = String::new();
+        let mut buf1 = vec![];
+        let mut buf2 = vec![];
 
-        file1.read_to_string(&mut buf1)?;
-        file2.read_to_string(&mut buf2)?;
+        file1.read_to_end(&mut buf1)?;
+        file2.read_to_end(&mut buf2)?;
 
-        assert_eq!(buf1, buf2,);
+        // show the difference as text, not every file is though, e.g. `objects.inv`
+        assert_eq!(
+            String::from_utf8_lossy(&buf1),
+            String::from_utf8_lossy(&buf2)
+        );
+        assert_eq!(buf1, buf2);
 
         Ok(())
     }
@@ -212,15 +861,13 @@ fn render_test_pkg_docs_full() -> Result<()> {
         let expected_result_dir = PathBuf::from("tests/rendered_full");
 
         render_docs(
-            &test_pkg_dir,
-            temp_dir.path(),
-            false,
-            false,
-            vec![
-                PathBuf::from("test_pkg/excluded_file.py"),
-                PathBuf::from("test_pkg/excluded_module"),
-            ],
-            &MdRenderer::new(),
+            &config(&test_pkg_dir, temp_dir.path())
+                .with_exclude(Some(vec![
+                    PathBuf::from("test_pkg/excluded_file.py"),
+                    PathBuf::from("test_pkg/excluded_module"),
+                ]))
+                .build()?,
+            None,
         )?;
 
         assert_dir_trees_equal(temp_dir.path(), &expected_result_dir);
@@ -233,35 +880,194 @@ fn render_test_pkg_docs_no_private_no_undoc() -> Result<()> {
         let test_pkg_dir = PathBuf::from("tests/test_pkg");
         let expected_result_dir = PathBuf::from("tests/rendered_no_private");
 
-        render_docs(
-            &test_pkg_dir,
-            temp_dir.path(),
-            true,
-            true,
-            vec![
+        let config = config(&test_pkg_dir, temp_dir.path())
+            .with_skip_private(Some(true))
+            .with_skip_undoc(Some(true))
+            .with_exclude(Some(vec![
                 PathBuf::from("test_pkg/excluded_file.py"),
                 PathBuf::from("test_pkg/excluded_module"),
-            ],
-            &MdRenderer::new(),
-        )?;
+            ]))
+            .build()?;
+        let output = render_docs(&config, None)?;
+        export_objects_inv(&output.package, &config)?;
 
         assert_dir_trees_equal(temp_dir.path(), &expected_result_dir);
 
         Ok(())
     }
     #[test]
-    fn render_test_pkg_docs_exit_on_err() -> Result<()> {
+    fn render_test_pkg_docs_per_object() -> Result<()> {
         let temp_dir = assert_fs::TempDir::new()?;
         let test_pkg_dir = PathBuf::from("tests/test_pkg");
 
-        render_docs(
-            &test_pkg_dir,
-            temp_dir.path(),
-            false,
-            false,
-            vec![],
-            &MdRenderer::new(),
+        let output = render_per_object_docs(
+            &config(&test_pkg_dir, temp_dir.path())
+                .with_skip_private(Some(true))
+                .with_skip_undoc(Some(true))
+                .with_exclude(Some(vec![
+                    PathBuf::from("test_pkg/excluded_file.py"),
+                    PathBuf::from("test_pkg/excluded_module"),
+                ]))
+                .with_layout(Some(Layout::PerObject))
+                .build()?,
+            None,
+        )?;
+
+        let greeter = fs::read_to_string(temp_dir.path().join("bar/Greeter.md"))?;
+        assert!(greeter.starts_with("# test_pkg.bar.Greeter\n"));
+        assert!(temp_dir.path().join("bar/greet.md").is_file());
+        assert!(
+            output
+                .written
+                .contains(&temp_dir.path().join("bar/Greeter.md"))
+        );
+        assert!(output.written.iter().all(|path| path.is_file()));
+        assert_eq!(output.build_report().outputs, output.written);
+        assert!(temp_dir.path().join("sub1/sub2/one").is_dir());
+
+        let index = fs::read_to_string(temp_dir.path().join("bar.md"))?;
+        assert!(index.contains("[Greeter](bar/Greeter.md)"));
+        assert!(!index.contains("## test_pkg.bar.Greeter"));
+        Ok(())
+    }
+
+    #[test]
+    fn render_docs_counts_failing_templates_as_failures() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let templates_dir = assert_fs::TempDir::new()?;
+        fs::write(
+            templates_dir.path().join("function.jinja"),
+            "{% for line in 42 %}{{ line }}{% endfor %}",
+        )?;
+        let output = render_docs(
+            &config(Path::new("tests/test_pkg"), temp_dir.path())
+                .with_skip_private(Some(true))
+                .with_skip_undoc(Some(true))
+                .with_templates_dir(Some(templates_dir.path().to_path_buf()))
+                .build()?,
+            None,
+        )?;
+
+        let bar = PathBuf::from("tests/test_pkg/bar.py");
+        assert!(output.failures.iter().any(|(path, _)| path == &bar));
+        assert!(!output.build_report().modules.contains(&bar));
+        assert!(!temp_dir.path().join("bar.md").exists());
+        // modules without functions don't use the template
+        assert!(temp_dir.path().join("_index.md").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn render_changed_docs_updates_affected_pages() -> Result<()> {
+        let src_dir = assert_fs::TempDir::new()?;
+        let out_dir = assert_fs::TempDir::new()?;
+        let pkg_dir = src_dir.path().join("pkg");
+        fs::create_dir(&pkg_dir)?;
+        fs::write(pkg_dir.join("__init__.py"), "\"\"\"The package.\"\"\"\n")?;
+        fs::write(pkg_dir.join("old.py"), "\"\"\"Going away.\"\"\"\n")?;
+        fs::write(pkg_dir.join("same.py"), "\"\"\"Untouched.\"\"\"\n")?;
+        render_docs(&config(&pkg_dir, out_dir.path()).build()?, None)?;
+        // the untouched page is left alone
+        fs::write(out_dir.path().join("same.md"), "stale")?;
+
+        fs::remove_file(pkg_dir.join("old.py"))?;
+        fs::write(pkg_dir.join("new.py"), "\"\"\"Just arrived.\"\"\"\n")?;
+        let errored = render_changed_docs(
+            &config(&pkg_dir, out_dir.path()).build()?,
+            None,
+            &SourceChanges {
+                modified: vec![],
+                added: vec![pkg_dir.join("new.py")],
+                removed: vec![pkg_dir.join("old.py")],
+            },
         )?;
+        assert!(errored.is_empty());
+
+        assert!(!out_dir.path().join("old.md").exists());
+        assert!(out_dir.path().join("new.md").is_file());
+        assert_eq!(fs::read_to_string(out_dir.path().join("same.md"))?, "stale");
+        let index = fs::read_to_string(out_dir.path().join("_index.md"))?;
+        assert!(index.contains("- [new](new.md): Just arrived."));
+        assert!(!index.contains("[old]"));
+        Ok(())
+    }
+
+    #[test]
+    fn render_changed_docs_removes_object_pages() -> Result<()> {
+        let src_dir = assert_fs::TempDir::new()?;
+        let out_dir = assert_fs::TempDir::new()?;
+        let pkg_dir = src_dir.path().join("pkg");
+        fs::create_dir(&pkg_dir)?;
+        fs::write(pkg_dir.join("__init__.py"), "\"\"\"The package.\"\"\"\n")?;
+        fs::write(
+            pkg_dir.join("old.py"),
+            "class Gone:\n    \"\"\"Going.\"\"\"\n\ndef gone():\n    \"\"\"Going.\"\"\"\n",
+        )?;
+        render_per_object_docs(
+            &config(&pkg_dir, out_dir.path())
+                .with_layout(Some(Layout::PerObject))
+                .build()?,
+            None,
+        )?;
+        assert!(out_dir.path().join("old/Gone.md").is_file());
+
+        fs::remove_file(pkg_dir.join("old.py"))?;
+        render_changed_docs(
+            &config(&pkg_dir, out_dir.path())
+                .with_layout(Some(Layout::PerObject))
+                .build()?,
+            None,
+            &SourceChanges {
+                modified: vec![],
+                added: vec![],
+                removed: vec![pkg_dir.join("old.py")],
+            },
+        )?;
+
+        assert!(!out_dir.path().join("old.md").exists());
+        assert!(!out_dir.path().join("old").exists());
+        assert!(out_dir.path().join("_index.md").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn clean_docs_removes_only_rendered_pages() -> Result<()> {
+        let out_dir = assert_fs::TempDir::new()?;
+        let out_path = out_dir.path().join("api");
+        let test_pkg_dir = PathBuf::from("tests/test_pkg");
+        render_per_object_docs(
+            &config(&test_pkg_dir, &out_path)
+                .with_layout(Some(Layout::PerObject))
+                .build()?,
+            None,
+        )?;
+        fs::write(out_path.join("llms.txt"), "")?;
+        fs::write(out_path.join("notes.md"), "written by hand")?;
+
+        let removed = clean_docs(
+            &config(&test_pkg_dir, &out_path)
+                .with_layout(Some(Layout::PerObject))
+                .build()?,
+            &[out_path.join("llms.txt"), out_path.join("missing.json")],
+        )?;
+        assert!(removed.contains(&out_path.join("llms.txt")));
+        assert!(!removed.contains(&out_path.join("missing.json")));
+
+        let left: Vec<PathBuf> = WalkDir::new(&out_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .collect();
+        assert_eq!(left, vec![out_path.clone(), out_path.join("notes.md")]);
+        Ok(())
+    }
+
+    #[test]
+    fn render_test_pkg_docs_exit_on_err() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let test_pkg_dir = PathBuf::from("tests/test_pkg");
+
+        render_docs(&config(&test_pkg_dir, temp_dir.path()).build()?, None)?;
 
         Ok(())
     }
@@ -0,0 +1,420 @@
+//! Checks that docstrings agree with the signatures they document: every parameter is
+//! documented, nothing else is, annotated return values are described and the types given
+//! in the docstring match the annotations. Docstrings without any sections are prose and
+//! are left alone, see `parsing::docstring`.
+
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+};
+
+use lazy_regex::regex_replace_all;
+use serde::Serialize;
+
+use crate::{
+    parsing::{
+        class::ClassDocumentation,
+        docstring::{StructuredDocstring, parse_docstring, strip_optional},
+        function::{FunctionDocumentation, Parameter},
+        package::PackageDocumentation,
+    },
+    render::{metadata::PageMetadata, qualify},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    UndocumentedParameter,
+    UnknownParameter,
+    MissingReturns,
+    TypeMismatch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    /// the source file, relative to the current directory like the package path it was
+    /// found through
+    pub path: PathBuf,
+    pub line: usize,
+    /// the qualified name of the function, method or class
+    pub object: String,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.object,
+            self.message
+        )
+    }
+}
+
+/// Lints every function, method and class docstring of `package`, which was extracted
+/// from `pkg_path`
+pub fn lint_package(package: &PackageDocumentation, pkg_path: &Path) -> Vec<LintIssue> {
+    let mut issues = vec![];
+    for module in &package.modules {
+        let (Some(module_name), Some(rel_path)) =
+            (PageMetadata::from(module).title, module.relative_path())
+        else {
+            continue;
+        };
+        let path = pkg_path.join(rel_path);
+        let mut linter = Linter {
+            path: &path,
+            issues: &mut issues,
+        };
+        for function in &module.functions {
+            linter.function(
+                &qualify(Some(&module_name), &function.name),
+                function,
+                false,
+            );
+        }
+        for class in &module.classes {
+            linter.class(&qualify(Some(&module_name), &class.name), class);
+        }
+    }
+    issues
+}
+
+struct Linter<'a> {
+    path: &'a Path,
+    issues: &'a mut Vec<LintIssue>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, line: usize, object: &str, kind: LintKind, message: String) {
+        self.issues.push(LintIssue {
+            path: self.path.to_path_buf(),
+            line,
+            object: object.to_string(),
+            kind,
+            message,
+        });
+    }
+
+    fn class(&mut self, qualified_name: &str, class: &ClassDocumentation) {
+        let line = class.lines.map_or(1, |lines| lines.start);
+        let init = class.methods.iter().find(|m| m.name == "__init__");
+        // the parameters of `__init__` are often documented on the class instead
+        if let (Some(docstring), Some(init)) = (&class.docstring, init) {
+            let doc = parse_docstring(docstring);
+            if !doc.params.is_empty() {
+                self.params(line, qualified_name, &doc, own_params(init, true));
+            }
+        }
+        for method in &class.methods {
+            self.function(&qualify(Some(qualified_name), &method.name), method, true);
+        }
+    }
+
+    fn function(
+        &mut self,
+        qualified_name: &str,
+        function: &FunctionDocumentation,
+        is_method: bool,
+    ) {
+        let Some(docstring) = &function.docstring else {
+            return;
+        };
+        let doc = parse_docstring(docstring);
+        if !doc.has_sections {
+            return;
+        }
+        let line = function.lines.map_or(1, |lines| lines.start);
+        let is_init = is_method && function.name == "__init__";
+        // an `__init__` documented only on its class has no parameters section of its own
+        if !(is_init && doc.params.is_empty()) {
+            self.params(line, qualified_name, &doc, own_params(function, is_method));
+        }
+
+        let returns_value = function
+            .return_type
+            .as_deref()
+            .is_some_and(|r| !["None", "NoReturn", "Never"].contains(&r));
+        let is_property = function.decorators.iter().any(|d| d.ends_with("property"));
+        match &doc.returns {
+            None if returns_value && !is_init && !is_property => self.report(
+                line,
+                qualified_name,
+                LintKind::MissingReturns,
+                format!(
+                    "returns `{}` but the docstring has no returns section",
+                    function.return_type.as_deref().unwrap_or_default()
+                ),
+            ),
+            Some(returns) => {
+                if let Some((documented, annotation)) =
+                    mismatch(&returns.type_hint, &function.return_type)
+                {
+                    self.report(
+                        line,
+                        qualified_name,
+                        LintKind::TypeMismatch,
+                        format!(
+                            "the return value is documented as `{documented}` but annotated as `{annotation}`"
+                        ),
+                    );
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn params(
+        &mut self,
+        line: usize,
+        qualified_name: &str,
+        doc: &StructuredDocstring,
+        params: Vec<&Parameter>,
+    ) {
+        for param in &params {
+            let Some(documented) = doc.params.iter().find(|p| p.name == param.name) else {
+                self.report(
+                    line,
+                    qualified_name,
+                    LintKind::UndocumentedParameter,
+                    format!("parameter `{}` is not documented", param.name),
+                );
+                continue;
+            };
+            if let Some((documented, annotation)) =
+                mismatch(&documented.type_hint, &param.annotation)
+            {
+                self.report(
+                    line,
+                    qualified_name,
+                    LintKind::TypeMismatch,
+                    format!(
+                        "parameter `{}` is documented as `{documented}` but annotated as `{annotation}`",
+                        param.name
+                    ),
+                );
+            }
+        }
+        for documented in &doc.params {
+            if !params.iter().any(|p| p.name == documented.name) {
+                self.report(
+                    line,
+                    qualified_name,
+                    LintKind::UnknownParameter,
+                    format!("`{}` is documented but is not a parameter", documented.name),
+                );
+            }
+        }
+    }
+}
+
+/// the parameters a caller passes, without the `self` or `cls` of methods
+fn own_params(function: &FunctionDocumentation, is_method: bool) -> Vec<&Parameter> {
+    let is_static = function
+        .decorators
+        .iter()
+        .any(|d| d.ends_with("staticmethod"));
+    let skip = usize::from(is_method && !is_static && !function.args.is_empty());
+    function.args.iter().skip(skip).collect()
+}
+
+/// the documented type and the annotation, if both are there and they differ
+fn mismatch<'a>(
+    documented: &'a Option<String>,
+    annotation: &'a Option<String>,
+) -> Option<(&'a str, &'a str)> {
+    let (documented, annotation) = (documented.as_deref()?, annotation.as_deref()?);
+    (!types_match(documented, annotation)).then_some((documented, annotation))
+}
+
+/// `Optional[int]`, `int | None` and `typing.Optional[int]` are all the same, and a docstring
+/// saying `int` for a parameter defaulting to `None` is fine as well
+fn types_match(documented: &str, annotation: &str) -> bool {
+    let documented = union_members(&normalize_type(&strip_optional(documented)));
+    let annotation = union_members(&normalize_type(annotation));
+    if documented == annotation {
+        return true;
+    }
+    let mut without_none = annotation;
+    without_none.remove("None");
+    documented == without_none
+}
+
+fn normalize_type(type_hint: &str) -> String {
+    // sphinx cross references like :class:`~pkg.Foo`
+    let type_hint = regex_replace_all!(r":\w+:", type_hint, "");
+    let mut type_hint: String = type_hint
+        .chars()
+        .filter(|c| !c.is_whitespace() && !['`', '~'].contains(c))
+        .collect();
+    type_hint = type_hint.replace("typing.", "");
+    for (alias, builtin) in [
+        ("List[", "list["),
+        ("Dict[", "dict["),
+        ("Tuple[", "tuple["),
+        ("Set[", "set["),
+        ("FrozenSet[", "frozenset["),
+        ("Type[", "type["),
+    ] {
+        type_hint = type_hint.replace(alias, builtin);
+    }
+    if let Some(inner) = type_hint
+        .strip_prefix("Optional[")
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return format!("{inner}|None");
+    }
+    if let Some(inner) = type_hint
+        .strip_prefix("Union[")
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return split_top_level(inner, ',').join("|");
+    }
+    type_hint
+}
+
+fn union_members(type_hint: &str) -> BTreeSet<String> {
+    split_top_level(type_hint, '|').into_iter().collect()
+}
+
+/// splits on `separator` outside of brackets
+fn split_top_level(type_hint: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut depth = 0usize;
+    for c in type_hint.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+        if c == separator && depth == 0 {
+            parts.push(String::new());
+        } else if let Some(last) = parts.last_mut() {
+            last.push(c);
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{LintIssue, LintKind, lint_package, types_match};
+    use crate::parsing::{
+        module::extract_located_module_documentation, package::PackageDocumentation,
+        utils::parse_python_str,
+    };
+
+    fn lint(source: &str) -> Result<Vec<LintIssue>> {
+        let program = parse_python_str(source)?;
+        let mut module = extract_located_module_documentation(
+            &program,
+            source,
+            Some("api".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        module.path = Some("pkg/api.py".into());
+        let package = PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![module],
+        };
+        Ok(lint_package(&package, Path::new("src/pkg")))
+    }
+
+    #[test]
+    fn test_types_match() {
+        assert!(types_match("int, optional", "int"));
+        assert!(types_match("Optional[int]", "int | None"));
+        assert!(types_match("int", "typing.Optional[int]"));
+        assert!(types_match("List[str]", "list[str]"));
+        assert!(types_match(":class:`~pkg.Foo`", "pkg.Foo"));
+        assert!(types_match("Union[int, str]", "str | int"));
+        assert!(!types_match("int", "str"));
+        assert!(!types_match("int | None", "int"));
+    }
+
+    #[test]
+    fn test_lint_google_docstrings() -> Result<()> {
+        let issues = lint(
+            r#""""The api."""
+
+def send(url: str, retries: int = 3, *args) -> dict:
+    """Sends a request.
+
+    Args:
+        url (bytes): where to send it
+        timeout: gone since
+        *args: passed on
+    """
+
+def fine(url: str) -> int:
+    """Counts.
+
+    Args:
+        url: where
+
+    Returns:
+        int: the count
+    """
+
+def prose(url: str) -> int:
+    """Counts the url, no sections so nothing to check."""
+
+class Client:
+    """A client.
+
+    Args:
+        host (str): where to connect to
+    """
+
+    def __init__(self, host: str, port: int):
+        self.host = host
+
+    def parse(self, data: bytes) -> str:
+        """Parses.
+
+        Args:
+            data: the data
+
+        Returns:
+            bytes: the parsed data
+        """
+"#,
+        )?;
+        assert_eq!(
+            issues.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "src/pkg/api.py:3: pkg.api.send: parameter `url` is documented as `bytes` but annotated as `str`",
+                "src/pkg/api.py:3: pkg.api.send: parameter `retries` is not documented",
+                "src/pkg/api.py:3: pkg.api.send: `timeout` is documented but is not a parameter",
+                "src/pkg/api.py:3: pkg.api.send: returns `dict` but the docstring has no returns section",
+                "src/pkg/api.py:25: pkg.api.Client: parameter `port` is not documented",
+                "src/pkg/api.py:35: pkg.api.Client.parse: the return value is documented as `bytes` but annotated as `str`",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_sphinx_docstrings() -> Result<()> {
+        let issues = lint(
+            "def f(x: int) -> int:\n    \"\"\"F.\n\n    :param x: the x\n    :type x: str\n    \"\"\"\n",
+        )?;
+        assert_eq!(
+            issues.iter().map(|issue| issue.kind).collect::<Vec<_>>(),
+            vec![LintKind::TypeMismatch, LintKind::MissingReturns]
+        );
+        Ok(())
+    }
+}
@@ -1,10 +1,49 @@
-use color_eyre::eyre::Result;
-use snakedown::render_docs;
+use color_eyre::eyre::{Report, Result, eyre};
+use snakedown::{
+    BuildOutput, clean_docs,
+    config::{Config, ConfigBuilder},
+    diff_api,
+    export::{
+        coverage::CoverageReport,
+        inventory::OBJECTS_INV_NAME,
+        llms::{LLMS_FULL_TXT_NAME, LLMS_TXT_NAME},
+        report::{Diagnostic, ReportFormat},
+        search::{SEARCH_INDEX_NAME, SEARCH_WIDGET_NAME},
+    },
+    export_import_graph, export_json, export_llms_txt, export_objects_inv, export_search_index,
+    extract_package_documentation, extract_package_documentation_with_failures,
+    indexing::{
+        cache::{cached_inventories, cached_inventory_path, init_cache, inventory_objects},
+        fetch::cache_remote_objects_inv,
+    },
+    init::{detect_package, pyproject_name, scaffold_site},
+    lint::lint_package,
+    render::{
+        SSG,
+        diagrams::DiagramConfig,
+        formats::html::STYLESHEET_NAME,
+        imports::{IMPORT_GRAPH_DOT_NAME, IMPORT_GRAPH_MERMAID_NAME},
+        layout::Layout,
+        source::SourceLinker,
+    },
+    render_changed_docs, render_docs, render_html_docs, render_per_object_docs,
+    render_single_page_docs,
+    serve::{DevServer, watch_package},
+    watch::{next_changes, source_snapshot},
+};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 use tracing::subscriber::set_global_default;
 
 mod cli;
 
-use crate::cli::{Args, resolve_runtime_config};
+use crate::cli::{
+    Args, BuildArgs, Command, CoverageArgs, DiffArgs, InitArgs, InventoryArgs, InventoryCommand,
+    LintArgs, ServeArgs, WatchArgs, resolve_coverage_config, resolve_runtime_config,
+    resolve_serve_config,
+};
 use clap::Parser;
 
 #[allow(clippy::missing_errors_doc)]
@@ -19,15 +58,370 @@ async fn main() -> Result<()> {
 
     set_global_default(subscriber)?;
 
-    let config = resolve_runtime_config(args)?;
-    render_docs(
+    match args.into_command() {
+        Command::Build(build_args) => {
+            let report = build_args.build_report.clone();
+            let report_format = build_args.build_report_format;
+            let config = resolve_runtime_config(build_args)?;
+            let source_linker = load_source_linker(&config)?;
+            let output = build(&config, source_linker.as_ref())?;
+            if let Some(path) = report {
+                write_build_report(&output, &path, report_format)?;
+            }
+            report_failures(&output.failures, config.fail_on_error)
+        }
+        Command::Check(build_args) => check(build_args),
+        Command::Init(init_args) => init(&init_args),
+        Command::Clean(build_args) => clean(build_args),
+        Command::Coverage(coverage_args) => coverage(coverage_args),
+        Command::Lint(lint_args) => lint(lint_args),
+        Command::Diff(diff_args) => diff(&diff_args),
+        Command::Inventory(inventory_args) => inventory(inventory_args).await,
+        Command::Serve(serve_args) => serve(serve_args).await,
+        Command::Watch(watch_args) => watch(watch_args).await,
+    }
+}
+
+fn load_source_linker(config: &Config) -> Result<Option<SourceLinker>> {
+    config
+        .source_links
+        .as_ref()
+        .map(|source_links| SourceLinker::new(source_links, &config.pkg_path))
+        .transpose()
+}
+
+/// Renders the docs and writes every export `config` asks for. Returns the files that were
+/// written and the modules that could not be processed.
+fn build(config: &Config, source_linker: Option<&SourceLinker>) -> Result<BuildOutput> {
+    let mut output = match (config.ssg, config.layout) {
+        (SSG::Markdown | SSG::Zola, Layout::SinglePage) => {
+            render_single_page_docs(config, source_linker)?
+        }
+        (SSG::Markdown | SSG::Zola, Layout::PerObject) => {
+            render_per_object_docs(config, source_linker)?
+        }
+        (SSG::Html, _) => render_html_docs(config, source_linker)?,
+        (SSG::Markdown | SSG::Zola | SSG::Rst, _) => render_docs(config, source_linker)?,
+    };
+
+    // the exports document the package the pages were rendered from
+    output
+        .written
+        .extend(export_objects_inv(&output.package, config)?);
+    if config.llms_txt {
+        output
+            .written
+            .extend(export_llms_txt(&output.package, config)?);
+    }
+    if config.search_index {
+        output
+            .written
+            .extend(export_search_index(&output.package, config)?);
+    }
+    if let Some(import_graph) = &config.diagrams.import_graph {
+        output.written.extend(export_import_graph(
+            &output.package,
+            &config.output_dir,
+            import_graph,
+        )?);
+    }
+    if let Some(json_output) = &config.json_output {
+        output
+            .written
+            .extend(export_json(&output.package, json_output)?);
+    }
+    Ok(output)
+}
+
+/// Shows why each of the modules in `failures` failed and sums them up at the end, failing
+/// if `fail_on_error` is set
+fn report_failures(failures: &[(PathBuf, Report)], fail_on_error: bool) -> Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let diagnostics: Vec<Diagnostic> = failures
+        .iter()
+        .map(|(path, error)| Diagnostic::from_failure(path, error))
+        .collect();
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic.render());
+    }
+    eprintln!("{} modules could not be processed:", diagnostics.len());
+    for diagnostic in &diagnostics {
+        eprintln!("  {}: {}", diagnostic.location(), diagnostic.message);
+    }
+
+    if fail_on_error {
+        return Err(eyre!("{} modules could not be processed", failures.len()));
+    }
+    Ok(())
+}
+
+/// Parses the whole package like `build` would, without writing anything
+fn check(build_args: BuildArgs) -> Result<()> {
+    let config = resolve_runtime_config(build_args)?;
+    let (package, failures) = extract_package_documentation_with_failures(
         &config.pkg_path,
-        &config.output_dir,
         config.skip_private,
         config.skip_undoc,
-        config.exclude,
-        &config.renderer,
+        config.exclude.clone(),
+        None,
     )?;
+    println!(
+        "checked {} modules of {}",
+        package.modules.len() + failures.len(),
+        package.name
+    );
+    // checking is all about the failures, so they always fail it
+    report_failures(&failures, true)
+}
+
+fn init(init_args: &InitArgs) -> Result<()> {
+    if init_args.path.exists() && !init_args.force {
+        return Err(eyre!(
+            "{} already exists, pass --force to overwrite it",
+            init_args.path.display()
+        ));
+    }
+    // the paths in the configuration are relative to the directory it is in
+    let root = match init_args.path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut config_builder = ConfigBuilder::defaults();
+    let pkg_path = detect_package(root);
+    match &pkg_path {
+        Some(pkg_path) => println!("documenting the package at {}", pkg_path.display()),
+        None => println!("could not tell which package to document, set pkg_path yourself"),
+    }
+    config_builder = config_builder.with_pkg_path(pkg_path.clone());
 
+    if let Some(site) = init_args.site {
+        let title = pyproject_name(root)
+            .or_else(|| Some(pkg_path?.file_name()?.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "API reference".to_string());
+        let api_dir = scaffold_site(
+            site,
+            &root.join(&init_args.site_dir),
+            &title,
+            init_args.force,
+        )?;
+        println!("set up a {site} site in {}", init_args.site_dir.display());
+        config_builder = config_builder
+            .with_ssg(Some(site.ssg()))
+            .with_output_dir(Some(api_dir.strip_prefix(root)?.to_path_buf()));
+    }
+
+    config_builder.to_file(&init_args.path)?;
+    println!("wrote {}", init_args.path.display());
+    Ok(())
+}
+
+/// The files a build writes besides the pages, e.g. `llms.txt`
+fn build_exports(config: &Config) -> Vec<PathBuf> {
+    let out = &config.output_dir;
+    let mut exports = vec![out.join(OBJECTS_INV_NAME)];
+    exports.extend(config.json_output.iter().cloned());
+    if config.llms_txt {
+        exports.extend([out.join(LLMS_TXT_NAME), out.join(LLMS_FULL_TXT_NAME)]);
+    }
+    if config.search_index {
+        exports.extend([out.join(SEARCH_INDEX_NAME), out.join(SEARCH_WIDGET_NAME)]);
+    }
+    if config.diagrams.import_graph.is_some() {
+        exports.extend([
+            out.join(IMPORT_GRAPH_DOT_NAME),
+            out.join(IMPORT_GRAPH_MERMAID_NAME),
+        ]);
+    }
+    if config.ssg == SSG::Html {
+        exports.push(out.join(STYLESHEET_NAME));
+    }
+    exports
+}
+
+fn clean(build_args: BuildArgs) -> Result<()> {
+    let config = resolve_runtime_config(build_args)?;
+    let out = &config.output_dir;
+    let exports = build_exports(&config);
+
+    let removed = clean_docs(&config, &exports)?;
+    println!("removed {} files from {}", removed.len(), out.display());
+    Ok(())
+}
+
+fn write_build_report(output: &BuildOutput, path: &Path, format: ReportFormat) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    tracing::info!("writing build report to {}", path.display());
+    std::fs::write(path, output.build_report().render(format)?)?;
     Ok(())
 }
+
+fn coverage(coverage_args: CoverageArgs) -> Result<()> {
+    let config = resolve_coverage_config(&coverage_args)?;
+    // undocumented objects are what this is about, private ones don't count
+    let (package, errored) =
+        extract_package_documentation(&config.pkg_path, true, false, config.exclude.clone(), None)?;
+    let report = CoverageReport::new(&package);
+    for path in &errored {
+        eprintln!("could not process {}, it is not counted", path.display());
+    }
+
+    let rendered = report.render(coverage_args.format)?;
+    match &coverage_args.report {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => print!("{rendered}"),
+    }
+
+    match report.below(&config.coverage) {
+        Some(percent) => Err(eyre!(
+            "documentation coverage is {percent:.1}%, below the required {:.1}%",
+            config.coverage.fail_under.unwrap_or_default()
+        )),
+        None => Ok(()),
+    }
+}
+
+fn lint(lint_args: LintArgs) -> Result<()> {
+    let config = resolve_runtime_config(lint_args.build)?;
+    let (package, errored) = extract_package_documentation(
+        &config.pkg_path,
+        config.skip_private,
+        false,
+        config.exclude.clone(),
+        None,
+    )?;
+    let issues = lint_package(&package, &config.pkg_path);
+    for path in &errored {
+        eprintln!("could not process {}, it was not linted", path.display());
+    }
+    for issue in &issues {
+        println!("{issue}");
+    }
+
+    if lint_args.fail_on_issues && !issues.is_empty() {
+        return Err(eyre!("found {} docstring issues", issues.len()));
+    }
+    Ok(())
+}
+
+fn diff(diff_args: &DiffArgs) -> Result<()> {
+    let (api_diff, errored) = diff_api(&diff_args.old, &diff_args.new, diff_args.exclude.clone())?;
+    for path in &errored {
+        eprintln!("could not process {}, it was not compared", path.display());
+    }
+
+    let markdown = api_diff.to_markdown();
+    match &diff_args.output {
+        Some(path) => std::fs::write(path, markdown)?,
+        None => print!("{markdown}"),
+    }
+
+    if diff_args.fail_on_breaking && api_diff.is_breaking() {
+        return Err(eyre!("the public API has breaking changes"));
+    }
+    Ok(())
+}
+
+async fn inventory(inventory_args: InventoryArgs) -> Result<()> {
+    let cache_path = init_cache(inventory_args.cache_dir)?;
+    match inventory_args.command {
+        InventoryCommand::Fetch { name, url } => {
+            let path = cached_inventory_path(&cache_path, &name);
+            // the inventory is fetched with a blocking client, which can't run on the runtime
+            tokio::task::spawn_blocking(move || {
+                cache_remote_objects_inv(&url, name, Some(cache_path))
+            })
+            .await??;
+            println!("cached {}", path.display());
+        }
+        InventoryCommand::List => {
+            for name in cached_inventories(&cache_path)? {
+                println!("{name}");
+            }
+        }
+        InventoryCommand::Inspect { inventory, filter } => {
+            let path = Path::new(&inventory);
+            let path = if path.is_file() {
+                path.to_path_buf()
+            } else {
+                cached_inventory_path(&cache_path, &inventory)
+            };
+            if !path.is_file() {
+                return Err(eyre!(
+                    "no inventory {inventory}, fetch it with `snakedown inventory fetch`"
+                ));
+            }
+            for (name, location) in inventory_objects(&path)? {
+                if filter.as_ref().is_none_or(|f| name.contains(f.as_str())) {
+                    println!("{name} {}", location.display());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn serve(serve_args: ServeArgs) -> Result<()> {
+    // the default output directory lives as long as the server
+    let (config, _temp_dir) = resolve_serve_config(&serve_args)?;
+    let source_linker = load_source_linker(&config)?;
+    let pkg_path = config.pkg_path.clone();
+    let site_dir = config.output_dir.clone();
+    let build = move || -> Result<()> {
+        render_html_docs(&config, source_linker.as_ref())?;
+        Ok(())
+    };
+    build()?;
+
+    let address = format!("{}:{}", serve_args.host, serve_args.port);
+    let server = DevServer::bind(&address, &site_dir).await?;
+    println!(
+        "serving {} at http://{}",
+        site_dir.display(),
+        server.local_addr()?
+    );
+    let reloads = server.reloads();
+    tokio::select! {
+        result = server.run() => result,
+        result = watch_package(&pkg_path, Duration::from_millis(250), reloads, build) => result,
+        // stop by returning, so the temporary output directory is removed
+        result = tokio::signal::ctrl_c() => Ok(result?),
+    }
+}
+
+async fn watch(watch_args: WatchArgs) -> Result<()> {
+    let config = resolve_runtime_config(watch_args.build)?;
+    let source_linker = load_source_linker(&config)?;
+    report_failures(&build(&config, source_linker.as_ref())?.failures, false)?;
+
+    // anything drawn from the whole package has to be redone on every change. `objects.inv`
+    // is only for other projects linking to the published docs, the next build refreshes it
+    let incremental = config.ssg != SSG::Html
+        && config.layout != Layout::SinglePage
+        && config.diagrams == DiagramConfig::default()
+        && !config.llms_txt
+        && !config.search_index
+        && config.json_output.is_none();
+    println!("watching {} for changes", config.pkg_path.display());
+
+    let debounce = Duration::from_millis(watch_args.debounce);
+    let mut snapshot = source_snapshot(&config.pkg_path);
+    loop {
+        let changes = next_changes(&config.pkg_path, &mut snapshot, debounce).await;
+        let start = Instant::now();
+        let result = if incremental {
+            render_changed_docs(&config, source_linker.as_ref(), &changes)
+        } else {
+            build(&config, source_linker.as_ref()).map(|output| output.failures)
+        }
+        .and_then(|failures| report_failures(&failures, false));
+        match result {
+            Ok(()) => tracing::info!("updated the docs in {:?}", start.elapsed()),
+            // keep watching, the next save probably fixes it
+            Err(e) => tracing::error!("updating the docs failed: {e}"),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use rustpython_parser::ast::Stmt;
+use serde::{Deserialize, Serialize};
+
+use crate::render::expr::render_expr;
+
+use super::utils::extract_docstring_from_body;
+
+/// A module or class level variable. Python itself has no attribute docstrings,
+/// so like sphinx we take a string literal directly following the assignment as its docstring
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeDocumentation {
+    pub name: String,
+    pub annotation: Option<String>,
+    pub value: Option<String>,
+    pub docstring: Option<String>,
+}
+
+pub fn is_private_attribute(attr_doc: &AttributeDocumentation) -> bool {
+    attr_doc.name.starts_with("_")
+}
+
+/// extracts all simple (i.e. single name target) assignments from the body.
+/// dunders such as `__all__` are left out since they are not part of the api
+pub(crate) fn extract_attributes(body: &[Stmt]) -> Vec<AttributeDocumentation> {
+    body.iter()
+        .enumerate()
+        .filter_map(|(i, statement)| {
+            let (name, annotation, value) = match statement {
+                Stmt::Assign(stmt_assign) => {
+                    if stmt_assign.targets.len() != 1 {
+                        return None;
+                    }
+                    let name = stmt_assign.targets.first()?.as_name_expr()?.id.to_string();
+                    (name, None, Some(render_expr(*stmt_assign.value.clone())))
+                }
+                Stmt::AnnAssign(stmt_ann_assign) => {
+                    let name = stmt_ann_assign.target.as_name_expr()?.id.to_string();
+                    (
+                        name,
+                        Some(render_expr(*stmt_ann_assign.annotation.clone())),
+                        stmt_ann_assign
+                            .value
+                            .as_ref()
+                            .map(|v| render_expr(*v.clone())),
+                    )
+                }
+                _ => return None,
+            };
+            if name.starts_with("__") && name.ends_with("__") {
+                return None;
+            }
+            Some(AttributeDocumentation {
+                name,
+                annotation,
+                value,
+                docstring: extract_docstring_from_body(&body[i + 1..]),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::parsing::{module::extract_module_documentation, utils::parse_python_str};
+
+    use super::AttributeDocumentation;
+
+    fn test_python_attributes() -> &'static str {
+        r#"
+__all__ = ["GREETING"]
+
+GREETING: str = "Hello"
+"""The default greeting."""
+
+_counter = 0
+a, b = 1, 2
+UNDOC = None
+
+class Greeter:
+    name: str
+    """Who to greet."""
+"#
+    }
+
+    #[test]
+    fn parse_module_attributes() -> Result<()> {
+        let program = parse_python_str(test_python_attributes())?;
+        let documentation = extract_module_documentation(&program, None, None, false, false);
+
+        assert_eq!(
+            documentation.attributes,
+            vec![
+                AttributeDocumentation {
+                    name: String::from("GREETING"),
+                    annotation: Some(String::from("str")),
+                    value: Some(String::from("\"Hello\"")),
+                    docstring: Some(String::from("The default greeting.")),
+                },
+                AttributeDocumentation {
+                    name: String::from("_counter"),
+                    annotation: None,
+                    value: Some(String::from("0")),
+                    docstring: None,
+                },
+                AttributeDocumentation {
+                    name: String::from("UNDOC"),
+                    annotation: None,
+                    value: Some(String::from("None")),
+                    docstring: None,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_fstring_attribute() -> Result<()> {
+        let program = parse_python_str(
+            r#"BASE = "https://example.com"
+URL = f"{BASE}/api"
+"""Where the API lives."""
+"#,
+        )?;
+        let documentation = extract_module_documentation(&program, None, None, false, false);
+
+        assert_eq!(
+            documentation.attributes.last(),
+            Some(&AttributeDocumentation {
+                name: String::from("URL"),
+                annotation: None,
+                value: Some(String::from("f\"{BASE}/api\"")),
+                docstring: Some(String::from("Where the API lives.")),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_module_attributes_skip_private_and_undoc() -> Result<()> {
+        let program = parse_python_str(test_python_attributes())?;
+        let documentation = extract_module_documentation(&program, None, None, true, true);
+
+        assert_eq!(documentation.attributes.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_class_attributes() -> Result<()> {
+        let program = parse_python_str(test_python_attributes())?;
+        let documentation = extract_module_documentation(&program, None, None, false, false);
+
+        // we checked before there is at least one class, so this is safe
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+        assert_eq!(
+            class.attributes,
+            vec![AttributeDocumentation {
+                name: String::from("name"),
+                annotation: Some(String::from("str")),
+                value: None,
+                docstring: Some(String::from("Who to greet.")),
+            }]
+        );
+        Ok(())
+    }
+}
@@ -1,25 +1,58 @@
-use rustpython_parser::ast::{Identifier, StmtClassDef};
+use rustpython_parser::ast::{Stmt, StmtClassDef};
+use serde::{Deserialize, Serialize};
 
-use super::{function::FunctionDocumentation, utils::extract_docstring_from_body};
+use crate::render::expr::render_expr;
 
-#[derive(Debug)]
+use super::{
+    attribute::{AttributeDocumentation, extract_attributes},
+    function::FunctionDocumentation,
+    utils::{LineIndex, SourceLines, extract_docstring_from_body},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ClassDocumentation {
-    pub name: Identifier,
+    pub name: String,
     pub docstring: Option<String>,
+    /// the base classes as python source, keyword arguments like `metaclass=` are left out
+    pub bases: Vec<String>,
     pub methods: Vec<FunctionDocumentation>,
+    pub attributes: Vec<AttributeDocumentation>,
+    pub lines: Option<SourceLines>,
+    /// link to the definition in the repository, see `render::source`
+    pub source_url: Option<String>,
 }
 
 impl From<&StmtClassDef> for ClassDocumentation {
     fn from(value: &StmtClassDef) -> Self {
         Self {
-            name: value.name.clone(),
+            name: value.name.to_string(),
             docstring: extract_docstring_from_body(&value.body),
+            bases: value.bases.iter().map(|b| render_expr(b.clone())).collect(),
             methods: value
                 .body
                 .iter()
                 .filter_map(|s| FunctionDocumentation::try_from(s).ok())
                 .collect(),
+            attributes: extract_attributes(&value.body),
+            lines: None,
+            source_url: None,
+        }
+    }
+}
+
+impl ClassDocumentation {
+    /// records the lines of the class and its methods
+    pub(crate) fn locate(mut self, class_def: &StmtClassDef, index: &LineIndex) -> Self {
+        self.lines = Some(index.lines(class_def.range));
+        // the same statements the methods were extracted from, in the same order
+        let method_ranges = class_def.body.iter().filter_map(|s| match s {
+            Stmt::FunctionDef(method) => Some(method.range),
+            _ => None,
+        });
+        for (method, range) in self.methods.iter_mut().zip(method_ranges) {
+            method.lines = Some(index.lines(range));
         }
+        self
     }
 }
 
@@ -93,6 +126,18 @@ fn parse_test_python_class_docstring() -> Result<()> {
         Ok(())
     }
     #[test]
+    fn parse_class_bases() -> Result<()> {
+        let program = parse_python_str(
+            "class Plugin(base.Plugin, Generic[T], metaclass=ABCMeta):\n    pass\n",
+        )?;
+        let documentation = extract_module_documentation(&program, None, None, false, false);
+
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+        assert_eq!(class.bases, vec!["base.Plugin", "Generic[T]"]);
+        Ok(())
+    }
+    #[test]
     fn parse_test_python_file_on_disk() -> Result<()> {
         let file_contents = test_python_class();
 
@@ -0,0 +1,351 @@
+//! The parameters and return value a docstring documents. Google (`Args:`), NumPy
+//! (`Parameters` underlined with dashes) and Sphinx (`:param x:`) style are understood,
+//! anything else in the docstring is skipped.
+
+use lazy_regex::regex_captures;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StructuredDocstring {
+    pub params: Vec<DocumentedParam>,
+    /// set if the docstring has a returns (or yields) section
+    pub returns: Option<DocumentedReturn>,
+    /// whether there were any sections or fields at all, a docstring without them is
+    /// just prose and documents nothing in particular
+    pub has_sections: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentedParam {
+    /// without the leading `*` or `**` of variadic parameters
+    pub name: String,
+    pub type_hint: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DocumentedReturn {
+    pub type_hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Params,
+    Returns,
+    Other,
+}
+
+fn section(header: &str) -> Option<Section> {
+    match header {
+        "Args" | "Arguments" | "Parameters" | "Params" | "Keyword Args" | "Keyword Arguments"
+        | "Other Parameters" => Some(Section::Params),
+        "Returns" | "Return" | "Yields" | "Yield" => Some(Section::Returns),
+        "Raises" | "Attributes" | "Example" | "Examples" | "Note" | "Notes" | "See Also"
+        | "References" | "Warning" | "Warnings" | "Warns" | "Todo" | "Methods" => {
+            Some(Section::Other)
+        }
+        _ => None,
+    }
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3 && line.chars().all(|c| c == '-')
+}
+
+pub fn parse_docstring(docstring: &str) -> StructuredDocstring {
+    let lines: Vec<&str> = docstring.lines().collect();
+    let mut doc = StructuredDocstring::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let header = line.trim();
+
+        if let Some(field) = header.strip_prefix(':') {
+            doc.has_sections |= parse_sphinx_field(field, &mut doc);
+            i += 1;
+            continue;
+        }
+
+        // numpy: the header is underlined and the entries are as indented as the header
+        if lines.get(i + 1).is_some_and(|next| is_underline(next)) && !header.is_empty() {
+            doc.has_sections = true;
+            let start = i + 2;
+            let mut end = start;
+            while end < lines.len()
+                && !(lines.get(end + 1).is_some_and(|next| is_underline(next))
+                    && indent(lines[end]) <= indent(line)
+                    && !lines[end].trim().is_empty())
+            {
+                end += 1;
+            }
+            let mut entries = lines[start..end]
+                .iter()
+                .filter(|l| indent(l) == indent(line) && !l.trim().is_empty())
+                .map(|l| l.trim());
+            match section(header) {
+                Some(Section::Params) => {
+                    for entry in entries {
+                        let (names, type_hint) = match entry.split_once(" : ") {
+                            Some((names, type_hint)) => (names, Some(type_hint)),
+                            None => (entry.trim_end_matches(':').trim(), None),
+                        };
+                        for name in names.split(',') {
+                            doc.params.push(DocumentedParam {
+                                name: name.trim().trim_start_matches('*').to_string(),
+                                type_hint: type_hint.map(strip_optional),
+                            });
+                        }
+                    }
+                }
+                Some(Section::Returns) => {
+                    let type_hint = entries.next().map(|entry| match entry.split_once(" : ") {
+                        Some((_, type_hint)) => type_hint.trim().to_string(),
+                        None => entry.to_string(),
+                    });
+                    doc.returns = Some(DocumentedReturn { type_hint });
+                }
+                _ => (),
+            }
+            i = end;
+            continue;
+        }
+
+        // google: `Args:` followed by the entries, indented further than the header
+        if let Some(kind) = header.strip_suffix(':').and_then(section) {
+            doc.has_sections = true;
+            let start = i + 1;
+            let mut end = start;
+            while end < lines.len()
+                && (lines[end].trim().is_empty() || indent(lines[end]) > indent(line))
+            {
+                end += 1;
+            }
+            let entry_indent = lines[start..end]
+                .iter()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| indent(l))
+                .min();
+            let mut entries = lines[start..end]
+                .iter()
+                .filter(|l| Some(indent(l)) == entry_indent && !l.trim().is_empty())
+                .map(|l| l.trim());
+            match kind {
+                Section::Params => {
+                    for entry in entries {
+                        if let Some((_, name, type_hint)) =
+                            regex_captures!(r"^(\*{0,2}\w+)\s*(?:\((.*?)\))?\s*:", entry)
+                        {
+                            doc.params.push(DocumentedParam {
+                                name: name.trim_start_matches('*').to_string(),
+                                type_hint: (!type_hint.is_empty())
+                                    .then(|| strip_optional(type_hint)),
+                            });
+                        }
+                    }
+                }
+                Section::Returns => {
+                    let type_hint = entries
+                        .next()
+                        .and_then(|entry| entry.split_once(':'))
+                        .map(|(type_hint, _)| type_hint.trim())
+                        .filter(|type_hint| looks_like_type(type_hint))
+                        .map(str::to_string);
+                    doc.returns = Some(DocumentedReturn { type_hint });
+                }
+                Section::Other => (),
+            }
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    doc
+}
+
+/// handles `param x:`, `param int x:`, `type x:`, `returns:` and `rtype:`, the leading `:`
+/// already stripped. Returns whether the field was one of them.
+fn parse_sphinx_field(field: &str, doc: &mut StructuredDocstring) -> bool {
+    let Some((head, body)) = field.split_once(':') else {
+        return false;
+    };
+    let mut words: Vec<&str> = head.split_whitespace().collect();
+    let Some(kind) = words.first().copied() else {
+        return false;
+    };
+    match kind {
+        "param" | "parameter" | "arg" | "argument" | "key" | "keyword" => {
+            let Some(name) = words.pop().filter(|_| !words.is_empty()) else {
+                return false;
+            };
+            let name = name.trim_start_matches('*').to_string();
+            let type_hint = (words.len() > 1).then(|| words[1..].join(" "));
+            match doc.params.iter_mut().find(|p| p.name == name) {
+                Some(param) => param.type_hint = type_hint.or(param.type_hint.take()),
+                None => doc.params.push(DocumentedParam { name, type_hint }),
+            }
+        }
+        "type" => {
+            let Some(name) = words.get(1) else {
+                return false;
+            };
+            let name = name.trim_start_matches('*').to_string();
+            let type_hint = Some(strip_optional(body));
+            match doc.params.iter_mut().find(|p| p.name == name) {
+                Some(param) => param.type_hint = type_hint,
+                None => doc.params.push(DocumentedParam { name, type_hint }),
+            }
+        }
+        "returns" | "return" | "yields" | "yield" => {
+            doc.returns.get_or_insert_with(DocumentedReturn::default);
+        }
+        "rtype" | "ytype" => {
+            doc.returns
+                .get_or_insert_with(DocumentedReturn::default)
+                .type_hint = Some(body.trim().to_string());
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// `int, optional` only says that there is a default, the type is `int`
+pub(crate) fn strip_optional(type_hint: &str) -> String {
+    let type_hint = type_hint.trim();
+    let type_hint = match type_hint.find(", default") {
+        Some(index) => &type_hint[..index],
+        None => type_hint,
+    };
+    type_hint
+        .strip_suffix(", optional")
+        .unwrap_or(type_hint)
+        .trim()
+        .to_string()
+}
+
+/// whether the text before the colon of a google style `Returns:` entry is a type rather
+/// than the start of a sentence, e.g. `dict[str, int]` but not `The count`
+fn looks_like_type(text: &str) -> bool {
+    regex_captures!(r"^[\w.]+(\[.*\])?(\s*\|\s*[\w.]+(\[.*\])?)*$", text).is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{DocumentedParam, DocumentedReturn, StructuredDocstring, parse_docstring};
+
+    fn param(name: &str, type_hint: Option<&str>) -> DocumentedParam {
+        DocumentedParam {
+            name: name.to_string(),
+            type_hint: type_hint.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_google_style() {
+        let doc = parse_docstring(
+            r#"Sends a request.
+
+    Args:
+        url (str): where to send it,
+            over multiple lines: really
+        retries (int, optional): how often to try
+        **kwargs: passed on
+
+    Returns:
+        dict[str, int]: the response
+
+    Raises:
+        ValueError: if the url is bad
+    "#,
+        );
+        assert_eq!(
+            doc,
+            StructuredDocstring {
+                params: vec![
+                    param("url", Some("str")),
+                    param("retries", Some("int")),
+                    param("kwargs", None),
+                ],
+                returns: Some(DocumentedReturn {
+                    type_hint: Some("dict[str, int]".to_string())
+                }),
+                has_sections: true,
+            }
+        );
+
+        let doc = parse_docstring("Counts.\n\n    Returns:\n        The count: of things.\n");
+        assert_eq!(doc.returns, Some(DocumentedReturn { type_hint: None }));
+    }
+
+    #[test]
+    fn test_numpy_style() {
+        let doc = parse_docstring(
+            r#"Sends a request.
+
+    Parameters
+    ----------
+    url : str
+        where to send it
+    x, y : float, default 0.0
+        the coordinates
+    verbose
+
+    Returns
+    -------
+    response : Response
+        the response
+    "#,
+        );
+        assert_eq!(
+            doc.params,
+            vec![
+                param("url", Some("str")),
+                param("x", Some("float")),
+                param("y", Some("float")),
+                param("verbose", None),
+            ]
+        );
+        assert_eq!(
+            doc.returns,
+            Some(DocumentedReturn {
+                type_hint: Some("Response".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_sphinx_style() {
+        let doc = parse_docstring(
+            r#"Sends a request.
+
+    :param str url: where to send it
+    :param retries: how often to try
+    :type retries: int, optional
+    :returns: the response
+    :rtype: Response
+    "#,
+        );
+        assert_eq!(
+            doc.params,
+            vec![param("url", Some("str")), param("retries", Some("int"))]
+        );
+        assert_eq!(
+            doc.returns,
+            Some(DocumentedReturn {
+                type_hint: Some("Response".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_prose_documents_nothing() {
+        assert_eq!(
+            parse_docstring("Sends a request.\n\n    The url: is required.\n"),
+            StructuredDocstring::default()
+        );
+    }
+}
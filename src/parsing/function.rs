@@ -1,16 +1,100 @@
 use rustpython_parser::ast::{
-    Arguments, Expr, Stmt, StmtAsyncFunctionDef, StmtFunctionDef, TypeParam,
+    Arg, ArgWithDefault, Arguments, Stmt, StmtAsyncFunctionDef, StmtFunctionDef, TypeParam,
 };
+use serde::{Deserialize, Serialize};
 
-use super::utils::extract_docstring_from_body;
+use crate::render::expr::render_expr;
 
-#[derive(Debug)]
+use super::utils::{SourceLines, extract_docstring_from_body};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FunctionDocumentation {
     pub name: String,
     pub docstring: Option<String>,
-    pub return_type: Option<Expr>,
-    pub args: Arguments,
-    pub generics: Vec<TypeParam>,
+    pub return_type: Option<String>,
+    pub args: Vec<Parameter>,
+    pub generics: Vec<String>,
+    pub decorators: Vec<String>,
+    pub is_async: bool,
+    pub lines: Option<SourceLines>,
+    /// link to the definition in the repository, see `render::source`
+    pub source_url: Option<String>,
+}
+
+/// The different ways a parameter can be passed to a python function
+/// see <https://docs.python.org/3/glossary.html#term-parameter>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterKind {
+    PositionalOnly,
+    PositionalOrKeyword,
+    VarPositional,
+    KeywordOnly,
+    VarKeyword,
+}
+
+/// A single parameter of a function signature. Annotations and defaults are kept
+/// as the rendered python source so the signature can be serialized without
+/// dragging the AST along.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    pub kind: ParameterKind,
+    pub annotation: Option<String>,
+    pub default: Option<String>,
+}
+
+impl Parameter {
+    fn from_arg(arg: &Arg, kind: ParameterKind) -> Self {
+        Self {
+            name: arg.arg.to_string(),
+            kind,
+            annotation: arg.annotation.as_ref().map(|a| render_expr(*a.clone())),
+            default: None,
+        }
+    }
+    fn from_arg_with_default(arg: &ArgWithDefault, kind: ParameterKind) -> Self {
+        let mut param = Self::from_arg(&arg.def, kind);
+        param.default = arg.default.as_ref().map(|d| render_expr(*d.clone()));
+        param
+    }
+}
+
+pub(crate) fn extract_parameters(args: &Arguments) -> Vec<Parameter> {
+    let mut params = vec![];
+    params.extend(
+        args.posonlyargs
+            .iter()
+            .map(|a| Parameter::from_arg_with_default(a, ParameterKind::PositionalOnly)),
+    );
+    params.extend(
+        args.args
+            .iter()
+            .map(|a| Parameter::from_arg_with_default(a, ParameterKind::PositionalOrKeyword)),
+    );
+    if let Some(vararg) = &args.vararg {
+        params.push(Parameter::from_arg(vararg, ParameterKind::VarPositional));
+    }
+    params.extend(
+        args.kwonlyargs
+            .iter()
+            .map(|a| Parameter::from_arg_with_default(a, ParameterKind::KeywordOnly)),
+    );
+    if let Some(kwarg) = &args.kwarg {
+        params.push(Parameter::from_arg(kwarg, ParameterKind::VarKeyword));
+    }
+    params
+}
+
+fn render_type_param(type_param: &TypeParam) -> String {
+    match type_param {
+        TypeParam::TypeVar(type_var) => match &type_var.bound {
+            Some(bound) => format!("{}: {}", type_var.name, render_expr(*bound.clone())),
+            None => type_var.name.to_string(),
+        },
+        TypeParam::ParamSpec(param_spec) => format!("**{}", param_spec.name),
+        TypeParam::TypeVarTuple(var_tuple) => format!("*{}", var_tuple.name),
+    }
 }
 
 impl From<&StmtFunctionDef> for FunctionDocumentation {
@@ -18,9 +102,17 @@ fn from(value: &StmtFunctionDef) -> Self {
         Self {
             name: value.name.to_string(),
             docstring: extract_docstring_from_body(&value.body),
-            return_type: value.returns.as_ref().map(|r| *r.clone()),
-            args: *value.args.clone(),
-            generics: value.type_params.clone(),
+            return_type: value.returns.as_ref().map(|r| render_expr(*r.clone())),
+            args: extract_parameters(&value.args),
+            generics: value.type_params.iter().map(render_type_param).collect(),
+            decorators: value
+                .decorator_list
+                .iter()
+                .map(|d| render_expr(d.clone()))
+                .collect(),
+            is_async: false,
+            lines: None,
+            source_url: None,
         }
     }
 }
@@ -30,9 +122,17 @@ fn from(value: &StmtAsyncFunctionDef) -> Self {
         Self {
             name: value.name.to_string(),
             docstring: extract_docstring_from_body(&value.body),
-            return_type: value.returns.as_ref().map(|r| *r.clone()),
-            args: *value.args.clone(),
-            generics: value.type_params.clone(),
+            return_type: value.returns.as_ref().map(|r| render_expr(*r.clone())),
+            args: extract_parameters(&value.args),
+            generics: value.type_params.iter().map(render_type_param).collect(),
+            decorators: value
+                .decorator_list
+                .iter()
+                .map(|d| render_expr(d.clone()))
+                .collect(),
+            is_async: true,
+            lines: None,
+            source_url: None,
         }
     }
 }
@@ -58,8 +158,13 @@ pub fn is_private_function(fn_doc: &FunctionDocumentation) -> bool {
 mod test {
 
     use color_eyre::Result;
+    use pretty_assertions::assert_eq;
 
-    use crate::parsing::{module::extract_module_documentation, utils::parse_python_str};
+    use crate::parsing::{
+        function::{Parameter, ParameterKind},
+        module::extract_module_documentation,
+        utils::parse_python_str,
+    };
 
     fn test_python_func_no_types() -> &'static str {
         "
@@ -216,4 +321,59 @@ fn parse_test_python_function_docstring() -> Result<()> {
         );
         Ok(())
     }
+
+    #[test]
+    fn parse_test_python_func_parameter_kinds() -> Result<()> {
+        let program = parse_python_str(
+            "
+async def fetch[T](url, /, retries: int = 3, *args, timeout: float, **kwargs) -> T:
+    pass
+        ",
+        )?;
+        let documentation = extract_module_documentation(&program, None, None, false, false);
+
+        // we checked before there is at least one function, so this is safe
+        #[allow(clippy::unwrap_used)]
+        let function = documentation.functions.first().unwrap();
+
+        assert!(function.is_async);
+        assert_eq!(function.return_type, Some(String::from("T")));
+        assert_eq!(function.generics, vec![String::from("T")]);
+        assert_eq!(
+            function.args,
+            vec![
+                Parameter {
+                    name: String::from("url"),
+                    kind: ParameterKind::PositionalOnly,
+                    annotation: None,
+                    default: None,
+                },
+                Parameter {
+                    name: String::from("retries"),
+                    kind: ParameterKind::PositionalOrKeyword,
+                    annotation: Some(String::from("int")),
+                    default: Some(String::from("3")),
+                },
+                Parameter {
+                    name: String::from("args"),
+                    kind: ParameterKind::VarPositional,
+                    annotation: None,
+                    default: None,
+                },
+                Parameter {
+                    name: String::from("timeout"),
+                    kind: ParameterKind::KeywordOnly,
+                    annotation: Some(String::from("float")),
+                    default: None,
+                },
+                Parameter {
+                    name: String::from("kwargs"),
+                    kind: ParameterKind::VarKeyword,
+                    annotation: None,
+                    default: None,
+                },
+            ]
+        );
+        Ok(())
+    }
 }
@@ -1,6 +1,19 @@
 #![allow(dead_code)]
+pub(crate) mod attribute;
 pub(crate) mod class;
+pub(crate) mod docstring;
 pub(crate) mod function;
 pub(crate) mod module;
+pub(crate) mod package;
 pub(crate) mod sphinx;
 pub(crate) mod utils;
+
+// the documentation IR, the types `export::json` and `load_api` hand out
+pub use attribute::AttributeDocumentation;
+pub use class::ClassDocumentation;
+pub use function::{FunctionDocumentation, Parameter, ParameterKind};
+pub use module::{
+    ExtractionWarning, Import, ModuleDocumentation, ModuleReference, SkipReason, SkippedObject,
+};
+pub use package::PackageDocumentation;
+pub use utils::SourceLines;
@@ -1,39 +1,106 @@
 use std::path::PathBuf;
 
 use color_eyre::{Result, eyre::eyre};
-use rustpython_parser::ast::{Mod, Stmt, StmtAssign};
+use rustpython_parser::ast::{ExceptHandler, Mod, Stmt, StmtAssign};
+use serde::{Deserialize, Serialize};
+use strum::Display;
 
 use super::{
+    attribute::{AttributeDocumentation, extract_attributes, is_private_attribute},
     class::{ClassDocumentation, is_private_class},
     function::{FunctionDocumentation, is_private_function},
-    utils::extract_docstring_from_body,
+    utils::{LineIndex, extract_docstring_from_body},
 };
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModuleDocumentation {
     pub name: Option<String>,
     pub prefix: Option<String>,
     pub docstring: Option<String>,
     pub functions: Vec<FunctionDocumentation>,
     pub classes: Vec<ClassDocumentation>,
-    pub sub_modules: Option<Vec<PathBuf>>,
+    pub attributes: Vec<AttributeDocumentation>,
+    pub sub_modules: Option<Vec<ModuleReference>>,
     pub exports: Option<Vec<String>>,
+    pub imports: Vec<Import>,
+    /// path of the source file relative to the directory containing the root package
+    pub path: Option<PathBuf>,
+    /// mermaid source of the inheritance diagram shown on the page, see `render::diagrams`.
+    /// It depends on the rest of the package so it is not part of the json export
+    #[serde(skip)]
+    pub class_diagram: Option<String>,
+    /// mermaid source of the import graph of the package, only set on the root package
+    #[serde(skip)]
+    pub import_graph: Option<String>,
+    /// the functions, classes and attributes that were left out, see `export::report`
+    #[serde(skip)]
+    pub skipped: Vec<SkippedObject>,
+    /// anything odd noticed while extracting, like `__all__` being assigned twice
+    #[serde(skip)]
+    pub warnings: Vec<ExtractionWarning>,
 }
 
-#[derive(Debug)]
+/// Why something was left out of the docs
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "lowercase")]
+pub enum SkipReason {
+    Private,
+    Undocumented,
+    /// matched one of the `exclude` paths
+    Excluded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedObject {
+    pub name: String,
+    /// the line it is defined on, attributes don't keep theirs
+    pub line: Option<usize>,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionWarning {
+    /// a short identifier of the kind of warning, e.g. `duplicate-all`
+    pub rule: &'static str,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// An `import module` or `from module import names` statement anywhere in the module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Import {
+    /// the module as written, relative imports keep their leading dots
+    pub module: String,
+    /// the names imported from `module`, empty for `import module`
+    pub names: Vec<String>,
+}
+
+/// A direct child of a package, as listed on the page of the package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModuleReference {
     pub name: String,
+    /// source path relative to the package, e.g. `mid.py` or `sub2/__init__.py`
     pub path: PathBuf,
+    /// the first sentence of the docstring of the child
+    pub summary: Option<String>,
+    pub is_package: bool,
 }
 
 // This one is the only gets done separately because it we can't deduce it from the file
 // contents themselves, we have to walk the fs for it, so we add a convenience
 // function so we can add that after the fact
 impl ModuleDocumentation {
-    pub fn with_sub_modules(&mut self, subs: Option<&Vec<PathBuf>>) -> &mut Self {
-        self.sub_modules = subs.cloned();
+    pub fn with_sub_modules(&mut self, subs: Option<Vec<ModuleReference>>) -> &mut Self {
+        self.sub_modules = subs;
         self
     }
+
+    /// path of the source file relative to the root package, e.g. `sub/mod.py`
+    pub fn relative_path(&self) -> Option<PathBuf> {
+        // the first component is the name of the root package itself
+        Some(self.path.as_ref()?.components().skip(1).collect())
+    }
 }
 
 // just a conveneience function
@@ -43,10 +110,50 @@ pub fn extract_module_documentation(
     prefix: Option<String>,
     skip_private: bool,
     skip_undoc: bool,
+) -> ModuleDocumentation {
+    extract_module_documentation_with_lines(
+        input_module,
+        None,
+        name,
+        prefix,
+        skip_private,
+        skip_undoc,
+    )
+}
+
+/// Like `extract_module_documentation`, but also records the lines functions and classes
+/// span in `source`, the code `input_module` was parsed from
+pub fn extract_located_module_documentation(
+    input_module: &Mod,
+    source: &str,
+    name: Option<String>,
+    prefix: Option<String>,
+    skip_private: bool,
+    skip_undoc: bool,
+) -> ModuleDocumentation {
+    let index = LineIndex::new(source);
+    extract_module_documentation_with_lines(
+        input_module,
+        Some(&index),
+        name,
+        prefix,
+        skip_private,
+        skip_undoc,
+    )
+}
+
+fn extract_module_documentation_with_lines(
+    input_module: &Mod,
+    index: Option<&LineIndex>,
+    name: Option<String>,
+    prefix: Option<String>,
+    skip_private: bool,
+    skip_undoc: bool,
 ) -> ModuleDocumentation {
     if let Mod::Module(mod_module) = input_module {
         extract_documentation_from_statements(
             &mod_module.body,
+            index,
             name,
             prefix,
             skip_private,
@@ -79,8 +186,58 @@ fn extract_exports_from_statement(statement: &StmtAssign) -> Result<Vec<String>>
     }
 }
 
+fn extract_imports(statements: &[Stmt]) -> Vec<Import> {
+    let mut imports = vec![];
+    for statement in statements {
+        match statement {
+            Stmt::Import(stmt_import) => {
+                imports.extend(stmt_import.names.iter().map(|alias| Import {
+                    module: alias.name.to_string(),
+                    names: vec![],
+                }));
+            }
+            Stmt::ImportFrom(stmt_import_from) => {
+                let level = stmt_import_from.level.map(|l| l.to_u32()).unwrap_or(0);
+                let module = format!(
+                    "{}{}",
+                    ".".repeat(level as usize),
+                    stmt_import_from.module.as_deref().unwrap_or_default()
+                );
+                imports.push(Import {
+                    module,
+                    names: stmt_import_from
+                        .names
+                        .iter()
+                        .map(|alias| alias.name.to_string())
+                        .collect(),
+                });
+            }
+            // imports in functions or behind `if TYPE_CHECKING:` are dependencies all the same
+            Stmt::FunctionDef(stmt) => imports.extend(extract_imports(&stmt.body)),
+            Stmt::AsyncFunctionDef(stmt) => imports.extend(extract_imports(&stmt.body)),
+            Stmt::ClassDef(stmt) => imports.extend(extract_imports(&stmt.body)),
+            Stmt::If(stmt) => {
+                imports.extend(extract_imports(&stmt.body));
+                imports.extend(extract_imports(&stmt.orelse));
+            }
+            Stmt::With(stmt) => imports.extend(extract_imports(&stmt.body)),
+            Stmt::Try(stmt) => {
+                imports.extend(extract_imports(&stmt.body));
+                for ExceptHandler::ExceptHandler(handler) in &stmt.handlers {
+                    imports.extend(extract_imports(&handler.body));
+                }
+                imports.extend(extract_imports(&stmt.orelse));
+                imports.extend(extract_imports(&stmt.finalbody));
+            }
+            _ => (),
+        }
+    }
+    imports
+}
+
 fn extract_documentation_from_statements(
     statements: &[Stmt],
+    index: Option<&LineIndex>,
     name: Option<String>,
     prefix: Option<String>,
     skip_private: bool,
@@ -90,71 +247,105 @@ fn extract_documentation_from_statements(
     assert_ne!(prefix, Some(String::from("")));
     let mut free_functions = vec![];
     let mut class_definitions = vec![];
+    let mut skipped = vec![];
+    let mut warnings = vec![];
     let mut exports = None;
     let docstring = extract_docstring_from_body(statements);
+    let skip_reason = |is_private: bool, is_undocumented: bool| {
+        if is_private && skip_private {
+            Some(SkipReason::Private)
+        } else if is_undocumented && skip_undoc {
+            Some(SkipReason::Undocumented)
+        } else {
+            None
+        }
+    };
     for statement in statements {
         if let Stmt::Assign(stmt_assign) = statement {
             match (&mut exports, extract_exports_from_statement(stmt_assign)) {
                 (None, Ok(exported)) => exports = Some(exported),
                 (Some(_), Ok(new_exported)) => {
-                    tracing::warn!("__all__ was defined multiple times.");
+                    let message = "__all__ was defined multiple times.";
+                    tracing::warn!("{message}");
+                    warnings.push(ExtractionWarning {
+                        rule: "duplicate-all",
+                        line: index.map(|i| i.line(stmt_assign.range.start().into())),
+                        message: message.to_string(),
+                    });
                     exports = Some(new_exported);
                 }
                 _ => (),
             }
         }
-        if let Stmt::FunctionDef(stmt_function_def) = statement {
-            let function_doc: FunctionDocumentation = stmt_function_def.into();
-            if function_doc.docstring.is_none() && skip_undoc {
-                tracing::debug!(
-                    "skipping function {} because it is undocumented",
-                    function_doc.name,
-                );
-                continue;
-            };
-
-            if is_private_function(&function_doc) && skip_private {
-                tracing::debug!(
-                    "skipping function {} because it is private",
-                    function_doc.name,
-                );
-                continue;
+        let function_doc = match statement {
+            Stmt::FunctionDef(stmt_function_def) => {
+                let mut function_doc: FunctionDocumentation = stmt_function_def.into();
+                function_doc.lines = index.map(|i| i.lines(stmt_function_def.range));
+                Some(function_doc)
             }
-            free_functions.push(function_doc);
-        }
-        if let Stmt::AsyncFunctionDef(stmt_async_function_def) = statement {
-            let function_doc: FunctionDocumentation = stmt_async_function_def.into();
-            if function_doc.docstring.is_none() && skip_undoc {
-                tracing::debug!(
-                    "skipping function {} because it is undocumented",
-                    function_doc.name,
-                );
-                continue;
-            };
-
-            if is_private_function(&function_doc) && skip_private {
-                tracing::debug!(
-                    "skipping function {} because it is private",
-                    function_doc.name,
-                );
-                continue;
+            Stmt::AsyncFunctionDef(stmt_async_function_def) => {
+                let mut function_doc: FunctionDocumentation = stmt_async_function_def.into();
+                function_doc.lines = index.map(|i| i.lines(stmt_async_function_def.range));
+                Some(function_doc)
+            }
+            _ => None,
+        };
+        if let Some(function_doc) = function_doc {
+            match skip_reason(
+                is_private_function(&function_doc),
+                function_doc.docstring.is_none(),
+            ) {
+                Some(reason) => {
+                    tracing::debug!(
+                        "skipping function {} because it is {reason}",
+                        function_doc.name,
+                    );
+                    skipped.push(SkippedObject {
+                        name: function_doc.name,
+                        line: function_doc.lines.map(|lines| lines.start),
+                        reason,
+                    });
+                }
+                None => free_functions.push(function_doc),
             }
-            free_functions.push(function_doc);
         }
         if let Stmt::ClassDef(stmt_class_def) = statement {
-            let class_doc: ClassDocumentation = stmt_class_def.into();
-            if is_private_class(&class_doc) && skip_private {
-                tracing::debug!("skipping class {} because it is private", class_doc.name,);
-                continue;
+            let mut class_doc: ClassDocumentation = stmt_class_def.into();
+            if let Some(index) = index {
+                class_doc = class_doc.locate(stmt_class_def, index);
+            }
+            match skip_reason(is_private_class(&class_doc), class_doc.docstring.is_none()) {
+                Some(reason) => {
+                    tracing::debug!("skipping class {} because it is {reason}", class_doc.name);
+                    skipped.push(SkippedObject {
+                        name: class_doc.name,
+                        line: class_doc.lines.map(|lines| lines.start),
+                        reason,
+                    });
+                }
+                None => class_definitions.push(class_doc),
             }
-            if class_doc.docstring.is_none() && skip_undoc {
+        }
+    }
+
+    let mut attributes = vec![];
+    for attr_doc in extract_attributes(statements) {
+        match skip_reason(
+            is_private_attribute(&attr_doc),
+            attr_doc.docstring.is_none(),
+        ) {
+            Some(reason) => {
                 tracing::debug!(
-                    "skipping function {} because it is undocumented",
-                    class_doc.name,
+                    "skipping attribute {} because it is {reason}",
+                    attr_doc.name
                 );
-                continue;
-            };
-            class_definitions.push(class_doc);
+                skipped.push(SkippedObject {
+                    name: attr_doc.name,
+                    line: None,
+                    reason,
+                });
+            }
+            None => attributes.push(attr_doc),
         }
     }
 
@@ -164,14 +355,22 @@ fn extract_documentation_from_statements(
         docstring,
         functions: free_functions,
         classes: class_definitions,
+        attributes,
         sub_modules: None,
         exports,
+        imports: extract_imports(statements),
+        path: None,
+        class_diagram: None,
+        import_graph: None,
+        skipped,
+        warnings,
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::parsing::utils::SourceLines;
     use color_eyre::Result;
     use rustpython_parser::{Mode, parse};
     use tracing_test::traced_test;
@@ -263,6 +462,58 @@ fn test_doc_extraction_multiple_exports() -> Result<()> {
 
         assert_eq!(docs.exports, Some(vec![String::from("b")]));
         assert!(logs_contain("__all__ was defined multiple times."));
+        assert_eq!(
+            docs.warnings.iter().map(|w| w.rule).collect::<Vec<_>>(),
+            vec!["duplicate-all"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_doc_extraction_records_skipped_objects() -> Result<()> {
+        let source = r#"
+def _private():
+    """Documented but private."""
+
+async def undocumented():
+    pass
+
+class Kept:
+    """Documented."""
+
+_hidden = 1
+"#;
+        let expr = parse(source, Mode::Module, "<embedded>")?;
+        let docs = extract_located_module_documentation(&expr, source, None, None, true, true);
+
+        assert_eq!(
+            docs.classes
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Kept"]
+        );
+        assert_eq!(
+            docs.skipped,
+            vec![
+                SkippedObject {
+                    name: String::from("_private"),
+                    line: Some(2),
+                    reason: SkipReason::Private,
+                },
+                SkippedObject {
+                    name: String::from("undocumented"),
+                    line: Some(5),
+                    reason: SkipReason::Undocumented,
+                },
+                SkippedObject {
+                    name: String::from("_hidden"),
+                    line: None,
+                    reason: SkipReason::Private,
+                },
+            ]
+        );
 
         Ok(())
     }
@@ -285,4 +536,91 @@ fn test_doc_extraction_export_non_list() -> Result<()> {
 
         Ok(())
     }
+    #[test]
+    fn test_doc_extraction_imports() -> Result<()> {
+        let expr = parse(
+            r#"
+import os, pkg.core as core
+from . import sibling
+from ..api import Client, Server
+
+if TYPE_CHECKING:
+    from typing import Any
+
+def lazy():
+    import json
+"#,
+            Mode::Module,
+            "<embedded>",
+        )?;
+        let docs = extract_module_documentation(&expr, None, None, true, true);
+
+        let imports: Vec<(&str, Vec<&str>)> = docs
+            .imports
+            .iter()
+            .map(|i| {
+                (
+                    i.module.as_str(),
+                    i.names.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("os", vec![]),
+                ("pkg.core", vec![]),
+                (".", vec!["sibling"]),
+                ("..api", vec!["Client", "Server"]),
+                ("typing", vec!["Any"]),
+                ("json", vec![]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_doc_extraction_records_lines() -> Result<()> {
+        let source = r#"
+def foo():
+    pass
+
+class Bar:
+    """Bar."""
+
+    def baz(self):
+        return 1
+"#;
+        let expr = parse(source, Mode::Module, "<embedded>")?;
+        let docs = extract_located_module_documentation(&expr, source, None, None, false, false);
+
+        assert_eq!(
+            docs.functions[0].lines,
+            Some(SourceLines { start: 2, end: 3 })
+        );
+        assert_eq!(
+            docs.classes[0].lines,
+            Some(SourceLines { start: 5, end: 9 })
+        );
+        assert_eq!(
+            docs.classes[0].methods[0].lines,
+            Some(SourceLines { start: 8, end: 9 })
+        );
+
+        let unlocated = extract_module_documentation(&expr, None, None, false, false);
+        assert_eq!(unlocated.functions[0].lines, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_path_drops_the_root_package() {
+        let module = ModuleDocumentation {
+            path: Some(PathBuf::from("pkg/sub/mod.py")),
+            ..ModuleDocumentation::default()
+        };
+        assert_eq!(module.relative_path(), Some(PathBuf::from("sub/mod.py")));
+        assert_eq!(ModuleDocumentation::default().relative_path(), None);
+    }
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+use super::module::ModuleDocumentation;
+
+/// The documentation of an entire package, modules are ordered by their path on disk
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageDocumentation {
+    pub name: String,
+    pub modules: Vec<ModuleDocumentation>,
+}
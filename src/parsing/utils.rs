@@ -1,10 +1,58 @@
-use color_eyre::Result;
+use color_eyre::{Report, Result};
 use rustpython_parser::{
-    Mode,
+    Mode, ParseError,
     ast::{Constant, Expr, ExprConstant, Mod, Stmt, StmtExpr},
     parse,
+    text_size::TextRange,
 };
-use std::{fs::File, io::Read, path::Path};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::Read,
+    path::Path,
+};
+
+/// The first and last line of a definition in its source file, 1-based and inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLines {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Maps the byte offsets of the AST ranges back to line numbers
+pub(crate) struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub(crate) fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    /// the 1-based line the byte at `offset` is on
+    pub(crate) fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// the 1-based column of the byte at `offset` in `source`, counted in characters
+    pub(crate) fn column(&self, source: &str, offset: usize) -> usize {
+        let line_start = self.line_starts[self.line(offset) - 1];
+        source
+            .get(line_start..offset)
+            .map_or(1, |before| before.chars().count() + 1)
+    }
+
+    pub(crate) fn lines(&self, range: TextRange) -> SourceLines {
+        SourceLines {
+            start: self.line(range.start().into()),
+            end: self.line(range.end().into()),
+        }
+    }
+}
 
 pub fn parse_python_file(path: &Path) -> Result<Mod> {
     let mut file = File::open(path)?;
@@ -15,10 +63,52 @@ pub fn parse_python_file(path: &Path) -> Result<Mod> {
 }
 
 pub fn parse_python_str(content: &str) -> Result<Mod> {
-    let parsed = parse(content, Mode::Module, "<embedded>");
-    Ok(parsed?)
+    parse(content, Mode::Module, "<embedded>")
+        .map_err(|error| SyntaxError::new(&error, content).into())
 }
 
+/// Where in the source parsing it failed. Located while the source is at hand, so reporting
+/// it later doesn't depend on the file still being the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// the line the error is on, `None` if it is past the end of the source
+    pub source_line: Option<String>,
+}
+
+impl SyntaxError {
+    fn new(error: &ParseError, source: &str) -> Self {
+        let offset = usize::from(error.offset);
+        let index = LineIndex::new(source);
+        let line = index.line(offset);
+        Self {
+            line,
+            column: index.column(source, offset),
+            message: error.error.to_string(),
+            source_line: source.lines().nth(line - 1).map(str::to_string),
+        }
+    }
+
+    /// the syntax error `error` is, if it is one
+    pub fn from_report(error: &Report) -> Option<&Self> {
+        error.downcast_ref()
+    }
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
 pub(crate) fn extract_docstring_from_body(body: &[Stmt]) -> Option<String> {
     match body.first() {
         Some(Stmt::Expr(StmtExpr { range: _, value })) => {
@@ -55,4 +145,41 @@ fn parse_empty_string() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn line_index_is_one_based() -> Result<()> {
+        let index = LineIndex::new("a\nbc\n\nd");
+        assert_eq!(index.line(0), 1);
+        // the newline still belongs to the line it ends
+        assert_eq!(index.line(1), 1);
+        assert_eq!(index.line(2), 2);
+        assert_eq!(index.line(5), 3);
+        assert_eq!(index.line(6), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn line_index_columns_count_characters() {
+        let source = "a\nµb = 1\n";
+        let index = LineIndex::new(source);
+        assert_eq!(index.column(source, 0), 1);
+        assert_eq!(index.column(source, 2), 1);
+        // `µ` is two bytes
+        assert_eq!(index.column(source, 4), 2);
+    }
+
+    #[test]
+    fn syntax_errors_are_located() {
+        let source = "a = 1\nb = 1 +\n";
+        let Err(error) = parse_python_str(source) else {
+            panic!("expected a syntax error");
+        };
+        let syntax_error = SyntaxError::from_report(&error);
+        assert_eq!(syntax_error.map(|e| e.line), Some(2));
+        assert_eq!(
+            syntax_error.and_then(|e| e.source_line.as_deref()),
+            Some("b = 1 +")
+        );
+        assert!(syntax_error.is_some_and(|e| !e.message.is_empty()));
+    }
 }
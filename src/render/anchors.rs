@@ -0,0 +1,39 @@
+//! Every documented object gets an explicit anchor derived from its fully qualified name,
+//! so links to it don't depend on how a particular SSG slugifies headers. Everything that
+//! links to an object (summary tables, the search index, the `objects.inv` writer) goes
+//! through here. Docstrings aren't scanned for cross references yet, a resolver for them
+//! should link through `object_link` as well.
+
+/// the anchor of an object, e.g. `pkg.mod.Class.method`.
+/// Qualified names never contain whitespace so they are valid ids as they are
+pub fn object_anchor(qualified_name: &str) -> String {
+    qualified_name.to_string()
+}
+
+/// a link to an object on `page`, relative to where the link is placed.
+/// An empty `page` links within the current page
+pub fn object_link(page: &str, qualified_name: &str) -> String {
+    format!("{page}#{}", object_anchor(qualified_name))
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{object_anchor, object_link};
+
+    #[test]
+    fn test_anchors_keep_dunders() -> Result<()> {
+        assert_eq!(
+            object_anchor("pkg.mod.Class.__init__"),
+            "pkg.mod.Class.__init__"
+        );
+        assert_eq!(object_link("", "pkg.f"), "#pkg.f");
+        assert_eq!(
+            object_link("sub/mod.md", "pkg.sub.mod.f"),
+            "sub/mod.md#pkg.sub.mod.f"
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,59 @@
 use rustpython_parser::ast::{Arg, ArgWithDefault, Arguments};
 
+use crate::parsing::function::{Parameter, ParameterKind};
+
 use super::expr::render_expr;
 
+/// renders the parameters extracted from a function signature, inserting the `/` and `*`
+/// markers where python requires them
+pub(crate) fn render_parameters(params: &[Parameter]) -> String {
+    let mut parts = vec![];
+    let has_vararg = params
+        .iter()
+        .any(|p| p.kind == ParameterKind::VarPositional);
+    let mut prev_kind = None;
+
+    for param in params {
+        if prev_kind == Some(ParameterKind::PositionalOnly)
+            && param.kind != ParameterKind::PositionalOnly
+        {
+            parts.push(String::from("/"));
+        }
+        if param.kind == ParameterKind::KeywordOnly
+            && !has_vararg
+            && prev_kind != Some(ParameterKind::KeywordOnly)
+        {
+            parts.push(String::from("*"));
+        }
+        parts.push(render_parameter(param));
+        prev_kind = Some(param.kind);
+    }
+    if prev_kind == Some(ParameterKind::PositionalOnly) {
+        parts.push(String::from("/"));
+    }
+
+    parts.join(", ")
+}
+
+pub(crate) fn render_parameter(param: &Parameter) -> String {
+    let mut out = String::new();
+    match param.kind {
+        ParameterKind::VarPositional => out.push('*'),
+        ParameterKind::VarKeyword => out.push_str("**"),
+        _ => (),
+    }
+    out.push_str(&param.name);
+    if let Some(annotation) = &param.annotation {
+        out.push_str(": ");
+        out.push_str(annotation);
+    }
+    if let Some(default) = &param.default {
+        out.push_str(" = ");
+        out.push_str(default);
+    }
+    out
+}
+
 pub(crate) fn render_args(args: Arguments) -> String {
     let mut out = String::new();
 
@@ -65,3 +117,47 @@ pub(crate) fn render_arg(arg: Arg) -> String {
 
     out
 }
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::parsing::{module::extract_module_documentation, utils::parse_python_str};
+
+    use super::render_parameters;
+
+    fn render_signature_params(src: &str) -> Result<String> {
+        let program = parse_python_str(src)?;
+        let documentation = extract_module_documentation(&program, None, None, false, false);
+        Ok(documentation
+            .functions
+            .first()
+            .map(|f| render_parameters(&f.args))
+            .unwrap_or_default())
+    }
+
+    #[test]
+    fn render_posonly_and_kwonly_markers() -> Result<()> {
+        assert_eq!(
+            render_signature_params("def f(a, /, b, *, c=1): pass")?,
+            "a, /, b, *, c = 1"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn render_only_varargs() -> Result<()> {
+        assert_eq!(
+            render_signature_params("def f(*args, **kwargs): pass")?,
+            "*args, **kwargs"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn render_only_posonly() -> Result<()> {
+        assert_eq!(render_signature_params("def f(a, b, /): pass")?, "a, b, /");
+        Ok(())
+    }
+}
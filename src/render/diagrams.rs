@@ -0,0 +1,288 @@
+//! Mermaid `classDiagram`s of the inheritance between the classes of a package. Bases are
+//! matched to classes of the package by name only, imports are not followed, so a base that
+//! is not defined in the package (or is ambiguous) is left out of the diagram.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    parsing::{class::ClassDocumentation, package::PackageDocumentation},
+    render::{
+        imports::{ImportGraphConfig, add_import_graph},
+        metadata::{PageKind, PageMetadata},
+        qualify,
+    },
+};
+
+/// The diagrams to draw on the pages, each one only if its section is in `snakedown.toml`
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct DiagramConfig {
+    pub class_diagrams: Option<ClassDiagramConfig>,
+    pub import_graph: Option<ImportGraphConfig>,
+}
+
+pub fn add_diagrams(package: &mut PackageDocumentation, config: &DiagramConfig) {
+    if let Some(class_diagrams) = &config.class_diagrams {
+        add_class_diagrams(package, class_diagrams);
+    }
+    if let Some(import_graph) = &config.import_graph {
+        add_import_graph(package, import_graph);
+    }
+}
+
+/// The `[class_diagrams]` section of `snakedown.toml`, diagrams are only drawn if it is present
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassDiagramConfig {
+    /// also list the public methods of every class
+    pub methods: bool,
+}
+
+/// Adds a diagram to every module that defines a class inheriting from another class of the
+/// package. The diagram of a package covers all classes in its sub modules as well.
+pub fn add_class_diagrams(package: &mut PackageDocumentation, config: &ClassDiagramConfig) {
+    let hierarchy = ClassHierarchy::new(package);
+    let diagrams: Vec<(usize, Option<String>)> = package
+        .modules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, module)| {
+            let page = PageMetadata::from(module);
+            let title = page.title?;
+            let is_package = page.kind == PageKind::Package;
+            let nested = format!("{title}.");
+            let roots = hierarchy
+                .classes
+                .keys()
+                .filter(|q| {
+                    let module_name = q.rsplit_once('.').map(|(m, _)| m).unwrap_or_default();
+                    module_name == title || (is_package && module_name.starts_with(&nested))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            Some((index, hierarchy.render(&roots, &title, config)))
+        })
+        .collect();
+    for (index, diagram) in diagrams {
+        package.modules[index].class_diagram = diagram;
+    }
+}
+
+struct ClassHierarchy<'a> {
+    /// every class of the package by its qualified name
+    classes: BTreeMap<String, &'a ClassDocumentation>,
+    /// the qualified names of the bases of every class that are defined in the package
+    bases: BTreeMap<String, Vec<String>>,
+}
+
+impl<'a> ClassHierarchy<'a> {
+    fn new(package: &'a PackageDocumentation) -> Self {
+        let mut classes = BTreeMap::new();
+        for module in &package.modules {
+            let title = PageMetadata::from(module).title;
+            for class_docs in &module.classes {
+                classes.insert(qualify(title.as_deref(), &class_docs.name), class_docs);
+            }
+        }
+
+        let bases = classes
+            .iter()
+            .map(|(qualified_name, class_docs)| {
+                let module_name = qualified_name.rsplit_once('.').map(|(m, _)| m);
+                let resolved = class_docs
+                    .bases
+                    .iter()
+                    .filter_map(|base| resolve_base(base, module_name, &classes))
+                    .collect();
+                (qualified_name.clone(), resolved)
+            })
+            .collect();
+
+        Self { classes, bases }
+    }
+
+    /// a diagram of `roots` and all their ancestors in the package, `None` if none of them
+    /// inherit from a class of the package. Classes of the module `title` are labeled by
+    /// their name, others by their qualified name.
+    fn render(&self, roots: &[String], title: &str, config: &ClassDiagramConfig) -> Option<String> {
+        let mut visited = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+        let mut todo = roots.to_vec();
+        while let Some(class_name) = todo.pop() {
+            if !visited.insert(class_name.clone()) {
+                continue;
+            }
+            for base in self.bases.get(&class_name).into_iter().flatten() {
+                edges.insert((base.clone(), class_name.clone()));
+                todo.push(base.clone());
+            }
+        }
+        if edges.is_empty() {
+            return None;
+        }
+        // classes without any relation in the package would only clutter the diagram
+        let nodes: BTreeSet<_> = edges.iter().flat_map(|(b, d)| [b, d]).collect();
+        let ids = NodeIds::new(nodes.iter().map(|name| name.as_str()));
+
+        let mut out = String::from("classDiagram\n");
+        for class_name in nodes {
+            let label = class_name
+                .strip_prefix(title)
+                .and_then(|name| name.strip_prefix('.'))
+                .filter(|name| !name.contains('.'))
+                .unwrap_or(class_name);
+            out.push_str(&format!("    class {}[\"{label}\"]\n", ids.get(class_name)));
+            if config.methods {
+                let methods = self.classes[class_name]
+                    .methods
+                    .iter()
+                    .filter(|m| !m.name.starts_with('_'));
+                for method in methods {
+                    out.push_str(&format!(
+                        "    {} : +{}()\n",
+                        ids.get(class_name),
+                        method.name
+                    ));
+                }
+            }
+        }
+        for (base, derived) in &edges {
+            out.push_str(&format!(
+                "    {} <|-- {}\n",
+                ids.get(base),
+                ids.get(derived)
+            ));
+        }
+        Some(out)
+    }
+}
+
+/// Finds the class `base` refers to, trying a class of the same module first, then a fully
+/// qualified name and finally the only class whose qualified name ends in `base`
+fn resolve_base(
+    base: &str,
+    module_name: Option<&str>,
+    classes: &BTreeMap<String, &ClassDocumentation>,
+) -> Option<String> {
+    let local = qualify(module_name, base);
+    if classes.contains_key(&local) {
+        return Some(local);
+    }
+    if classes.contains_key(base) {
+        return Some(base.to_string());
+    }
+    let suffix = format!(".{base}");
+    let mut candidates = classes.keys().filter(|q| q.ends_with(&suffix));
+    match (candidates.next(), candidates.next()) {
+        (Some(only), None) => Some(only.clone()),
+        _ => None,
+    }
+}
+
+/// The mermaid ids of the nodes of one diagram. Mermaid ids can't contain dots, so every
+/// node gets its index as id and its qualified name as label. Escaping the name instead
+/// would make e.g. `pkg.a_b` and `pkg.a.b` the same node.
+pub(crate) struct NodeIds<'a>(BTreeMap<&'a str, String>);
+
+impl<'a> NodeIds<'a> {
+    pub(crate) fn new(names: impl IntoIterator<Item = &'a str>) -> Self {
+        Self(
+            names
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| (name, format!("n{index}")))
+                .collect(),
+        )
+    }
+
+    /// the id of `name`, which has to be one of the nodes
+    pub(crate) fn get(&self, name: &str) -> &str {
+        &self.0[name]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{ClassDiagramConfig, add_class_diagrams};
+    use crate::parsing::{
+        module::{ModuleDocumentation, extract_module_documentation},
+        package::PackageDocumentation,
+        utils::parse_python_str,
+    };
+
+    fn module(
+        name: &str,
+        prefix: Option<&str>,
+        path: &str,
+        source: &str,
+    ) -> Result<ModuleDocumentation> {
+        let program = parse_python_str(source)?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some(name.to_string()),
+            prefix.map(str::to_string),
+            false,
+            false,
+        );
+        mod_doc.path = Some(path.into());
+        Ok(mod_doc)
+    }
+
+    fn test_package() -> Result<PackageDocumentation> {
+        Ok(PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![
+                module("pkg", None, "pkg/__init__.py", "")?,
+                module(
+                    "base",
+                    Some("pkg"),
+                    "pkg/base.py",
+                    "class Plugin:\n    def load(self): pass\n    def _setup(self): pass\n",
+                )?,
+                module(
+                    "plugins",
+                    Some("pkg"),
+                    "pkg/plugins.py",
+                    "class Csv(base.Plugin, Exception): pass\nclass Tsv(Csv): pass\nclass Other: pass\n",
+                )?,
+            ],
+        })
+    }
+
+    #[test]
+    fn test_module_diagram_includes_ancestors() -> Result<()> {
+        let mut package = test_package()?;
+        add_class_diagrams(&mut package, &ClassDiagramConfig::default());
+
+        assert_eq!(package.modules[1].class_diagram, None);
+        assert_eq!(
+            package.modules[2].class_diagram.as_deref(),
+            Some(
+                r#"classDiagram
+    class n0["pkg.base.Plugin"]
+    class n1["Csv"]
+    class n2["Tsv"]
+    n0 <|-- n1
+    n1 <|-- n2
+"#
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_diagram_with_methods() -> Result<()> {
+        let mut package = test_package()?;
+        add_class_diagrams(&mut package, &ClassDiagramConfig { methods: true });
+
+        let diagram = package.modules[0].class_diagram.clone().unwrap_or_default();
+        assert!(diagram.contains("    n0 : +load()\n"));
+        assert!(!diagram.contains("_setup"));
+        assert!(diagram.contains("    n0 <|-- n1\n"));
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use rustpython_parser::ast::{CmpOp, Comprehension, Constant, Expr, Keyword, Operator, UnaryOp};
+use rustpython_parser::ast::{
+    CmpOp, Comprehension, Constant, ConversionFlag, Expr, ExprConstant, Keyword, Operator, UnaryOp,
+};
 
 use super::args::render_args;
 
@@ -194,8 +196,22 @@ pub fn render_expr(expr: Expr) -> String {
             }
             out.push(')');
         }
-        Expr::FormattedValue(expr_formatted_value) => todo!(),
-        Expr::JoinedStr(expr_joined_str) => todo!(),
+        Expr::FormattedValue(expr_formatted_value) => {
+            out.push_str(&format!(
+                "f\"{}\"",
+                render_fstring_part(Expr::FormattedValue(expr_formatted_value))
+            ));
+        }
+        Expr::JoinedStr(expr_joined_str) => {
+            out.push_str(&format!(
+                "f\"{}\"",
+                expr_joined_str
+                    .values
+                    .into_iter()
+                    .map(render_fstring_part)
+                    .collect::<String>()
+            ));
+        }
         Expr::Constant(expr_constant) => out.push_str(&render_constant(expr_constant.value)),
         Expr::Attribute(expr_attribute) => {
             out.push_str(&format!(
@@ -269,6 +285,41 @@ fn render_comprehension(comp: Comprehension) -> String {
     out
 }
 
+/// render one piece of an f-string, without the surrounding `f"..."`
+fn render_fstring_part(part: Expr) -> String {
+    match part {
+        Expr::Constant(ExprConstant {
+            value: Constant::Str(s),
+            ..
+        }) => s.replace('{', "{{").replace('}', "}}"),
+        Expr::FormattedValue(formatted) => {
+            let conversion = match formatted.conversion {
+                ConversionFlag::None => "",
+                ConversionFlag::Str => "!s",
+                ConversionFlag::Ascii => "!a",
+                ConversionFlag::Repr => "!r",
+            };
+            let format_spec = match formatted.format_spec.map(|spec| *spec) {
+                Some(Expr::JoinedStr(spec)) => format!(
+                    ":{}",
+                    spec.values
+                        .into_iter()
+                        .map(render_fstring_part)
+                        .collect::<String>()
+                ),
+                Some(spec) => format!(":{}", render_fstring_part(spec)),
+                None => String::new(),
+            };
+            format!(
+                "{{{}{conversion}{format_spec}}}",
+                render_expr(*formatted.value)
+            )
+        }
+        Expr::JoinedStr(joined) => joined.values.into_iter().map(render_fstring_part).collect(),
+        other => format!("{{{}}}", render_expr(other)),
+    }
+}
+
 fn render_keyword(keyword: Keyword) -> String {
     let mut out = String::new();
     let fmt = if let Some(arg) = keyword.arg {
@@ -292,7 +343,14 @@ pub(crate) fn render_constant(constant: Constant) -> String {
             }
         }
         Constant::Str(s) => format!("\"{s}\""),
-        Constant::Bytes(_) => todo!(),
+        Constant::Bytes(bytes) => format!(
+            "b\"{}\"",
+            bytes
+                .into_iter()
+                .flat_map(std::ascii::escape_default)
+                .map(char::from)
+                .collect::<String>()
+        ),
         Constant::Int(big_int) => format!("{big_int}"),
         Constant::Tuple(constants) => format!(
             "({})",
@@ -394,6 +452,18 @@ fn test_render_name() -> Result<()> {
         Ok(())
     }
 
+    #[test]
+    fn test_render_fstring() -> Result<()> {
+        let s = r#"f"{BASE}/api/{{v}}/{version!r:>{width}}""#;
+        let expr = get_expr(s)?;
+
+        let rendered = render_expr(expr);
+
+        assert_eq!(rendered, s);
+
+        Ok(())
+    }
+
     #[test]
     fn test_render_int() -> Result<()> {
         let s = "24";
@@ -1030,6 +1100,17 @@ fn test_generator_exp() -> Result<()> {
 
         assert_eq!(rendered, s);
 
+        Ok(())
+    }
+    #[test]
+    fn test_bytes() -> Result<()> {
+        let s = r#"b"abc\n""#;
+        let expr = get_expr(s)?;
+
+        let rendered = render_expr(expr);
+
+        assert_eq!(rendered, s);
+
         Ok(())
     }
 }
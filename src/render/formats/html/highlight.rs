@@ -0,0 +1,172 @@
+use super::escape_html;
+
+const KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda",
+    "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+const CONSTANTS: &[&str] = &["None", "True", "False", "Ellipsis", "NotImplemented"];
+
+const BUILTINS: &[&str] = &[
+    "bool",
+    "bytes",
+    "complex",
+    "dict",
+    "float",
+    "frozenset",
+    "int",
+    "list",
+    "object",
+    "print",
+    "range",
+    "set",
+    "str",
+    "tuple",
+    "type",
+    "len",
+    "self",
+    "cls",
+];
+
+const STRING_PREFIXES: &[&str] = &[
+    "r", "u", "b", "f", "br", "rb", "fr", "rf", "R", "U", "B", "F", "Br", "bR", "BR", "Rb", "rB",
+    "RB", "Fr", "fR", "FR", "Rf", "rF", "RF",
+];
+
+/// A very small python highlighter. It does not try to be correct for every edge case
+/// of the python grammar, only good enough for signatures and short examples.
+/// Tokens are wrapped in `<span>`s with the following classes:
+/// `kw` (keywords), `const` (`None`, `True`, ...), `bi` (common builtins),
+/// `str`, `num`, `com` (comments) and `op` (operators).
+pub fn highlight_python(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '#' {
+            let end = chars[i..]
+                .iter()
+                .position(|c| *c == '\n')
+                .map_or(chars.len(), |p| i + p);
+            push_span(&mut out, "com", &chars[i..end]);
+            i = end;
+        } else if c == '"' || c == '\'' {
+            let end = string_end(&chars, i);
+            push_span(&mut out, "str", &chars[i..end]);
+            i = end;
+        } else if c.is_ascii_digit() {
+            let end = chars[i..]
+                .iter()
+                .position(|c| !(c.is_ascii_alphanumeric() || *c == '.' || *c == '_'))
+                .map_or(chars.len(), |p| i + p);
+            push_span(&mut out, "num", &chars[i..end]);
+            i = end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = chars[i..]
+                .iter()
+                .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                .map_or(chars.len(), |p| i + p);
+            let word: String = chars[i..end].iter().collect();
+            let next = chars.get(end);
+            if STRING_PREFIXES.contains(&word.as_str()) && matches!(next, Some('"' | '\'')) {
+                let string_end = string_end(&chars, end);
+                push_span(&mut out, "str", &chars[i..string_end]);
+                i = string_end;
+                continue;
+            }
+            if KEYWORDS.contains(&word.as_str()) {
+                push_span(&mut out, "kw", &chars[i..end]);
+            } else if CONSTANTS.contains(&word.as_str()) {
+                push_span(&mut out, "const", &chars[i..end]);
+            } else if BUILTINS.contains(&word.as_str()) {
+                push_span(&mut out, "bi", &chars[i..end]);
+            } else {
+                out.push_str(&escape_html(&word));
+            }
+            i = end;
+        } else if "+-*/%=<>!&|^~@:".contains(c) {
+            let end = chars[i..]
+                .iter()
+                .position(|c| !"+-*/%=<>!&|^~@:".contains(*c))
+                .map_or(chars.len(), |p| i + p);
+            push_span(&mut out, "op", &chars[i..end]);
+            i = end;
+        } else {
+            out.push_str(&escape_html(&c.to_string()));
+            i += 1;
+        }
+    }
+
+    out
+}
+
+/// returns the index one past the end of the string literal starting with the quote at `start`
+fn string_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let triple = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = if triple { start + 3 } else { start + 1 };
+    while i < chars.len() {
+        if chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if triple {
+            if chars[i] == quote
+                && chars.get(i + 1) == Some(&quote)
+                && chars.get(i + 2) == Some(&quote)
+            {
+                return i + 3;
+            }
+        } else if chars[i] == quote || chars[i] == '\n' {
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+fn push_span(out: &mut String, class: &str, content: &[char]) {
+    let content: String = content.iter().collect();
+    out.push_str(&format!(
+        "<span class=\"{class}\">{}</span>",
+        escape_html(&content)
+    ));
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::highlight_python;
+
+    #[test]
+    fn highlight_signature() -> Result<()> {
+        assert_eq!(
+            highlight_python(r#"greet(name: str = "world") -> None"#),
+            r#"greet(name<span class="op">:</span> <span class="bi">str</span> <span class="op">=</span> <span class="str">&quot;world&quot;</span>) <span class="op">-&gt;</span> <span class="const">None</span>"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn highlight_comments_and_numbers() -> Result<()> {
+        assert_eq!(
+            highlight_python("x = 0x1F # <b>"),
+            r#"x <span class="op">=</span> <span class="num">0x1F</span> <span class="com"># &lt;b&gt;</span>"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn highlight_prefixed_and_triple_strings() -> Result<()> {
+        assert_eq!(
+            highlight_python(r#"f'{a}' + """it's""""#),
+            r#"<span class="str">f&#39;{a}&#39;</span> <span class="op">+</span> <span class="str">&quot;&quot;&quot;it&#39;s&quot;&quot;&quot;</span>"#
+        );
+        Ok(())
+    }
+}
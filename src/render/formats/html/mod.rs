@@ -0,0 +1,551 @@
+pub mod highlight;
+
+use std::{
+    fs::{File, create_dir_all},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::Result;
+
+use crate::{
+    fs::PackageIndex,
+    parsing::{
+        attribute::AttributeDocumentation, function::FunctionDocumentation,
+        module::ModuleDocumentation, package::PackageDocumentation,
+    },
+    render::{
+        dedent_docstring, format_declaration, format_signature,
+        formats::{Admonition, Renderer},
+        link_target,
+        metadata::PageMetadata,
+        render_module, translate_filename,
+    },
+};
+use highlight::highlight_python;
+
+/// The only stylesheet of the site, written next to the root `index.html`.
+/// It is bundled into the binary so the site works offline.
+pub const STYLESHEET: &str = include_str!("snakedown.css");
+pub const STYLESHEET_NAME: &str = "snakedown.css";
+
+/// Renders complete html pages. Created with `for_site` every page also gets the navigation
+/// sidebar of the site, otherwise pages stand on their own.
+#[derive(Default)]
+pub struct HtmlRenderer {
+    nav: Option<NavEntry>,
+}
+
+impl HtmlRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// a renderer for the site documenting the package `pkg_index` indexes
+    pub fn for_site(pkg_index: &PackageIndex, pkg_path: &Path, pkg_name: &str) -> Self {
+        Self {
+            nav: Some(NavEntry::package(
+                pkg_index,
+                pkg_path,
+                Path::new(""),
+                pkg_name,
+            )),
+        }
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn render_header(&self, content: &str, level: usize) -> String {
+        // html only knows h1 through h6
+        let level = level.clamp(1, 6);
+        let escaped = escape_html(content);
+        format!(
+            "<h{level} id=\"{escaped}\">{escaped}<a class=\"anchor\" href=\"#{escaped}\">¶</a></h{level}>\n"
+        )
+    }
+
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        if let Some(t) = &page.title {
+            self.render_header(t, 1)
+        } else {
+            String::new()
+        }
+    }
+
+    fn render_signature(&self, fn_docs: &FunctionDocumentation) -> String {
+        let mut signature = String::new();
+        if fn_docs.is_async {
+            signature.push_str("async ");
+        }
+        signature.push_str(&format_signature(fn_docs));
+        format!(
+            "<pre class=\"signature\"><code>{}</code></pre>\n",
+            highlight_python(&signature)
+        )
+    }
+
+    fn render_declaration(&self, attr_docs: &AttributeDocumentation) -> String {
+        format!(
+            "<pre class=\"signature\"><code>{}</code></pre>\n",
+            highlight_python(&format_declaration(attr_docs))
+        )
+    }
+
+    fn render_docstring(&self, docstring: &str) -> String {
+        render_docstring_html(docstring)
+    }
+
+    fn render_code_block(&self, code: &str, language: Option<&str>) -> String {
+        render_code_html(code, language.unwrap_or_default())
+    }
+
+    fn render_inline_code(&self, code: &str) -> String {
+        format!("<code>{}</code>", escape_html(code))
+    }
+
+    fn render_link(&self, text: &str, target: &str) -> String {
+        format!(
+            "<a href=\"{}\">{}</a>",
+            escape_html(target),
+            escape_html(text)
+        )
+    }
+
+    // headers already use their content as id
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        self.render_header(qualified_name, level)
+    }
+
+    fn render_source_link(&self, url: &str) -> String {
+        format!(
+            "<p class=\"source\">{}</p>\n",
+            self.render_link("source", url)
+        )
+    }
+
+    // picked up by mermaid.js if the page loads it, readable as is otherwise
+    fn render_mermaid(&self, diagram: &str) -> String {
+        format!(
+            "<pre class=\"mermaid\">{}</pre>\n",
+            escape_html(diagram.trim_end())
+        )
+    }
+
+    fn render_anchor(&self, id: &str) -> String {
+        format!("<a id=\"{}\"></a>", escape_html(id))
+    }
+
+    fn render_admonition(&self, kind: Admonition, content: &str) -> String {
+        format!(
+            "<div class=\"admonition {}\">\n<p class=\"admonition-title\">{kind}</p>\n{}\n</div>\n",
+            kind.to_string().to_lowercase(),
+            content.trim()
+        )
+    }
+
+    fn render_table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        let mut out = String::from("<table>\n<thead>\n<tr>");
+        for header in headers {
+            out.push_str(&format!("<th>{}</th>", escape_html(header)));
+        }
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+        for row in rows {
+            out.push_str("<tr>");
+            for cell in row {
+                out.push_str(&format!("<td>{cell}</td>"));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</tbody>\n</table>\n");
+        out
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        escape_html(text)
+    }
+
+    fn render_list(&self, items: &[String]) -> String {
+        let mut out = String::from("<ul>\n");
+        for item in items {
+            out.push_str(&format!("<li>{item}</li>\n"));
+        }
+        out.push_str("</ul>\n");
+        out
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> String {
+        let mut out = String::from("<dl>\n");
+        for (term, definition) in items {
+            out.push_str(&format!(
+                "<dt>{term}</dt>\n<dd>{}</dd>\n",
+                definition.trim()
+            ));
+        }
+        out.push_str("</dl>\n");
+        out
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "html"
+    }
+
+    // so directories can be browsed directly
+    fn index_file_stem(&self) -> &'static str {
+        "index"
+    }
+
+    fn render_module_page(&self, mod_doc: ModuleDocumentation) -> Result<String> {
+        let title = PageMetadata::from(&mod_doc).title.unwrap_or_default();
+        let page_path = mod_doc
+            .relative_path()
+            .map(|path| translate_filename(&path, self))
+            .unwrap_or_default();
+        let root_href = root_href(&page_path);
+
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        out.push_str(&format!("<title>{}</title>\n", escape_html(&title)));
+        out.push_str(&format!(
+            "<link rel=\"stylesheet\" href=\"{root_href}{STYLESHEET_NAME}\">\n</head>\n<body>\n"
+        ));
+        if let Some(nav) = &self.nav {
+            out.push_str(&render_nav(nav, &page_path));
+        }
+        out.push_str("<main>\n");
+        out.push_str(&render_module(mod_doc, self));
+        out.push_str("</main>\n</body>\n</html>\n");
+        Ok(out)
+    }
+}
+
+pub(crate) fn escape_html(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    for c in content.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Writes one page per module plus the stylesheet to `out_path`.
+/// `pkg_index` is used to build the navigation sidebar shared by all pages.
+/// Returns the written files.
+pub fn render_html_site(
+    package: &PackageDocumentation,
+    pkg_path: &Path,
+    pkg_index: &PackageIndex,
+    out_path: &Path,
+) -> Result<Vec<PathBuf>> {
+    let renderer = HtmlRenderer::for_site(pkg_index, pkg_path, &package.name);
+    create_dir_all(out_path)?;
+
+    let stylesheet_path = out_path.join(STYLESHEET_NAME);
+    let mut stylesheet = File::create(&stylesheet_path)?;
+    stylesheet.write_all(STYLESHEET.as_bytes())?;
+    let mut written = vec![stylesheet_path];
+
+    for mod_doc in &package.modules {
+        let Some(rel_path) = mod_doc.relative_path() else {
+            tracing::warn!("skipping module without a known path");
+            continue;
+        };
+        let full_write_path = out_path.join(translate_filename(&rel_path, &renderer));
+        if let Some(parent) = full_write_path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let rendered = renderer.render_module_page(mod_doc.clone())?;
+        tracing::debug!("writing html page to {}", &full_write_path.display());
+        let mut file = File::create(&full_write_path)?;
+        file.write_all(rendered.as_bytes())?;
+        written.push(full_write_path);
+    }
+
+    Ok(written)
+}
+
+/// relative href from the page at `page_path` back to the root of the site
+fn root_href(page_path: &Path) -> String {
+    "../".repeat(page_path.components().count().saturating_sub(1))
+}
+
+fn href(root_href: &str, target: &Path) -> String {
+    format!("{root_href}{}", link_target(target))
+}
+
+/// An entry of the navigation sidebar, packages list their sub packages and modules
+#[derive(Clone, Debug, Default)]
+struct NavEntry {
+    name: String,
+    /// the page of the entry relative to the root of the site
+    page: PathBuf,
+    children: Vec<NavEntry>,
+}
+
+impl NavEntry {
+    fn package(pkg_index: &PackageIndex, dir: &Path, rel_dir: &Path, name: &str) -> Self {
+        let renderer = HtmlRenderer::new();
+        let mut children = vec![];
+        for child in pkg_index.sub_module_index.get(dir).into_iter().flatten() {
+            let mut components = child.components();
+            match (components.next(), components.next()) {
+                // sub packages are indexed by their `__init__.py`
+                (Some(sub_pkg), Some(_)) => {
+                    let sub_pkg = sub_pkg.as_os_str();
+                    children.push(Self::package(
+                        pkg_index,
+                        &dir.join(sub_pkg),
+                        &rel_dir.join(sub_pkg),
+                        &sub_pkg.to_string_lossy(),
+                    ));
+                }
+                (Some(module), None) => children.push(Self {
+                    name: Path::new(module.as_os_str())
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    page: translate_filename(&rel_dir.join(child), &renderer),
+                    children: vec![],
+                }),
+                _ => (),
+            }
+        }
+        Self {
+            name: name.to_string(),
+            page: translate_filename(&rel_dir.join("__init__.py"), &renderer),
+            children,
+        }
+    }
+
+    fn render(&self, out: &mut String, current: &Path, root_href: &str) {
+        let class = if self.page == current {
+            " class=\"current\""
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "<li><a href=\"{}\"{class}>{}</a>",
+            href(root_href, &self.page),
+            escape_html(&self.name)
+        ));
+        if !self.children.is_empty() {
+            out.push_str("\n<ul>\n");
+            for child in &self.children {
+                child.render(out, current, root_href);
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</li>\n");
+    }
+}
+
+fn render_nav(nav: &NavEntry, current: &Path) -> String {
+    let mut out = String::from("<nav class=\"sidebar\">\n<ul>\n");
+    nav.render(&mut out, current, &root_href(current));
+    out.push_str("</ul>\n</nav>\n");
+    out
+}
+
+/// Renders a docstring as html. Fenced code blocks and doctest blocks (starting with `>>>`)
+/// are highlighted, everything else becomes a paragraph with its line breaks preserved.
+pub(crate) fn render_docstring_html(docstring: &str) -> String {
+    let dedented = dedent_docstring(docstring);
+    let lines = dedented.trim().lines().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim_start();
+        if trimmed.is_empty() {
+            i += 1;
+        } else if let Some(info) = trimmed.strip_prefix("```") {
+            let lang = info.trim();
+            let mut code = vec![];
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with("```") {
+                code.push(lines[i]);
+                i += 1;
+            }
+            // skip the closing fence
+            i += 1;
+            out.push_str(&render_code_html(&code.join("\n"), lang));
+        } else if trimmed.starts_with(">>>") {
+            let mut block = vec![];
+            while i < lines.len()
+                && !lines[i].trim().is_empty()
+                && !lines[i].trim_start().starts_with("```")
+            {
+                block.push(render_doctest_line(lines[i]));
+                i += 1;
+            }
+            out.push_str(&format!(
+                "<pre class=\"code doctest\"><code>{}</code></pre>\n",
+                block.join("\n")
+            ));
+        } else {
+            let mut paragraph = vec![];
+            while i < lines.len() {
+                let trimmed = lines[i].trim_start();
+                if trimmed.is_empty() || trimmed.starts_with("```") || trimmed.starts_with(">>>") {
+                    break;
+                }
+                paragraph.push(lines[i]);
+                i += 1;
+            }
+            out.push_str(&format!("<p>{}</p>\n", escape_html(&paragraph.join("\n"))));
+        }
+    }
+
+    out
+}
+
+/// code without a language is assumed to be python, as it is in a python docstring
+fn render_code_html(code: &str, lang: &str) -> String {
+    let body = if matches!(lang, "" | "py" | "python" | "python3" | "pycon") {
+        highlight_python(code)
+    } else {
+        escape_html(code)
+    };
+    format!("<pre class=\"code\"><code>{body}</code></pre>\n")
+}
+
+fn render_doctest_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    for prompt in [">>>", "..."] {
+        if let Some(code) = trimmed.strip_prefix(prompt) {
+            return format!(
+                "{indent}<span class=\"gp\">{}</span>{}",
+                escape_html(prompt),
+                highlight_python(code)
+            );
+        }
+    }
+    escape_html(line)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::read_to_string, path::PathBuf};
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        config::ConfigBuilder,
+        render::{SSG, imports::ImportGraphConfig},
+        render_html_docs,
+    };
+
+    #[test]
+    fn test_html_header_has_anchor() -> Result<()> {
+        assert_eq!(
+            HtmlRenderer::new().render_header("foo.<bar>", 2),
+            "<h2 id=\"foo.&lt;bar&gt;\">foo.&lt;bar&gt;<a class=\"anchor\" href=\"#foo.&lt;bar&gt;\">¶</a></h2>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_blocks_escape_text() -> Result<()> {
+        let renderer = HtmlRenderer::new();
+        assert_eq!(
+            renderer.render_link("a < b", "page.html#a"),
+            "<a href=\"page.html#a\">a &lt; b</a>"
+        );
+        assert_eq!(
+            renderer.render_table(&["Name"], &[vec![renderer.render_inline_code("<T>")]]),
+            "<table>\n<thead>\n<tr><th>Name</th></tr>\n</thead>\n<tbody>\n<tr><td><code>&lt;T&gt;</code></td></tr>\n</tbody>\n</table>\n"
+        );
+        assert_eq!(
+            renderer.render_admonition(Admonition::Note, "<p>hi</p>"),
+            "<div class=\"admonition note\">\n<p class=\"admonition-title\">Note</p>\n<p>hi</p>\n</div>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_filename() -> Result<()> {
+        assert_eq!(
+            translate_filename(&PathBuf::from("foo/__init__.py"), &HtmlRenderer::new()),
+            PathBuf::from("foo/index.html")
+        );
+        assert_eq!(
+            translate_filename(&PathBuf::from("foo/bar.py"), &HtmlRenderer::new()),
+            PathBuf::from("foo/bar.html")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_docstring_html_blocks() -> Result<()> {
+        let docstring = r"
+        Return the square of a number.
+
+        Example:
+            >>> square(4)
+            16
+
+        ```text
+        <raw>
+        ```
+        ";
+        assert_eq!(
+            render_docstring_html(docstring),
+            r#"<p>Return the square of a number.</p>
+<p>Example:</p>
+<pre class="code doctest"><code>    <span class="gp">&gt;&gt;&gt;</span> square(<span class="num">4</span>)
+    16</code></pre>
+<pre class="code"><code>&lt;raw&gt;</code></pre>
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn render_test_pkg_html_site() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+
+        let config = ConfigBuilder::default()
+            .with_pkg_path(Some(PathBuf::from("tests/test_pkg")))
+            .with_output_dir(Some(temp_dir.path().to_path_buf()))
+            .with_ssg(Some(SSG::Html))
+            .with_skip_private(Some(true))
+            .with_exclude(Some(vec![
+                PathBuf::from("test_pkg/excluded_file.py"),
+                PathBuf::from("test_pkg/excluded_module"),
+            ]))
+            .with_import_graph(Some(ImportGraphConfig::default()))
+            .build()?;
+        render_html_docs(&config, None)?;
+
+        assert!(temp_dir.path().join(STYLESHEET_NAME).exists());
+        assert!(temp_dir.path().join("index.html").exists());
+        assert!(temp_dir.path().join("sub1/index.html").exists());
+        assert!(!temp_dir.path().join("_private/index.html").exists());
+
+        let mid = read_to_string(temp_dir.path().join("sub1/mid.html"))?;
+        assert!(mid.contains("href=\"../snakedown.css\""));
+        assert!(mid.contains("id=\"test_pkg.sub1.mid.square\""));
+        assert!(mid.contains("id=\"test_pkg.sub1.mid.Squarer.square_number\""));
+        assert!(mid.contains("<a href=\"#test_pkg.sub1.mid.square\">square</a>"));
+        assert!(mid.contains("<a href=\"../index.html\">test_pkg</a>"));
+        assert!(mid.contains("<a href=\"../sub1/mid.html\" class=\"current\">mid</a>"));
+        assert!(mid.contains("<a href=\"../sub1/sub2/one.html\">one</a>"));
+        // everything has to work offline
+        assert!(!mid.contains("http"));
+
+        let index = read_to_string(temp_dir.path().join("index.html"))?;
+        assert!(index.contains("<pre class=\"mermaid\">flowchart"));
+
+        Ok(())
+    }
+}
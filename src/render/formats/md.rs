@@ -1,4 +1,4 @@
-use crate::render::formats::Renderer;
+use crate::render::{formats::Renderer, metadata::PageMetadata};
 
 #[derive(Default)]
 pub struct MdRenderer {}
@@ -10,15 +10,16 @@ pub fn new() -> Self {
 impl Renderer for MdRenderer {
     fn render_header(&self, content: &str, level: usize) -> String {
         let mut out = String::new();
-        out.push_str(&"#".repeat(level));
+        // markdown has no headers deeper than 6, which deeply nested single pages can reach
+        out.push_str(&"#".repeat(level.clamp(1, 6)));
         out.push(' ');
         out.push_str(content);
         out.push('\n');
         out
     }
 
-    fn render_front_matter(&self, title: Option<&str>) -> String {
-        if let Some(t) = title {
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        if let Some(t) = &page.title {
             self.render_header(t, 1)
         } else {
             String::new()
@@ -30,7 +31,12 @@ fn render_front_matter(&self, title: Option<&str>) -> String {
 mod test {
     use color_eyre::Result;
 
-    use crate::render::formats::{Renderer, md::MdRenderer};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        parsing::{module::extract_module_documentation, utils::parse_python_str},
+        render::formats::{Admonition, Renderer, md::MdRenderer},
+    };
     #[test]
     fn test_render_md_header() -> Result<()> {
         let text = String::from("foo");
@@ -38,4 +44,75 @@ fn test_render_md_header() -> Result<()> {
         assert_eq!(out, String::from("# foo\n"));
         Ok(())
     }
+
+    #[test]
+    fn test_md_code_fences_outgrow_contents() -> Result<()> {
+        let renderer = MdRenderer::new();
+        assert_eq!(
+            renderer.render_code_block("x = 1\n", Some("python")),
+            "```python\nx = 1\n```\n"
+        );
+        assert_eq!(
+            renderer.render_code_block("```\nnested\n```", None),
+            "````\n```\nnested\n```\n````\n"
+        );
+        assert_eq!(renderer.render_inline_code("foo"), "`foo`");
+        assert_eq!(renderer.render_inline_code("a`b"), "``a`b``");
+        assert_eq!(renderer.render_inline_code("`"), "`` ` ``");
+        Ok(())
+    }
+
+    #[test]
+    fn test_md_link_and_anchor() -> Result<()> {
+        let renderer = MdRenderer::new();
+        assert_eq!(
+            renderer.render_link("__init__", "#pkg.Foo.__init__"),
+            "[\\_\\_init\\_\\_](#pkg.Foo.__init__)"
+        );
+        assert_eq!(
+            renderer.render_object_header("pkg.Foo.__init__", 3),
+            "### pkg.Foo.__init__ <a id=\"pkg.Foo.__init__\"></a>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_md_admonition() -> Result<()> {
+        assert_eq!(
+            MdRenderer::new().render_admonition(Admonition::Warning, "Careful.\n\nReally."),
+            "> [!WARNING]\n> Careful.\n>\n> Really.\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_md_parameter_table() -> Result<()> {
+        let program = parse_python_str("def f(a: int | None, *args, b=1): pass")?;
+        let mod_doc = extract_module_documentation(&program, None, None, false, false);
+        let params = &mod_doc.functions[0].args;
+
+        assert_eq!(
+            MdRenderer::new().render_parameter_table(params),
+            "| Name | Type | Default |
+| --- | --- | --- |
+| `a` | `int \\| None` |  |
+| `*args` |  |  |
+| `b` |  | `1` |
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_md_definition_list() -> Result<()> {
+        let items = vec![
+            (String::from("x"), String::from("the first")),
+            (String::from("y"), String::from("the second\n")),
+        ];
+        assert_eq!(
+            MdRenderer::new().render_definition_list(&items),
+            "x\n: the first\n\ny\n: the second\n"
+        );
+        Ok(())
+    }
 }
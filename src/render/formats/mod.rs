@@ -1,9 +1,217 @@
+pub mod html;
 pub mod md;
+pub mod rst;
+pub mod template;
 pub mod zola;
 
-pub trait Renderer {
+use color_eyre::Result;
+use strum::Display;
+
+use crate::{
+    parsing::{
+        attribute::AttributeDocumentation,
+        function::{FunctionDocumentation, Parameter, ParameterKind},
+        module::ModuleDocumentation,
+    },
+    render::{
+        anchors::object_anchor, dedent_docstring, format_declaration, format_signature,
+        metadata::PageMetadata, render_module,
+    },
+};
+
+/// The kinds of callout boxes a renderer has to support, named after the github alerts
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum Admonition {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+/// Everything that ends up on a page goes through one of these methods, so a new backend only
+/// has to override the constructs where its markup differs from markdown, which the defaults produce.
+/// Methods taking code, link text or ids escape them as needed. Methods that assemble larger
+/// blocks (admonitions, tables, definition lists) expect their contents to already be rendered.
+pub trait Renderer: Send + Sync {
     fn render_header(&self, content: &str, level: usize) -> String;
-    fn render_front_matter(&self, title: Option<&str>) -> String;
+    fn render_front_matter(&self, page: &PageMetadata) -> String;
+
+    /// renders the signature of a function or method. Plain text by default
+    fn render_signature(&self, fn_docs: &FunctionDocumentation) -> String {
+        format!("{}\n", format_signature(fn_docs))
+    }
+
+    /// renders the declaration of an attribute, e.g. `TIMEOUT: int = 30`. Plain text by default
+    fn render_declaration(&self, attr_docs: &AttributeDocumentation) -> String {
+        format!("{}\n", format_declaration(attr_docs))
+    }
+
+    /// renders the docstring of a module or object, which is passed through as is by default
+    fn render_docstring(&self, docstring: &str) -> String {
+        dedent_docstring(docstring).trim().to_string()
+    }
+
+    fn render_code_block(&self, code: &str, language: Option<&str>) -> String {
+        // the fence has to be longer than any run of backticks in the code itself
+        let longest_run = longest_backtick_run(code);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+        format!(
+            "{fence}{}\n{}\n{fence}\n",
+            language.unwrap_or_default(),
+            code.trim_end()
+        )
+    }
+
+    fn render_inline_code(&self, code: &str) -> String {
+        let fence = "`".repeat(longest_backtick_run(code) + 1);
+        // a space is needed so backticks at the edges don't merge with the fence
+        if code.starts_with('`') || code.ends_with('`') {
+            format!("{fence} {code} {fence}")
+        } else {
+            format!("{fence}{code}{fence}")
+        }
+    }
+
+    fn render_link(&self, text: &str, target: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            // otherwise names like `__init__` end up in bold
+            if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        format!("[{escaped}]({})", target.replace(' ', "%20"))
+    }
+
+    /// the header of a documented object, carrying the explicit anchor from
+    /// `anchors::object_anchor` so links to it survive changes to the slugifier
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        format!(
+            "{} {}\n",
+            self.render_header(qualified_name, level).trim_end(),
+            self.render_anchor(&object_anchor(qualified_name))
+        )
+    }
+
+    /// the link from an object to its definition in the repository
+    fn render_source_link(&self, url: &str) -> String {
+        format!("{}\n", self.render_link("source", url))
+    }
+
+    /// a diagram in mermaid syntax, most markdown renderers draw fenced `mermaid` blocks
+    fn render_mermaid(&self, diagram: &str) -> String {
+        self.render_code_block(diagram, Some("mermaid"))
+    }
+
+    /// an invisible target that `render_link` can point to with `#id`
+    fn render_anchor(&self, id: &str) -> String {
+        format!("<a id=\"{id}\"></a>")
+    }
+
+    fn render_admonition(&self, kind: Admonition, content: &str) -> String {
+        let mut out = format!("> [!{}]\n", kind.to_string().to_uppercase());
+        for line in content.trim().lines() {
+            if line.is_empty() {
+                out.push_str(">\n");
+            } else {
+                out.push_str(&format!("> {line}\n"));
+            }
+        }
+        out
+    }
+
+    fn render_table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        let escape_cell = |cell: &str| cell.replace('|', "\\|").replace('\n', " ");
+        let mut out = format!(
+            "| {} |\n",
+            headers
+                .iter()
+                .map(|h| escape_cell(h))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        );
+        out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for row in rows {
+            out.push_str(&format!(
+                "| {} |\n",
+                row.iter()
+                    .map(|c| escape_cell(c))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            ));
+        }
+        out
+    }
+
+    /// a table of the parameters of a function with their annotations and defaults
+    fn render_parameter_table(&self, params: &[Parameter]) -> String {
+        if params.is_empty() {
+            return String::new();
+        }
+        let optional_code = |s: &Option<String>| {
+            s.as_deref()
+                .map(|s| self.render_inline_code(s))
+                .unwrap_or_default()
+        };
+        let rows = params
+            .iter()
+            .map(|p| {
+                let name = match p.kind {
+                    ParameterKind::VarPositional => format!("*{}", p.name),
+                    ParameterKind::VarKeyword => format!("**{}", p.name),
+                    _ => p.name.clone(),
+                };
+                vec![
+                    self.render_inline_code(&name),
+                    optional_code(&p.annotation),
+                    optional_code(&p.default),
+                ]
+            })
+            .collect::<Vec<_>>();
+        self.render_table(&["Name", "Type", "Default"], &rows)
+    }
+
+    /// plain prose, escaped where the format needs it
+    fn render_text(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn render_list(&self, items: &[String]) -> String {
+        items.iter().map(|item| format!("- {item}\n")).collect()
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> String {
+        items
+            .iter()
+            .map(|(term, definition)| format!("{term}\n: {}\n", definition.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// extension of the files produced by this renderer, without the leading `.`
+    fn file_extension(&self) -> &'static str {
+        "md"
+    }
+
+    /// file stem of the page documenting a package i.e. its `__init__.py`
+    fn index_file_stem(&self) -> &'static str {
+        "_index"
+    }
+
+    /// where the page written to `page` is served, relative to the root of the output.
+    /// `page` is relative to the output directory and uses `/` as separator
+    fn page_url(&self, page: &str) -> String {
+        page.to_string()
+    }
+
+    /// renders the page of an entire module. Formats whose layout doesn't fit
+    /// `render::render_module` can override this. Errors if the page can't be rendered,
+    /// e.g. because a user template fails, in which case the module counts as failed.
+    fn render_module_page(&self, mod_doc: ModuleDocumentation) -> Result<String> {
+        Ok(render_module(mod_doc, &self))
+    }
 }
 
 impl<T: Renderer + ?Sized> Renderer for &T {
@@ -11,8 +219,88 @@ fn render_header(&self, content: &str, level: usize) -> String {
         (**self).render_header(content, level)
     }
 
-    fn render_front_matter(&self, title: Option<&str>) -> String {
-        (**self).render_front_matter(title)
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        (**self).render_front_matter(page)
+    }
+
+    fn render_signature(&self, fn_docs: &FunctionDocumentation) -> String {
+        (**self).render_signature(fn_docs)
+    }
+
+    fn render_declaration(&self, attr_docs: &AttributeDocumentation) -> String {
+        (**self).render_declaration(attr_docs)
+    }
+
+    fn render_docstring(&self, docstring: &str) -> String {
+        (**self).render_docstring(docstring)
+    }
+
+    fn render_code_block(&self, code: &str, language: Option<&str>) -> String {
+        (**self).render_code_block(code, language)
+    }
+
+    fn render_inline_code(&self, code: &str) -> String {
+        (**self).render_inline_code(code)
+    }
+
+    fn render_link(&self, text: &str, target: &str) -> String {
+        (**self).render_link(text, target)
+    }
+
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        (**self).render_object_header(qualified_name, level)
+    }
+
+    fn render_source_link(&self, url: &str) -> String {
+        (**self).render_source_link(url)
+    }
+
+    fn render_mermaid(&self, diagram: &str) -> String {
+        (**self).render_mermaid(diagram)
+    }
+
+    fn render_anchor(&self, id: &str) -> String {
+        (**self).render_anchor(id)
+    }
+
+    fn render_admonition(&self, kind: Admonition, content: &str) -> String {
+        (**self).render_admonition(kind, content)
+    }
+
+    fn render_table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        (**self).render_table(headers, rows)
+    }
+
+    fn render_parameter_table(&self, params: &[Parameter]) -> String {
+        (**self).render_parameter_table(params)
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> String {
+        (**self).render_definition_list(items)
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        (**self).render_text(text)
+    }
+
+    fn render_list(&self, items: &[String]) -> String {
+        (**self).render_list(items)
+    }
+
+    fn file_extension(&self) -> &'static str {
+        (**self).file_extension()
+    }
+
+    fn index_file_stem(&self) -> &'static str {
+        (**self).index_file_stem()
+    }
+
+    fn page_url(&self, page: &str) -> String {
+        (**self).page_url(page)
+    }
+
+    fn render_module_page(&self, mod_doc: ModuleDocumentation) -> Result<String> {
+        (**self).render_module_page(mod_doc)
     }
 }
 
@@ -20,7 +308,73 @@ impl Renderer for Box<dyn Renderer> {
     fn render_header(&self, content: &str, level: usize) -> String {
         (**self).render_header(content, level)
     }
-    fn render_front_matter(&self, title: Option<&str>) -> String {
-        (**self).render_front_matter(title)
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        (**self).render_front_matter(page)
+    }
+    fn render_signature(&self, fn_docs: &FunctionDocumentation) -> String {
+        (**self).render_signature(fn_docs)
+    }
+    fn render_declaration(&self, attr_docs: &AttributeDocumentation) -> String {
+        (**self).render_declaration(attr_docs)
+    }
+    fn render_docstring(&self, docstring: &str) -> String {
+        (**self).render_docstring(docstring)
+    }
+    fn render_code_block(&self, code: &str, language: Option<&str>) -> String {
+        (**self).render_code_block(code, language)
+    }
+    fn render_inline_code(&self, code: &str) -> String {
+        (**self).render_inline_code(code)
+    }
+    fn render_link(&self, text: &str, target: &str) -> String {
+        (**self).render_link(text, target)
+    }
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        (**self).render_object_header(qualified_name, level)
+    }
+    fn render_source_link(&self, url: &str) -> String {
+        (**self).render_source_link(url)
+    }
+    fn render_mermaid(&self, diagram: &str) -> String {
+        (**self).render_mermaid(diagram)
+    }
+    fn render_anchor(&self, id: &str) -> String {
+        (**self).render_anchor(id)
+    }
+    fn render_admonition(&self, kind: Admonition, content: &str) -> String {
+        (**self).render_admonition(kind, content)
     }
+    fn render_table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        (**self).render_table(headers, rows)
+    }
+    fn render_parameter_table(&self, params: &[Parameter]) -> String {
+        (**self).render_parameter_table(params)
+    }
+    fn render_definition_list(&self, items: &[(String, String)]) -> String {
+        (**self).render_definition_list(items)
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        (**self).render_text(text)
+    }
+
+    fn render_list(&self, items: &[String]) -> String {
+        (**self).render_list(items)
+    }
+    fn file_extension(&self) -> &'static str {
+        (**self).file_extension()
+    }
+    fn index_file_stem(&self) -> &'static str {
+        (**self).index_file_stem()
+    }
+    fn page_url(&self, page: &str) -> String {
+        (**self).page_url(page)
+    }
+    fn render_module_page(&self, mod_doc: ModuleDocumentation) -> Result<String> {
+        (**self).render_module_page(mod_doc)
+    }
+}
+
+fn longest_backtick_run(code: &str) -> usize {
+    code.split(|c| c != '`').map(str::len).max().unwrap_or(0)
 }
@@ -0,0 +1,589 @@
+use color_eyre::Result;
+
+use crate::{
+    parsing::{
+        attribute::AttributeDocumentation,
+        class::ClassDocumentation,
+        function::FunctionDocumentation,
+        module::{ModuleDocumentation, ModuleReference},
+    },
+    render::{
+        anchors::object_anchor,
+        args::render_parameters,
+        dedent_docstring, format_signature,
+        formats::{Admonition, Renderer},
+        link_target,
+        metadata::{PageMetadata, summarize},
+        qualify, translate_filename,
+    },
+};
+
+/// Renders reStructuredText using the directives of the sphinx python domain
+/// (`py:module`, `py:function`, `py:class`, `py:method`) so the output can be dropped into
+/// an existing sphinx project and cross-referenced without running autodoc.
+#[derive(Default)]
+pub struct RstRenderer {}
+
+impl RstRenderer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// the conventional order of section adornments, see the python devguide
+const ADORNMENTS: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+const INDENT: &str = "   ";
+
+impl Renderer for RstRenderer {
+    fn render_header(&self, content: &str, level: usize) -> String {
+        let adornment = ADORNMENTS[level.clamp(1, ADORNMENTS.len()) - 1];
+        format!(
+            "{content}\n{}\n",
+            adornment.to_string().repeat(content.chars().count())
+        )
+    }
+
+    // the label has to come before the section it points to
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        format!(
+            "{}\n{}",
+            self.render_anchor(&object_anchor(qualified_name)),
+            self.render_header(qualified_name, level)
+        )
+    }
+
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        if let Some(t) = &page.title {
+            self.render_header(t, 1)
+        } else {
+            String::new()
+        }
+    }
+
+    fn render_signature(&self, fn_docs: &FunctionDocumentation) -> String {
+        let prefix = if fn_docs.is_async { "async " } else { "" };
+        self.render_code_block(
+            &format!("{prefix}{}", format_signature(fn_docs)),
+            Some("python"),
+        )
+    }
+
+    fn render_code_block(&self, code: &str, language: Option<&str>) -> String {
+        format!(
+            ".. code-block:: {}\n\n{}\n",
+            language.unwrap_or("text"),
+            indent_lines(code.trim_end(), INDENT)
+        )
+    }
+
+    fn render_inline_code(&self, code: &str) -> String {
+        format!("``{code}``")
+    }
+
+    // anonymous (double underscore) so the same text can link to different targets
+    fn render_link(&self, text: &str, target: &str) -> String {
+        format!("`{text} <{target}>`__")
+    }
+
+    fn render_anchor(&self, id: &str) -> String {
+        format!(".. _{id}:\n")
+    }
+
+    // needs the sphinxcontrib-mermaid extension
+    fn render_mermaid(&self, diagram: &str) -> String {
+        format!(
+            ".. mermaid::\n\n{}\n",
+            indent_lines(diagram.trim_end(), INDENT)
+        )
+    }
+
+    fn render_admonition(&self, kind: Admonition, content: &str) -> String {
+        format!(
+            ".. {}::\n\n{}\n",
+            kind.to_string().to_lowercase(),
+            indent_lines(content.trim(), INDENT)
+        )
+    }
+
+    fn render_table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        let mut out = String::from(".. list-table::\n   :header-rows: 1\n");
+        let headers = headers.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+        for row in std::iter::once(&headers).chain(rows) {
+            out.push('\n');
+            for (i, cell) in row.iter().enumerate() {
+                let bullet = if i == 0 { "* -" } else { "  -" };
+                // an empty cell still needs its bullet
+                out.push_str(format!("{INDENT}{bullet} {}", cell.replace('\n', " ")).trim_end());
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> String {
+        items
+            .iter()
+            .map(|(term, definition)| {
+                format!("{term}\n{}\n", indent_lines(definition.trim(), INDENT))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "rst"
+    }
+
+    // sphinx expects the root document to be called index
+    fn index_file_stem(&self) -> &'static str {
+        "index"
+    }
+
+    fn render_module_page(&self, mod_doc: ModuleDocumentation) -> Result<String> {
+        let mut out = String::new();
+        let maybe_qualifier = match (&mod_doc.prefix, &mod_doc.name) {
+            (None, None) => None,
+            (None, Some(name)) => Some(name.to_string()),
+            (Some(pref), None) => Some(pref.to_string()),
+            (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
+        };
+
+        out.push_str(&self.render_front_matter(&PageMetadata::from(&mod_doc)));
+
+        if let Some(qualifier) = &maybe_qualifier {
+            out.push_str(&format!("\n.. py:module:: {qualifier}\n"));
+        }
+
+        if let Some(docstring) = &mod_doc.docstring {
+            out.push('\n');
+            out.push_str(&rst_docstring(docstring));
+            out.push('\n');
+        }
+
+        let sub_modules = mod_doc.sub_modules.as_deref().unwrap_or_default();
+        let document_name = |sub_module: &ModuleReference| {
+            link_target(&translate_filename(&sub_module.path, self).with_extension(""))
+        };
+        // the sections below do the linking, the toctree only has to tell sphinx the structure
+        if !sub_modules.is_empty() {
+            out.push_str("\n.. toctree::\n   :hidden:\n   :maxdepth: 1\n\n");
+            for sub_module in sub_modules {
+                out.push_str(&format!("{INDENT}{}\n", document_name(sub_module)));
+            }
+        }
+
+        let summary_table = self.render_rst_summary_table(
+            maybe_qualifier.as_deref(),
+            &mod_doc.functions,
+            &mod_doc.classes,
+            &mod_doc.attributes,
+        );
+        if !summary_table.is_empty() {
+            out.push('\n');
+            out.push_str(&summary_table);
+        }
+
+        for diagram in [&mod_doc.class_diagram, &mod_doc.import_graph]
+            .into_iter()
+            .flatten()
+        {
+            out.push('\n');
+            out.push_str(&self.render_mermaid(diagram));
+        }
+
+        for fn_docs in &mod_doc.functions {
+            out.push('\n');
+            out.push_str(&render_rst_function(fn_docs, "function", 0));
+        }
+
+        for class_docs in &mod_doc.classes {
+            out.push('\n');
+            out.push_str(&self.render_rst_class(class_docs, maybe_qualifier.as_deref(), 0));
+        }
+
+        for attr_docs in &mod_doc.attributes {
+            out.push('\n');
+            out.push_str(&render_rst_attribute(attr_docs, "data", 0));
+        }
+
+        // at the end, since everything after a section title would become part of the section
+        for (title, packages) in [("Subpackages", true), ("Submodules", false)] {
+            let items = sub_modules
+                .iter()
+                .filter(|s| s.is_package == packages)
+                .map(|s| {
+                    let link = format!(":doc:`{} <{}>`", s.name, document_name(s));
+                    match &s.summary {
+                        Some(summary) => format!("{link}: {summary}"),
+                        None => link,
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !items.is_empty() {
+                out.push('\n');
+                out.push_str(&self.render_header(title, 2));
+                out.push('\n');
+                out.push_str(&self.render_list(&items));
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl RstRenderer {
+    /// the autosummary of a module or class, linking with `py:obj` roles so sphinx
+    /// resolves the targets to the directives further down
+    fn render_rst_summary_table(
+        &self,
+        prefix: Option<&str>,
+        functions: &[FunctionDocumentation],
+        classes: &[ClassDocumentation],
+        attributes: &[AttributeDocumentation],
+    ) -> String {
+        let rows = functions
+            .iter()
+            .map(|f| (&f.name, &f.docstring))
+            .chain(classes.iter().map(|c| (&c.name, &c.docstring)))
+            .chain(attributes.iter().map(|a| (&a.name, &a.docstring)))
+            .map(|(name, docstring)| {
+                vec![
+                    format!(":py:obj:`~{}`", qualify(prefix, name)),
+                    summarize(docstring.as_deref()).unwrap_or_default(),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        if rows.is_empty() {
+            return String::new();
+        }
+        self.render_table(&["Name", "Summary"], &rows)
+    }
+
+    fn render_rst_class(
+        &self,
+        class_docs: &ClassDocumentation,
+        prefix: Option<&str>,
+        depth: usize,
+    ) -> String {
+        let indent = INDENT.repeat(depth);
+        let mut out = format!("{indent}.. py:class:: {}\n", class_docs.name);
+
+        if let Some(url) = &class_docs.source_url {
+            out.push('\n');
+            out.push_str(&indent_lines(
+                &self.render_source_link(url),
+                &INDENT.repeat(depth + 1),
+            ));
+            out.push('\n');
+        }
+
+        if let Some(docstring) = &class_docs.docstring {
+            out.push('\n');
+            out.push_str(&indent_lines(
+                &rst_docstring(docstring),
+                &INDENT.repeat(depth + 1),
+            ));
+            out.push('\n');
+        }
+
+        let summary_table = self.render_rst_summary_table(
+            Some(&qualify(prefix, &class_docs.name)),
+            &class_docs.methods,
+            &[],
+            &class_docs.attributes,
+        );
+        if !summary_table.is_empty() {
+            out.push('\n');
+            out.push_str(&indent_lines(&summary_table, &INDENT.repeat(depth + 1)));
+            out.push('\n');
+        }
+
+        for fn_docs in &class_docs.methods {
+            out.push('\n');
+            out.push_str(&render_rst_function(fn_docs, "method", depth + 1));
+        }
+
+        for attr_docs in &class_docs.attributes {
+            out.push('\n');
+            out.push_str(&render_rst_attribute(attr_docs, "attribute", depth + 1));
+        }
+        out
+    }
+}
+
+fn render_rst_attribute(
+    attr_docs: &AttributeDocumentation,
+    directive: &str,
+    depth: usize,
+) -> String {
+    let indent = INDENT.repeat(depth);
+    let mut out = format!("{indent}.. py:{directive}:: {}\n", attr_docs.name);
+    if let Some(annotation) = &attr_docs.annotation {
+        out.push_str(&format!("{indent}{INDENT}:type: {annotation}\n"));
+    }
+    if let Some(value) = &attr_docs.value {
+        out.push_str(&format!("{indent}{INDENT}:value: {value}\n"));
+    }
+
+    if let Some(docstring) = &attr_docs.docstring {
+        out.push('\n');
+        out.push_str(&indent_lines(
+            &rst_docstring(docstring),
+            &INDENT.repeat(depth + 1),
+        ));
+        out.push('\n');
+    }
+    out
+}
+
+fn render_rst_function(fn_docs: &FunctionDocumentation, directive: &str, depth: usize) -> String {
+    let indent = INDENT.repeat(depth);
+    let mut out = format!(
+        "{indent}.. py:{directive}:: {}({})",
+        fn_docs.name,
+        render_parameters(&fn_docs.args)
+    );
+    if let Some(return_annotation) = &fn_docs.return_type {
+        out.push_str(&format!(" -> {return_annotation}"));
+    }
+    out.push('\n');
+    if fn_docs.is_async {
+        out.push_str(&format!("{indent}{INDENT}:async:\n"));
+    }
+
+    if let Some(url) = &fn_docs.source_url {
+        out.push_str(&format!("\n{indent}{INDENT}`source <{url}>`__\n"));
+    }
+
+    if let Some(docstring) = &fn_docs.docstring {
+        out.push('\n');
+        out.push_str(&indent_lines(
+            &rst_docstring(docstring),
+            &INDENT.repeat(depth + 1),
+        ));
+        out.push('\n');
+    }
+    out
+}
+
+/// docutils does not allow section titles inside directives, so the underlined
+/// headings of numpy style docstrings (`Parameters\n----------`) are turned into rubrics
+fn rst_docstring(docstring: &str) -> String {
+    let dedented = dedent_docstring(docstring);
+    let lines = dedented.trim().lines().collect::<Vec<_>>();
+    let mut out = vec![];
+    let mut i = 0;
+
+    while i < lines.len() {
+        let title = lines[i].trim();
+        let is_section = lines.get(i + 1).is_some_and(|next| {
+            let underline = next.trim();
+            !title.is_empty()
+                && !underline.is_empty()
+                && underline.chars().all(|c| c == '-' || c == '=')
+                && underline.chars().count() >= title.chars().count()
+        });
+        if is_section {
+            let indent = &lines[i][..lines[i].len() - lines[i].trim_start().len()];
+            out.push(format!("{indent}.. rubric:: {title}"));
+            out.push(String::new());
+            i += 2;
+        } else {
+            out.push(lines[i].to_string());
+            i += 1;
+        }
+    }
+
+    out.join("\n")
+}
+
+fn indent_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|l| {
+            if l.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{indent}{l}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parsing::{module::extract_module_documentation, utils::parse_python_str};
+
+    fn test_module_str() -> &'static str {
+        r"'''Module doc.'''
+
+async def fetch(url: str, *, retries: int = 3) -> bytes:
+    '''
+    Fetch a url.
+
+    Parameters
+    ----------
+    url: str
+        where to fetch from
+    '''
+
+class Client:
+    '''A client.'''
+
+    timeout: float = 1.5
+
+    def get(self, key):
+        '''Get a key.'''
+
+DEFAULT_CLIENT = Client()
+'''The shared client.'''
+"
+    }
+
+    fn expected_rst() -> &'static str {
+        r"pkg.client
+==========
+
+.. py:module:: pkg.client
+
+Module doc.
+
+.. toctree::
+   :hidden:
+   :maxdepth: 1
+
+   mid
+   sub2/index
+
+.. list-table::
+   :header-rows: 1
+
+   * - Name
+     - Summary
+
+   * - :py:obj:`~pkg.client.fetch`
+     - Fetch a url.
+
+   * - :py:obj:`~pkg.client.Client`
+     - A client.
+
+   * - :py:obj:`~pkg.client.DEFAULT_CLIENT`
+     - The shared client.
+
+.. py:function:: fetch(url: str, *, retries: int = 3) -> bytes
+   :async:
+
+   Fetch a url.
+
+   .. rubric:: Parameters
+
+   url: str
+       where to fetch from
+
+.. py:class:: Client
+
+   A client.
+
+   .. list-table::
+      :header-rows: 1
+
+      * - Name
+        - Summary
+
+      * - :py:obj:`~pkg.client.Client.get`
+        - Get a key.
+
+      * - :py:obj:`~pkg.client.Client.timeout`
+        -
+
+   .. py:method:: get(self, key)
+
+      Get a key.
+
+   .. py:attribute:: timeout
+      :type: float
+      :value: 1.5
+
+.. py:data:: DEFAULT_CLIENT
+   :value: Client()
+
+   The shared client.
+
+Subpackages
+-----------
+
+- :doc:`sub2 <sub2/index>`
+
+Submodules
+----------
+
+- :doc:`mid <mid>`: A module.
+"
+    }
+
+    #[test]
+    fn render_rst_module() -> Result<()> {
+        let parsed = parse_python_str(test_module_str())?;
+        let mut mod_doc = extract_module_documentation(
+            &parsed,
+            Some(String::from("client")),
+            Some(String::from("pkg")),
+            false,
+            false,
+        );
+        mod_doc.with_sub_modules(Some(vec![
+            ModuleReference {
+                name: String::from("mid"),
+                path: PathBuf::from("mid.py"),
+                summary: Some(String::from("A module.")),
+                is_package: false,
+            },
+            ModuleReference {
+                name: String::from("sub2"),
+                path: PathBuf::from("sub2/__init__.py"),
+                summary: None,
+                is_package: true,
+            },
+        ]));
+
+        assert_eq!(
+            RstRenderer::new().render_module_page(mod_doc)?,
+            expected_rst()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rst_table_and_admonition() -> Result<()> {
+        let renderer = RstRenderer::new();
+        assert_eq!(
+            renderer.render_table(
+                &["Name", "Default"],
+                &[vec![renderer.render_inline_code("x"), String::new()]]
+            ),
+            ".. list-table::\n   :header-rows: 1\n\n   * - Name\n     - Default\n\n   * - ``x``\n     -\n"
+        );
+        assert_eq!(
+            renderer.render_admonition(Admonition::Warning, "Careful.\n\nReally."),
+            ".. warning::\n\n   Careful.\n\n   Really.\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rst_header_levels() -> Result<()> {
+        let renderer = RstRenderer::new();
+        assert_eq!(renderer.render_header("foo", 1), "foo\n===\n");
+        assert_eq!(renderer.render_header("foo", 2), "foo\n---\n");
+        assert_eq!(renderer.render_header("🙈", 3), "🙈\n~\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+use std::{fs::read_to_string, path::Path};
+
+use color_eyre::Result;
+use minijinja::{AutoEscape, Environment, context};
+
+use crate::{
+    parsing::{
+        attribute::AttributeDocumentation,
+        class::ClassDocumentation,
+        function::{FunctionDocumentation, Parameter},
+        module::ModuleDocumentation,
+    },
+    render::{
+        dedent_docstring, format_declaration, format_signature,
+        formats::{Admonition, Renderer},
+        metadata::PageMetadata,
+        qualify, render_sub_module_sections, render_summary_table,
+    },
+};
+
+pub const MODULE_TEMPLATE: &str = "module.jinja";
+pub const CLASS_TEMPLATE: &str = "class.jinja";
+pub const FUNCTION_TEMPLATE: &str = "function.jinja";
+pub const ATTRIBUTE_TEMPLATE: &str = "attribute.jinja";
+
+const DEFAULT_TEMPLATES: [(&str, &str); 4] = [
+    (MODULE_TEMPLATE, include_str!("templates/module.jinja")),
+    (CLASS_TEMPLATE, include_str!("templates/class.jinja")),
+    (FUNCTION_TEMPLATE, include_str!("templates/function.jinja")),
+    (
+        ATTRIBUTE_TEMPLATE,
+        include_str!("templates/attribute.jinja"),
+    ),
+];
+
+/// Renders pages through jinja templates, one for each kind of object. The defaults
+/// reproduce the output of `render::render_module`; any of them can be overridden
+/// by placing a file with the same name (e.g. `function.jinja`) in the templates directory.
+/// Headers and front matter are still produced by the wrapped renderer.
+pub struct TemplateRenderer {
+    inner: Box<dyn Renderer>,
+    env: Environment<'static>,
+}
+
+impl TemplateRenderer {
+    pub fn new(inner: Box<dyn Renderer>, templates_dir: Option<&Path>) -> Result<Self> {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+
+        for (name, source) in DEFAULT_TEMPLATES {
+            let user_template = templates_dir
+                .map(|dir| dir.join(name))
+                .filter(|path| path.is_file());
+            match user_template {
+                Some(path) => {
+                    tracing::debug!("using template {}", path.display());
+                    env.add_template_owned(name, read_to_string(&path)?)?;
+                }
+                None => env.add_template(name, source)?,
+            }
+        }
+
+        Ok(Self { inner, env })
+    }
+
+    fn render_function(
+        &self,
+        fn_docs: &FunctionDocumentation,
+        prefix: Option<&str>,
+        header_level: usize,
+    ) -> Result<String> {
+        let qualified_name = qualify(prefix, &fn_docs.name);
+        let rendered = self.env.get_template(FUNCTION_TEMPLATE)?.render(context! {
+            function => context! {
+                name => fn_docs.name,
+                qualified_name => qualified_name,
+                signature => format_signature(fn_docs),
+                return_type => fn_docs.return_type,
+                args => fn_docs.args,
+                generics => fn_docs.generics,
+                decorators => fn_docs.decorators,
+                is_async => fn_docs.is_async,
+                docstring => clean_docstring(&fn_docs.docstring),
+                lines => fn_docs.lines,
+                source_url => fn_docs.source_url,
+            },
+            level => header_level,
+            header => self.inner.render_object_header(&qualified_name, header_level).trim_end(),
+            signature => self.inner.render_signature(fn_docs).trim_end(),
+            source_link => self.render_source_link_of(fn_docs.source_url.as_deref()),
+        })?;
+        Ok(rendered.trim().to_string())
+    }
+
+    fn render_attribute(
+        &self,
+        attr_docs: &AttributeDocumentation,
+        prefix: Option<&str>,
+        header_level: usize,
+    ) -> Result<String> {
+        let qualified_name = qualify(prefix, &attr_docs.name);
+        let rendered = self
+            .env
+            .get_template(ATTRIBUTE_TEMPLATE)?
+            .render(context! {
+                attribute => context! {
+                    name => attr_docs.name,
+                    qualified_name => qualified_name,
+                    declaration => format_declaration(attr_docs),
+                    annotation => attr_docs.annotation,
+                    value => attr_docs.value,
+                    docstring => clean_docstring(&attr_docs.docstring),
+                },
+                level => header_level,
+                header => self.inner.render_object_header(&qualified_name, header_level).trim_end(),
+            })?;
+        Ok(rendered.trim().to_string())
+    }
+
+    fn render_class(
+        &self,
+        class_docs: &ClassDocumentation,
+        prefix: Option<&str>,
+        header_level: usize,
+    ) -> Result<String> {
+        let qualified_name = qualify(prefix, &class_docs.name);
+        let methods = class_docs
+            .methods
+            .iter()
+            .map(|m| self.render_function(m, Some(&qualified_name), header_level + 1))
+            .collect::<Result<Vec<_>>>()?;
+        let attributes = class_docs
+            .attributes
+            .iter()
+            .map(|a| self.render_attribute(a, Some(&qualified_name), header_level + 1))
+            .collect::<Result<Vec<_>>>()?;
+        let summary_table = render_summary_table(
+            Some(&qualified_name),
+            &class_docs.methods,
+            &[],
+            &class_docs.attributes,
+            &self.inner,
+        );
+        let rendered = self.env.get_template(CLASS_TEMPLATE)?.render(context! {
+            class => context! {
+                name => class_docs.name,
+                qualified_name => qualified_name,
+                docstring => clean_docstring(&class_docs.docstring),
+                lines => class_docs.lines,
+                source_url => class_docs.source_url,
+            },
+            level => header_level,
+            header => self.inner.render_object_header(&qualified_name, header_level).trim_end(),
+            source_link => self.render_source_link_of(class_docs.source_url.as_deref()),
+            summary_table => summary_table.trim_end(),
+            methods => methods,
+            attributes => attributes,
+        })?;
+        Ok(rendered.trim().to_string())
+    }
+
+    fn render_source_link_of(&self, source_url: Option<&str>) -> Option<String> {
+        source_url.map(|url| self.inner.render_source_link(url).trim_end().to_string())
+    }
+
+    fn render_module_with_templates(&self, mod_doc: &ModuleDocumentation) -> Result<String> {
+        let qualified_name = match (&mod_doc.prefix, &mod_doc.name) {
+            (None, None) => None,
+            (None, Some(name)) => Some(name.clone()),
+            (Some(pref), None) => Some(pref.clone()),
+            (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
+        };
+        let prefix = qualified_name.as_deref();
+
+        let functions = mod_doc
+            .functions
+            .iter()
+            .map(|f| self.render_function(f, prefix, 2))
+            .collect::<Result<Vec<_>>>()?;
+        let classes = mod_doc
+            .classes
+            .iter()
+            .map(|c| self.render_class(c, prefix, 2))
+            .collect::<Result<Vec<_>>>()?;
+        let attributes = mod_doc
+            .attributes
+            .iter()
+            .map(|a| self.render_attribute(a, prefix, 2))
+            .collect::<Result<Vec<_>>>()?;
+
+        let summary_table = render_summary_table(
+            prefix,
+            &mod_doc.functions,
+            &mod_doc.classes,
+            &mod_doc.attributes,
+            &self.inner,
+        );
+
+        let page = PageMetadata::from(mod_doc);
+        let rendered = self.env.get_template(MODULE_TEMPLATE)?.render(context! {
+            module => context! {
+                name => mod_doc.name,
+                prefix => mod_doc.prefix,
+                qualified_name => qualified_name,
+                docstring => mod_doc.docstring.as_deref().map(str::trim),
+                exports => mod_doc.exports,
+                sub_modules => mod_doc.sub_modules,
+                summary => page.summary,
+                kind => page.kind,
+            },
+            front_matter => self.inner.render_front_matter(&page),
+            summary_table => summary_table.trim_end(),
+            class_diagram => mod_doc
+                .class_diagram
+                .as_deref()
+                .map(|d| self.inner.render_mermaid(d).trim_end().to_string()),
+            import_graph => mod_doc
+                .import_graph
+                .as_deref()
+                .map(|d| self.inner.render_mermaid(d).trim_end().to_string()),
+            sub_module_sections => render_sub_module_sections(mod_doc, &self.inner).trim_end(),
+            functions => functions,
+            classes => classes,
+            attributes => attributes,
+        })?;
+        Ok(rendered)
+    }
+}
+
+fn clean_docstring(docstring: &Option<String>) -> Option<String> {
+    docstring
+        .as_deref()
+        .map(|d| dedent_docstring(d).trim().to_string())
+}
+
+impl Renderer for TemplateRenderer {
+    fn render_header(&self, content: &str, level: usize) -> String {
+        self.inner.render_header(content, level)
+    }
+
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        self.inner.render_front_matter(page)
+    }
+
+    fn render_signature(&self, fn_docs: &FunctionDocumentation) -> String {
+        self.inner.render_signature(fn_docs)
+    }
+
+    fn render_declaration(&self, attr_docs: &AttributeDocumentation) -> String {
+        self.inner.render_declaration(attr_docs)
+    }
+
+    fn render_docstring(&self, docstring: &str) -> String {
+        self.inner.render_docstring(docstring)
+    }
+
+    fn render_code_block(&self, code: &str, language: Option<&str>) -> String {
+        self.inner.render_code_block(code, language)
+    }
+
+    fn render_inline_code(&self, code: &str) -> String {
+        self.inner.render_inline_code(code)
+    }
+
+    fn render_link(&self, text: &str, target: &str) -> String {
+        self.inner.render_link(text, target)
+    }
+
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        self.inner.render_object_header(qualified_name, level)
+    }
+
+    fn render_source_link(&self, url: &str) -> String {
+        self.inner.render_source_link(url)
+    }
+
+    fn render_mermaid(&self, diagram: &str) -> String {
+        self.inner.render_mermaid(diagram)
+    }
+
+    fn render_anchor(&self, id: &str) -> String {
+        self.inner.render_anchor(id)
+    }
+
+    fn render_admonition(&self, kind: Admonition, content: &str) -> String {
+        self.inner.render_admonition(kind, content)
+    }
+
+    fn render_table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        self.inner.render_table(headers, rows)
+    }
+
+    fn render_parameter_table(&self, params: &[Parameter]) -> String {
+        self.inner.render_parameter_table(params)
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> String {
+        self.inner.render_definition_list(items)
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        self.inner.render_text(text)
+    }
+
+    fn render_list(&self, items: &[String]) -> String {
+        self.inner.render_list(items)
+    }
+
+    fn file_extension(&self) -> &'static str {
+        self.inner.file_extension()
+    }
+
+    fn index_file_stem(&self) -> &'static str {
+        self.inner.index_file_stem()
+    }
+
+    fn page_url(&self, page: &str) -> String {
+        self.inner.page_url(page)
+    }
+
+    fn render_module_page(&self, mod_doc: ModuleDocumentation) -> Result<String> {
+        self.render_module_with_templates(&mod_doc)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use assert_fs::TempDir;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        parsing::{module::extract_module_documentation, utils::parse_python_str},
+        render::{
+            formats::{Renderer, md::MdRenderer, zola::ZolaRenderer},
+            render_module,
+        },
+    };
+
+    use super::TemplateRenderer;
+
+    fn test_python() -> &'static str {
+        r#"
+"""This is a module docstring."""
+
+def greet(name: str, *, loud: bool = False) -> str:
+    """Greets someone.
+
+    Parameters
+    ----------
+    name: str
+        who to greet
+    """
+    return f"hello {name}"
+
+class Greeter:
+    """Greets people."""
+
+    def __init__(self, name):
+        """Sets the name."""
+        self.name = name
+
+    async def wave(self):
+        pass
+"#
+    }
+
+    #[test]
+    fn default_templates_match_default_layout() -> Result<()> {
+        let program = parse_python_str(test_python())?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some("greeting".to_string()),
+            None,
+            false,
+            false,
+        );
+        mod_doc.functions[0].source_url = Some("greeting.py#L4".to_string());
+        mod_doc.classes[0].source_url = Some("greeting.py#L14".to_string());
+
+        for inner in [
+            Box::new(MdRenderer::new()) as Box<dyn Renderer>,
+            Box::new(ZolaRenderer::new()),
+        ] {
+            let expected = render_module(mod_doc.clone(), &inner);
+            let renderer = TemplateRenderer::new(inner, None)?;
+            assert_eq!(renderer.render_module_page(mod_doc.clone())?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn default_templates_render_attributes() -> Result<()> {
+        let program = parse_python_str(
+            r#"
+TIMEOUT: int = 30
+"""Seconds to wait."""
+"#,
+        )?;
+        let mod_doc =
+            extract_module_documentation(&program, Some("net".to_string()), None, false, false);
+        let renderer = TemplateRenderer::new(Box::new(MdRenderer::new()), None)?;
+
+        assert_eq!(
+            renderer.render_module_page(mod_doc)?,
+            "# net\n\n| Name | Summary |\n| --- | --- |\n| [TIMEOUT](#net.TIMEOUT) | Seconds to wait. |\n\n## net.TIMEOUT <a id=\"net.TIMEOUT\"></a>\n\nTIMEOUT: int = 30\n\nSeconds to wait.\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn user_template_overrides_default() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        write(
+            tmp_dir.join("function.jinja"),
+            "{{ header }}\n\n```python\n{% if function.is_async %}async {% endif %}def {{ function.signature }}\n```\n",
+        )?;
+        let program = parse_python_str(test_python())?;
+        let mod_doc =
+            extract_module_documentation(&program, Some("greeting".to_string()), None, false, true);
+        let renderer = TemplateRenderer::new(Box::new(MdRenderer::new()), Some(&tmp_dir))?;
+
+        let rendered = renderer.render_module_page(mod_doc)?;
+
+        assert!(
+            rendered.contains("```python\ndef greet(name: str, *, loud: bool = False) -> str\n```")
+        );
+        assert!(rendered.contains("```python\ndef __init__(self, name)\n```"));
+        // templates that weren't overridden still apply
+        assert!(rendered.starts_with("# greeting\n\nThis is a module docstring.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn failing_user_template_fails_the_page() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        // valid syntax, but an integer can't be iterated
+        write(
+            tmp_dir.join("function.jinja"),
+            "{% for line in 42 %}{{ line }}{% endfor %}",
+        )?;
+        let program = parse_python_str(test_python())?;
+        let mod_doc =
+            extract_module_documentation(&program, Some("greeting".to_string()), None, false, true);
+        let renderer = TemplateRenderer::new(Box::new(MdRenderer::new()), Some(&tmp_dir))?;
+
+        assert!(renderer.render_module_page(mod_doc).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_user_template_errors() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        write(tmp_dir.join("class.jinja"), "{% if class.name %}unclosed")?;
+
+        let renderer = TemplateRenderer::new(Box::new(MdRenderer::new()), Some(&tmp_dir));
+
+        assert!(renderer.is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,14 @@
-use crate::render::formats::Renderer;
+use toml::Value;
 
-pub struct ZolaRenderer {}
+use crate::render::{
+    anchors::object_anchor,
+    formats::Renderer,
+    metadata::{FrontMatterConfig, PageMetadata},
+};
+
+pub struct ZolaRenderer {
+    front_matter: FrontMatterConfig,
+}
 impl Default for ZolaRenderer {
     fn default() -> Self {
         Self::new()
@@ -9,37 +17,103 @@ fn default() -> Self {
 
 impl ZolaRenderer {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            front_matter: FrontMatterConfig::default(),
+        }
+    }
+    pub fn with_front_matter(mut self, front_matter: FrontMatterConfig) -> Self {
+        self.front_matter = front_matter;
+        self
     }
 }
 impl Renderer for ZolaRenderer {
     fn render_header(&self, content: &str, level: usize) -> String {
         let mut out = String::new();
-        out.push_str(&"#".repeat(level));
+        out.push_str(&"#".repeat(level.clamp(1, 6)));
         out.push(' ');
         out.push_str(content);
         out.push('\n');
         out
     }
 
-    fn render_front_matter(&self, title: Option<&str>) -> String {
+    // zola (and hugo) support setting the id of a header with `{#id}`
+    fn render_object_header(&self, qualified_name: &str, level: usize) -> String {
+        format!(
+            "{} {{#{}}}\n",
+            self.render_header(qualified_name, level).trim_end(),
+            object_anchor(qualified_name)
+        )
+    }
+
+    // zola serves `foo/bar.md` at `foo/bar/` and `foo/_index.md` at `foo/`
+    fn page_url(&self, page: &str) -> String {
+        let page = page.strip_suffix(".md").unwrap_or(page);
+        let mut components: Vec<_> = page.split('/').collect();
+        if components.last() == Some(&self.index_file_stem()) {
+            components.pop();
+        }
+        components
+            .into_iter()
+            .map(|c| format!("{}/", slugify(c)))
+            .collect()
+    }
+
+    fn render_front_matter(&self, page: &PageMetadata) -> String {
+        let mut fields = self.front_matter.resolve(page);
+        // a title configured by the user wins
+        if let Some(t) = &page.title {
+            fields
+                .entry("title")
+                .or_insert_with(|| Value::String(t.clone()));
+        }
+        let serialized = toml::to_string(&fields).unwrap_or_else(|e| {
+            tracing::error!("could not serialize front matter: {e}");
+            String::new()
+        });
+
         let mut out = String::new();
         out.push_str("+++\n");
-        if let Some(t) = title {
-            out.push_str(&format!("title = \"{t}\"\n"));
-        };
+        out.push_str(&serialized);
         out.push_str("+++\n");
         out
     }
 }
 
+/// the way zola turns file names into urls with the default `slugify.paths = "on"`
+fn slugify(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::render::metadata::PageKind;
     use color_eyre::Result;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn test_zola_page_urls() -> Result<()> {
+        let renderer = ZolaRenderer::new();
+        assert_eq!(renderer.page_url("_index.md"), "");
+        assert_eq!(renderer.page_url("sub1/_index.md"), "sub1/");
+        assert_eq!(renderer.page_url("sub1/mid.md"), "sub1/mid/");
+        assert_eq!(
+            renderer.page_url("bar/greet_undocumented.md"),
+            "bar/greet-undocumented/"
+        );
+        assert_eq!(renderer.page_url("_private/Client.md"), "private/client/");
+        Ok(())
+    }
+
     #[test]
     fn test_zola_header() -> Result<()> {
         let renderer = ZolaRenderer::new();
@@ -51,10 +125,23 @@ fn test_zola_header() -> Result<()> {
         Ok(())
     }
 
+    #[test]
+    fn test_zola_object_header() -> Result<()> {
+        assert_eq!(
+            ZolaRenderer::new().render_object_header("pkg.mod.Class.__init__", 3),
+            "### pkg.mod.Class.__init__ {#pkg.mod.Class.__init__}\n"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_empty_zola_front_matter() -> Result<()> {
         assert_eq!(
-            ZolaRenderer::new().render_front_matter(None),
+            ZolaRenderer::new().render_front_matter(&PageMetadata::new(
+                None,
+                None,
+                PageKind::Module
+            )),
             r"+++
 +++
 "
@@ -65,10 +152,51 @@ fn test_empty_zola_front_matter() -> Result<()> {
     #[test]
     fn test_zola_front_matter_with_title() -> Result<()> {
         assert_eq!(
-            ZolaRenderer::new().render_front_matter(Some("foo")),
+            ZolaRenderer::new().render_front_matter(&PageMetadata::new(
+                Some("foo".to_string()),
+                None,
+                PageKind::Module
+            )),
             r#"+++
 title = "foo"
 +++
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_zola_front_matter_with_configured_fields() -> Result<()> {
+        let front_matter: FrontMatterConfig = toml::from_str(
+            r#"
+[default]
+weight = 5
+
+[module]
+template = "api-module.html"
+
+[module.extra]
+summary = "{summary}"
+"#,
+        )?;
+        let page = PageMetadata::new(
+            Some("pkg.mod".to_string()),
+            Some("Does things.".to_string()),
+            PageKind::Module,
+        );
+
+        assert_eq!(
+            ZolaRenderer::new()
+                .with_front_matter(front_matter)
+                .render_front_matter(&page),
+            r#"+++
+template = "api-module.html"
+title = "pkg.mod"
+weight = 5
+
+[extra]
+summary = "Does things."
++++
 "#
         );
         Ok(())
@@ -0,0 +1,298 @@
+//! The graph of the imports between the modules of a package, rendered as DOT for graphviz
+//! and as a mermaid flowchart on the page of the root package. Only modules that made it
+//! through `walk_package` are drawn, so excluded and private modules are left out.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    parsing::{
+        module::{Import, ModuleDocumentation},
+        package::PackageDocumentation,
+    },
+    render::{
+        diagrams::NodeIds,
+        metadata::{PageKind, PageMetadata},
+    },
+};
+
+pub const IMPORT_GRAPH_DOT_NAME: &str = "import_graph.dot";
+pub const IMPORT_GRAPH_MERMAID_NAME: &str = "import_graph.mmd";
+
+/// The `[import_graph]` section of `snakedown.toml`, the graph is only drawn if it is present
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportGraphConfig {
+    /// also draw the imports from outside the package, collapsed into a node per top level
+    /// package, e.g. `os` for `os.path`
+    pub external: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportGraph {
+    /// the qualified names of the modules of the package
+    pub modules: BTreeSet<String>,
+    /// the top level packages imported from outside the package
+    pub external: BTreeSet<String>,
+    /// `(importer, imported)` pairs
+    pub edges: BTreeSet<(String, String)>,
+}
+
+impl ImportGraph {
+    pub fn new(package: &PackageDocumentation, config: &ImportGraphConfig) -> Self {
+        let modules: BTreeMap<String, &ModuleDocumentation> = package
+            .modules
+            .iter()
+            .filter_map(|module| Some((PageMetadata::from(module).title?, module)))
+            .collect();
+
+        let mut graph = ImportGraph {
+            modules: modules.keys().cloned().collect(),
+            ..Default::default()
+        };
+        for (name, module) in &modules {
+            let is_package = PageMetadata::from(*module).kind == PageKind::Package;
+            for import in &module.imports {
+                let Some(imported) = resolve_import(import, name, is_package) else {
+                    tracing::warn!("{name} imports {} from beyond the package", import.module);
+                    continue;
+                };
+                for target in graph.targets(&imported, &import.names, &package.name, config) {
+                    if target != *name {
+                        graph.edges.insert((name.clone(), target));
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// the nodes `from imported import names` points to. Names are modules of the package
+    /// only if they are in the graph, otherwise they are objects of `imported` itself.
+    fn targets(
+        &mut self,
+        imported: &str,
+        names: &[String],
+        package_name: &str,
+        config: &ImportGraphConfig,
+    ) -> Vec<String> {
+        let sub_modules: Vec<String> = names
+            .iter()
+            .map(|n| format!("{imported}.{n}"))
+            .filter(|q| self.modules.contains(q))
+            .collect();
+        if sub_modules.len() == names.len() && !names.is_empty() {
+            return sub_modules;
+        }
+        if self.modules.contains(imported) {
+            return [sub_modules, vec![imported.to_string()]].concat();
+        }
+
+        let top_level = imported.split('.').next().unwrap_or(imported);
+        // an internal module that was excluded, or doesn't exist at all
+        if top_level == package_name || !config.external {
+            return sub_modules;
+        }
+        self.external.insert(top_level.to_string());
+        vec![top_level.to_string()]
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph imports {\n");
+        for module in &self.modules {
+            out.push_str(&format!("    \"{module}\";\n"));
+        }
+        for package in &self.external {
+            out.push_str(&format!("    \"{package}\" [style=dashed];\n"));
+        }
+        for (importer, imported) in &self.edges {
+            out.push_str(&format!("    \"{importer}\" -> \"{imported}\";\n"));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let ids = NodeIds::new(
+            self.modules
+                .iter()
+                .chain(&self.external)
+                .map(String::as_str),
+        );
+        let mut out = String::from("flowchart LR\n");
+        for module in &self.modules {
+            out.push_str(&format!("    {}[\"{module}\"]\n", ids.get(module)));
+        }
+        for package in &self.external {
+            out.push_str(&format!("    {}([\"{package}\"])\n", ids.get(package)));
+        }
+        for (importer, imported) in &self.edges {
+            out.push_str(&format!(
+                "    {} --> {}\n",
+                ids.get(importer),
+                ids.get(imported)
+            ));
+        }
+        out
+    }
+}
+
+/// Shows the import graph on the page of the root package
+pub fn add_import_graph(package: &mut PackageDocumentation, config: &ImportGraphConfig) {
+    let diagram = ImportGraph::new(package, config).to_mermaid();
+    let root = package
+        .modules
+        .iter_mut()
+        .find(|m| PageMetadata::from(&**m).title.as_deref() == Some(&package.name));
+    if let Some(root) = root {
+        root.import_graph = Some(diagram);
+    } else {
+        tracing::warn!("no page for {} to show the import graph on", package.name);
+    }
+}
+
+/// The absolute name of the module `import` refers to, from within the module `importer`
+fn resolve_import(import: &Import, importer: &str, is_package: bool) -> Option<String> {
+    let relative = import.module.trim_start_matches('.');
+    let level = import.module.len() - relative.len();
+    if level == 0 {
+        return Some(import.module.clone());
+    }
+
+    // `.` is the package containing a module, but the package itself for an `__init__.py`
+    let mut base: Vec<&str> = importer.split('.').collect();
+    let up = if is_package { level - 1 } else { level };
+    if up >= base.len() {
+        return None;
+    }
+    base.truncate(base.len() - up);
+    if !relative.is_empty() {
+        base.push(relative);
+    }
+    Some(base.join("."))
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{ImportGraph, ImportGraphConfig, add_import_graph};
+    use crate::parsing::{
+        module::{ModuleDocumentation, extract_module_documentation},
+        package::PackageDocumentation,
+        utils::parse_python_str,
+    };
+
+    fn module(
+        name: &str,
+        prefix: Option<&str>,
+        path: &str,
+        source: &str,
+    ) -> Result<ModuleDocumentation> {
+        let program = parse_python_str(source)?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some(name.to_string()),
+            prefix.map(str::to_string),
+            false,
+            false,
+        );
+        mod_doc.path = Some(path.into());
+        Ok(mod_doc)
+    }
+
+    fn test_package() -> Result<PackageDocumentation> {
+        Ok(PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![
+                module(
+                    "pkg",
+                    None,
+                    "pkg/__init__.py",
+                    "from . import api, core\nfrom .core import Engine\n",
+                )?,
+                module(
+                    "api",
+                    Some("pkg"),
+                    "pkg/api.py",
+                    "import os.path\nfrom .core import engine\nfrom pkg import excluded\n",
+                )?,
+                module(
+                    "core",
+                    Some("pkg"),
+                    "pkg/core/__init__.py",
+                    "from . import engine\n",
+                )?,
+                module(
+                    "engine",
+                    Some("pkg.core"),
+                    "pkg/core/engine.py",
+                    "from ..api import handler\nfrom typing import Any\n",
+                )?,
+            ],
+        })
+    }
+
+    #[test]
+    fn test_internal_imports() -> Result<()> {
+        let graph = ImportGraph::new(&test_package()?, &ImportGraphConfig::default());
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph imports {
+    "pkg";
+    "pkg.api";
+    "pkg.core";
+    "pkg.core.engine";
+    "pkg" -> "pkg.api";
+    "pkg" -> "pkg.core";
+    "pkg.api" -> "pkg";
+    "pkg.api" -> "pkg.core.engine";
+    "pkg.core" -> "pkg.core.engine";
+    "pkg.core.engine" -> "pkg.api";
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_imports_are_collapsed() -> Result<()> {
+        let mut package = test_package()?;
+        add_import_graph(&mut package, &ImportGraphConfig { external: true });
+
+        let diagram = package.modules[0].import_graph.clone().unwrap_or_default();
+        assert!(diagram.starts_with("flowchart LR\n    n0[\"pkg\"]\n"));
+        assert!(diagram.contains("    n4([\"os\"])\n"));
+        assert!(diagram.contains("    n1 --> n4\n"));
+        assert!(diagram.contains("    n3 --> n5\n"));
+        assert_eq!(package.modules[1].import_graph, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mermaid_ids_of_similar_names_differ() -> Result<()> {
+        let package = PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![
+                module("pkg", None, "pkg/__init__.py", "from . import a_b\n")?,
+                module("a", Some("pkg"), "pkg/a/__init__.py", "")?,
+                module("b", Some("pkg.a"), "pkg/a/b.py", "")?,
+                module("a_b", Some("pkg"), "pkg/a_b.py", "")?,
+            ],
+        };
+        let graph = ImportGraph::new(&package, &ImportGraphConfig::default());
+        assert_eq!(
+            graph.to_mermaid(),
+            r#"flowchart LR
+    n0["pkg"]
+    n1["pkg.a"]
+    n2["pkg.a.b"]
+    n3["pkg.a_b"]
+    n0 --> n3
+"#
+        );
+        Ok(())
+    }
+}
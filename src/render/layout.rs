@@ -0,0 +1,489 @@
+//! How the pages of a package are laid out on disk. `Tree` mirrors the package with one page
+//! per module, `SinglePage` puts the whole API in one document and `PerObject` gives every
+//! class and function its own page next to an index page for the module.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::{
+    parsing::{module::ModuleDocumentation, package::PackageDocumentation},
+    render::{
+        anchors::object_link,
+        formats::Renderer,
+        link_target,
+        metadata::{PageKind, PageMetadata, summarize},
+        qualify, render_attribute_docs, render_class_body, render_class_docs, render_function_body,
+        render_function_docs, render_module_body, render_sub_module_sections,
+        render_summary_table_with, translate_filename,
+    },
+};
+
+#[derive(
+    Clone, Copy, Debug, Default, Display, ValueEnum, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum Layout {
+    /// one page per module, mirroring the directory tree of the package
+    #[default]
+    Tree,
+    /// the entire package in a single document with a table of contents
+    SinglePage,
+    /// a page per class and function, the page of the module becomes an index linking to them
+    PerObject,
+}
+
+/// the deepest header markdown and rst have
+const MAX_HEADER_LEVEL: usize = 6;
+/// the header of a module leaves room for its classes and their methods below it
+const MAX_MODULE_LEVEL: usize = MAX_HEADER_LEVEL - 2;
+
+/// depth of a module in the package, the root package has depth 1
+fn module_depth(qualified_name: &str) -> usize {
+    qualified_name.split('.').count()
+}
+
+/// Renders every module of the package into one document. Modules are ordered so that a
+/// package comes right before its children and the header of each module is as deep as
+/// the module is nested, which pushes its functions and classes down by the same amount.
+/// Modules nested deeper than `MAX_MODULE_LEVEL` get the header level of their parent, the
+/// table of contents still shows how they are nested.
+pub fn render_single_page<R: Renderer>(package: &PackageDocumentation, renderer: &R) -> String {
+    let mut modules: Vec<_> = package
+        .modules
+        .iter()
+        .map(|mod_doc| {
+            let qualified_name = PageMetadata::from(mod_doc)
+                .title
+                .unwrap_or_else(|| package.name.clone());
+            (qualified_name, mod_doc.clone())
+        })
+        .collect();
+    modules.sort_by(|(a, _), (b, _)| a.split('.').cmp(b.split('.')));
+
+    let root = modules.iter().find(|(q, _)| *q == package.name);
+    let mut out = renderer.render_front_matter(&PageMetadata::new(
+        Some(package.name.clone()),
+        root.and_then(|(_, mod_doc)| summarize(mod_doc.docstring.as_deref())),
+        PageKind::Package,
+    ));
+    let has_root = root.is_some();
+
+    let toc = modules
+        .iter()
+        .filter(|(q, _)| *q != package.name)
+        .map(|(q, mod_doc)| {
+            let mut entry = "  ".repeat(module_depth(q).saturating_sub(2));
+            entry.push_str("- ");
+            entry.push_str(&renderer.render_link(q, &object_link("", q)));
+            if let Some(summary) = summarize(mod_doc.docstring.as_deref()) {
+                entry.push_str(": ");
+                entry.push_str(&renderer.render_text(&summary));
+            }
+            entry.push('\n');
+            entry
+        })
+        .collect::<String>();
+    let toc = if toc.is_empty() {
+        toc
+    } else {
+        format!("\n{toc}")
+    };
+    let too_deep: Vec<_> = modules
+        .iter()
+        .map(|(q, _)| q.as_str())
+        .filter(|q| module_depth(q) > MAX_MODULE_LEVEL)
+        .collect();
+    if !too_deep.is_empty() {
+        tracing::warn!(
+            "headers only go {MAX_HEADER_LEVEL} levels deep, these modules share the header level of their parent: {}",
+            too_deep.join(", ")
+        );
+    }
+    // the table of contents goes right below the docstring of the root package
+    if !has_root {
+        out.push_str(&toc);
+    }
+    for (qualified_name, mut mod_doc) in modules {
+        // sub module sections link to pages that don't exist in this layout
+        mod_doc.with_sub_modules(None);
+        let depth = module_depth(&qualified_name).min(MAX_MODULE_LEVEL);
+        let is_root = qualified_name == package.name;
+        if !is_root {
+            out.push('\n');
+            out.push_str(&renderer.render_object_header(&qualified_name, depth));
+        }
+        let body = render_module_body(mod_doc, depth, renderer);
+        if !body.trim_end().is_empty() {
+            out.push_str(body.trim_end());
+            out.push('\n');
+        }
+        if is_root {
+            out.push_str(&toc);
+        }
+    }
+
+    out
+}
+
+/// The page a module is documented on in `layout`, relative to the output directory.
+/// On a single page that is the page of the whole package
+pub fn module_page<R: Renderer>(
+    module: &ModuleDocumentation,
+    package_name: &str,
+    layout: Layout,
+    renderer: &R,
+) -> Option<PathBuf> {
+    if layout == Layout::SinglePage {
+        return Some(PathBuf::from(format!(
+            "{package_name}.{}",
+            renderer.file_extension()
+        )));
+    }
+    Some(translate_filename(&module.relative_path()?, renderer))
+}
+
+/// The page of the class or function `name` of `mod_doc` in the per object layout, relative
+/// to the directory the page of the module is in. `None` if it stays on the page of the
+/// module, see `render_module_pages`.
+pub(crate) fn object_page_path<R: Renderer>(
+    mod_doc: &ModuleDocumentation,
+    name: &str,
+    renderer: &R,
+) -> Option<PathBuf> {
+    let taken = mod_doc
+        .sub_modules
+        .iter()
+        .flatten()
+        .any(|sub| sub.name == name);
+    if taken {
+        return None;
+    }
+    Some(object_page_dir(mod_doc).join(format!("{name}.{}", renderer.file_extension())))
+}
+
+fn object_page_dir(mod_doc: &ModuleDocumentation) -> PathBuf {
+    match (PageMetadata::from(mod_doc).kind, &mod_doc.name) {
+        (PageKind::Module, Some(name)) => PathBuf::from(name),
+        _ => PathBuf::new(),
+    }
+}
+
+/// The pages of a single module in the per object layout
+#[derive(Debug)]
+pub struct ModulePages {
+    /// the page of the module itself, it goes where it would in the tree layout
+    pub index: String,
+    /// the page of every class and function, by their path relative to the directory
+    /// the index is in
+    pub objects: Vec<(PathBuf, String)>,
+}
+
+/// Splits a module into an index page and a page per class and function. The pages of the
+/// objects in `pkg/mod.py` go in `pkg/mod/`, those of a package in its own directory. An object
+/// of a package named after one of its sub modules would overwrite the page of that sub module,
+/// so it is kept on the index page instead.
+pub fn render_module_pages<R: Renderer>(mod_doc: ModuleDocumentation, renderer: &R) -> ModulePages {
+    let page = PageMetadata::from(&mod_doc);
+    let qualifier = page.title.clone();
+    let object_dir = object_page_dir(&mod_doc);
+    let has_own_page = |name: &String| {
+        let free = object_page_path(&mod_doc, name, renderer).is_some();
+        if !free {
+            tracing::warn!(
+                "{} has the same name as a sub module, keeping it on the page of the package",
+                qualify(qualifier.as_deref(), name)
+            );
+        }
+        free
+    };
+
+    let (paged_functions, functions): (Vec<_>, Vec<_>) = mod_doc
+        .functions
+        .iter()
+        .cloned()
+        .partition(|f| has_own_page(&f.name));
+    let (paged_classes, classes): (Vec<_>, Vec<_>) = mod_doc
+        .classes
+        .iter()
+        .cloned()
+        .partition(|c| has_own_page(&c.name));
+
+    let object_path = |name: &str| object_dir.join(format!("{name}.{}", renderer.file_extension()));
+    let object_pages: HashMap<String, PathBuf> = paged_functions
+        .iter()
+        .map(|f| &f.name)
+        .chain(paged_classes.iter().map(|c| &c.name))
+        .map(|name| (qualify(qualifier.as_deref(), name), object_path(name)))
+        .collect();
+
+    let mut index = renderer.render_front_matter(&page);
+    if let Some(docstring) = &mod_doc.docstring {
+        index.push('\n');
+        index.push_str(docstring.trim());
+        index.push('\n');
+    }
+
+    let summary_table = render_summary_table_with(
+        qualifier.as_deref(),
+        &mod_doc.functions,
+        &mod_doc.classes,
+        &mod_doc.attributes,
+        |qualified_name| match object_pages.get(qualified_name) {
+            Some(path) => link_target(path),
+            None => object_link("", qualified_name),
+        },
+        renderer,
+    );
+    if !summary_table.is_empty() {
+        index.push('\n');
+        index.push_str(summary_table.trim_end());
+        index.push('\n');
+    }
+
+    for diagram in [&mod_doc.class_diagram, &mod_doc.import_graph]
+        .into_iter()
+        .flatten()
+    {
+        index.push('\n');
+        index.push_str(renderer.render_mermaid(diagram).trim_end());
+        index.push('\n');
+    }
+
+    let sub_module_sections = render_sub_module_sections(&mod_doc, renderer);
+    if !sub_module_sections.is_empty() {
+        index.push('\n');
+        index.push_str(sub_module_sections.trim_end());
+        index.push('\n');
+    }
+
+    for fn_docs in functions {
+        index.push('\n');
+        index.push_str(render_function_docs(fn_docs, &qualifier, 2, renderer).trim_end());
+        index.push('\n');
+    }
+    for class_docs in classes {
+        index.push_str(render_class_docs(class_docs, &qualifier, 2, &renderer).trim_end());
+        index.push('\n');
+    }
+    for attr_docs in &mod_doc.attributes {
+        index.push('\n');
+        index.push_str(&render_attribute_docs(
+            attr_docs,
+            qualifier.as_deref(),
+            2,
+            renderer,
+        ));
+        index.push('\n');
+    }
+
+    let mut objects = vec![];
+    for fn_docs in paged_functions {
+        let qualified_name = qualify(qualifier.as_deref(), &fn_docs.name);
+        let mut out = renderer.render_front_matter(&PageMetadata::new(
+            Some(qualified_name),
+            summarize(fn_docs.docstring.as_deref()),
+            PageKind::Object,
+        ));
+        let path = object_path(&fn_docs.name);
+        out.push_str(render_function_body(fn_docs, renderer).trim_end());
+        out.push('\n');
+        objects.push((path, out));
+    }
+    for class_docs in paged_classes {
+        let qualified_name = qualify(qualifier.as_deref(), &class_docs.name);
+        let mut out = renderer.render_front_matter(&PageMetadata::new(
+            Some(qualified_name.clone()),
+            summarize(class_docs.docstring.as_deref()),
+            PageKind::Object,
+        ));
+        let path = object_path(&class_docs.name);
+        out.push_str(render_class_body(class_docs, &qualified_name, 1, renderer).trim_end());
+        out.push('\n');
+        objects.push((path, out));
+    }
+
+    ModulePages { index, objects }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{render_module_pages, render_single_page};
+    use crate::{
+        extract_package_documentation,
+        parsing::{
+            module::{ModuleReference, extract_module_documentation},
+            package::PackageDocumentation,
+            utils::parse_python_str,
+        },
+        render::formats::{html::HtmlRenderer, md::MdRenderer},
+    };
+
+    #[test]
+    fn test_single_page_shifts_headers_by_depth() -> Result<()> {
+        let (package, errored) = extract_package_documentation(
+            &PathBuf::from("tests/test_pkg"),
+            true,
+            true,
+            vec![
+                PathBuf::from("test_pkg/excluded_file.py"),
+                PathBuf::from("test_pkg/excluded_module"),
+            ],
+            None,
+        )?;
+        assert!(errored.is_empty());
+
+        let page = render_single_page(&package, &MdRenderer::new());
+        let headers: Vec<_> = page.lines().filter(|l| l.starts_with('#')).collect();
+
+        assert_eq!(headers.first(), Some(&"# test_pkg"));
+        assert_eq!(headers.iter().filter(|h| h.starts_with("# ")).count(), 1);
+        assert!(headers.contains(&"## test_pkg.bar <a id=\"test_pkg.bar\"></a>"));
+        assert!(headers.contains(&"### test_pkg.bar.Greeter <a id=\"test_pkg.bar.Greeter\"></a>"));
+        assert!(
+            headers.contains(&"#### test_pkg.sub1.sub2.one <a id=\"test_pkg.sub1.sub2.one\"></a>")
+        );
+        assert!(page.contains("- [test\\_pkg.bar](#test_pkg.bar)"));
+        assert!(page.contains("  - [test\\_pkg.sub1.mid](#test_pkg.sub1.mid)"));
+        assert!(!page.contains("## Submodules"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_page_keeps_members_of_deep_modules_below_them() -> Result<()> {
+        let program = parse_python_str(
+            r#""""Deep down, <really>."""
+
+class Diver:
+    def dive(self): pass
+"#,
+        )?;
+        let mod_doc = extract_module_documentation(
+            &program,
+            Some("deep".to_string()),
+            Some("pkg.a.b.c".to_string()),
+            false,
+            false,
+        );
+        let package = PackageDocumentation {
+            name: "pkg".to_string(),
+            modules: vec![mod_doc],
+        };
+
+        let page = render_single_page(&package, &MdRenderer::new());
+        let headers: Vec<_> = page.lines().filter(|l| l.starts_with('#')).collect();
+
+        assert!(headers.contains(&"#### pkg.a.b.c.deep <a id=\"pkg.a.b.c.deep\"></a>"));
+        assert!(
+            headers.contains(&"##### pkg.a.b.c.deep.Diver <a id=\"pkg.a.b.c.deep.Diver\"></a>")
+        );
+        assert!(headers.contains(
+            &"###### pkg.a.b.c.deep.Diver.dive <a id=\"pkg.a.b.c.deep.Diver.dive\"></a>"
+        ));
+        // the summaries in the table of contents are text like any other
+        let page = render_single_page(&package, &HtmlRenderer::new());
+        assert!(page.contains("</a>: Deep down, &lt;really&gt;."));
+        Ok(())
+    }
+
+    #[test]
+    fn test_per_object_pages() -> Result<()> {
+        let program = parse_python_str(
+            r#""""The module."""
+
+def add(a, b):
+    """Adds things."""
+
+class Adder:
+    """Keeps adding."""
+
+    def add(self, x):
+        """Adds x."""
+"#,
+        )?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some("maths".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        mod_doc.path = Some(PathBuf::from("pkg/maths.py"));
+
+        let pages = render_module_pages(mod_doc, &MdRenderer::new());
+        assert_eq!(
+            pages.index,
+            r"# pkg.maths
+
+The module.
+
+| Name | Summary |
+| --- | --- |
+| [add](maths/add.md) | Adds things. |
+| [Adder](maths/Adder.md) | Keeps adding. |
+"
+        );
+
+        let paths: Vec<_> = pages.objects.iter().map(|(p, _)| p.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("maths/add.md"),
+                PathBuf::from("maths/Adder.md")
+            ]
+        );
+        assert_eq!(
+            pages.objects[0].1,
+            "# pkg.maths.add\n\nadd(a, b)\n\nAdds things.\n"
+        );
+        assert_eq!(
+            pages.objects[1].1,
+            r#"# pkg.maths.Adder
+
+Keeps adding.
+
+| Name | Summary |
+| --- | --- |
+| [add](#pkg.maths.Adder.add) | Adds x. |
+
+## pkg.maths.Adder.add <a id="pkg.maths.Adder.add"></a>
+
+add(self, x)
+
+Adds x.
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_per_object_pages_of_package_avoid_sub_modules() -> Result<()> {
+        let program = parse_python_str("def maths():\n    pass\n\ndef other():\n    pass\n")?;
+        let mut mod_doc =
+            extract_module_documentation(&program, Some("pkg".to_string()), None, false, false);
+        mod_doc.path = Some(PathBuf::from("pkg/__init__.py"));
+        mod_doc.with_sub_modules(Some(vec![ModuleReference {
+            name: "maths".to_string(),
+            path: PathBuf::from("maths.py"),
+            summary: None,
+            is_package: false,
+        }]));
+
+        let pages = render_module_pages(mod_doc, &MdRenderer::new());
+        let paths: Vec<_> = pages.objects.iter().map(|(p, _)| p.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("other.md")]);
+        assert!(pages.index.contains("| [other](other.md) |"));
+        assert!(
+            pages
+                .index
+                .contains("## pkg.maths <a id=\"pkg.maths\"></a>")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use toml::{Table, Value};
+
+use crate::parsing::module::ModuleDocumentation;
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PageKind {
+    /// the page of an `__init__.py`
+    Package,
+    Module,
+    /// the page of a single class or function in the per object layout
+    Object,
+}
+
+/// Everything a renderer may want to know about a page to produce its front matter
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageMetadata {
+    /// the fully qualified name of the module, e.g. `pkg.sub.mod`
+    pub title: Option<String>,
+    /// the first sentence of the module docstring
+    pub summary: Option<String>,
+    pub kind: PageKind,
+}
+
+impl PageMetadata {
+    pub fn new(title: Option<String>, summary: Option<String>, kind: PageKind) -> Self {
+        Self {
+            title,
+            summary,
+            kind,
+        }
+    }
+}
+
+impl From<&ModuleDocumentation> for PageMetadata {
+    fn from(mod_doc: &ModuleDocumentation) -> Self {
+        let title = match (&mod_doc.prefix, &mod_doc.name) {
+            (None, None) => None,
+            (None, Some(name)) => Some(name.to_string()),
+            (Some(pref), None) => Some(pref.to_string()),
+            (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
+        };
+        let is_package = match &mod_doc.path {
+            Some(path) => path.ends_with("__init__.py"),
+            None => mod_doc.sub_modules.is_some(),
+        };
+        let kind = if is_package {
+            PageKind::Package
+        } else {
+            PageKind::Module
+        };
+
+        Self::new(title, summarize(mod_doc.docstring.as_deref()), kind)
+    }
+}
+
+/// the first sentence of the first paragraph of a docstring, or the whole paragraph
+/// if it has no sentence ending in a period
+pub(crate) fn summarize(docstring: Option<&str>) -> Option<String> {
+    let paragraph = docstring?
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| l.is_empty())
+        .take_while(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if paragraph.is_empty() {
+        return None;
+    }
+
+    let sentence_end = paragraph
+        .char_indices()
+        .zip(paragraph.chars().skip(1))
+        .find(|((_, c), next)| *c == '.' && next.is_whitespace())
+        .map(|((i, _), _)| i + 1);
+    match sentence_end {
+        Some(end) => Some(paragraph[..end].to_string()),
+        None => Some(paragraph),
+    }
+}
+
+/// Extra front matter fields from the `[front_matter]` section of `snakedown.toml`.
+/// Fields in `default` are added to every page, `package`, `module` and `object` are merged
+/// on top for pages of that kind. String values may contain the placeholders `{module}`,
+/// `{summary}` and `{kind}`.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrontMatterConfig {
+    pub default: Table,
+    pub package: Table,
+    pub module: Table,
+    pub object: Table,
+}
+
+impl FrontMatterConfig {
+    pub fn is_empty(&self) -> bool {
+        self.default.is_empty()
+            && self.package.is_empty()
+            && self.module.is_empty()
+            && self.object.is_empty()
+    }
+
+    /// the fields of the page with all placeholders substituted
+    pub fn resolve(&self, page: &PageMetadata) -> Table {
+        let mut fields = self.default.clone();
+        let overrides = match page.kind {
+            PageKind::Package => &self.package,
+            PageKind::Module => &self.module,
+            PageKind::Object => &self.object,
+        };
+        merge_tables(&mut fields, overrides);
+
+        fields
+            .into_iter()
+            .map(|(k, v)| (k, substitute(v, page)))
+            .collect()
+    }
+}
+
+/// nested tables are merged key by key, anything else in `other` replaces what is in `base`
+fn merge_tables(base: &mut Table, other: &Table) {
+    for (key, value) in other {
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(base_table)), Value::Table(other_table)) => {
+                merge_tables(base_table, other_table);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn substitute(value: Value, page: &PageMetadata) -> Value {
+    match value {
+        Value::String(s) => Value::String(
+            s.replace("{module}", page.title.as_deref().unwrap_or_default())
+                .replace("{summary}", page.summary.as_deref().unwrap_or_default())
+                .replace("{kind}", &page.kind.to_string()),
+        ),
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(|v| substitute(v, page)).collect())
+        }
+        Value::Table(table) => Value::Table(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, substitute(v, page)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use toml::Table;
+
+    use super::{FrontMatterConfig, PageKind, PageMetadata, summarize};
+    use crate::parsing::{module::extract_module_documentation, utils::parse_python_str};
+
+    #[test]
+    fn test_page_metadata_from_module() -> Result<()> {
+        let program = parse_python_str("'''\n\n  Does things.\n\n  In detail.\n'''")?;
+        let mut mod_doc = extract_module_documentation(
+            &program,
+            Some("mod".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        mod_doc.path = Some("pkg/mod.py".into());
+
+        assert_eq!(
+            PageMetadata::from(&mod_doc),
+            PageMetadata::new(
+                Some("pkg.mod".to_string()),
+                Some("Does things.".to_string()),
+                PageKind::Module
+            )
+        );
+
+        mod_doc.path = Some("pkg/__init__.py".into());
+        assert_eq!(PageMetadata::from(&mod_doc).kind, PageKind::Package);
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize() -> Result<()> {
+        assert_eq!(summarize(None), None);
+        assert_eq!(summarize(Some("\n   \n")), None);
+        assert_eq!(
+            summarize(Some(
+                "\n    Adds two numbers. Floats work too.\n\n    Details."
+            )),
+            Some("Adds two numbers.".to_string())
+        );
+        assert_eq!(
+            summarize(Some("Spans\n    two lines. Then more")),
+            Some("Spans two lines.".to_string())
+        );
+        assert_eq!(
+            summarize(Some("version 1.2 of the api")),
+            Some("version 1.2 of the api".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_matter_resolution() -> Result<()> {
+        let config: FrontMatterConfig = toml::from_str(
+            r#"
+[default]
+weight = 10
+template = "api.html"
+
+[default.extra]
+module = "{module}"
+lead = "{summary}"
+
+[default.taxonomies]
+kind = ["{kind}"]
+
+[package]
+template = "api-package.html"
+
+[package.extra]
+collapsed = true
+"#,
+        )?;
+        let page = PageMetadata::new(
+            Some("pkg".to_string()),
+            Some("The package.".to_string()),
+            PageKind::Package,
+        );
+
+        let expected: Table = toml::from_str(
+            r#"
+weight = 10
+template = "api-package.html"
+
+[extra]
+module = "pkg"
+lead = "The package."
+collapsed = true
+
+[taxonomies]
+kind = ["package"]
+"#,
+        )?;
+
+        assert_eq!(config.resolve(&page), expected);
+        Ok(())
+    }
+}
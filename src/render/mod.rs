@@ -1,6 +1,12 @@
+pub mod anchors;
 pub mod args;
+pub mod diagrams;
 pub mod expr;
 pub mod formats;
+pub mod imports;
+pub mod layout;
+pub mod metadata;
+pub mod source;
 
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
@@ -10,26 +16,39 @@
 };
 use strum::Display;
 
-use args::render_args;
-use expr::render_expr;
+use args::render_parameters;
 
 use crate::{
     parsing::{
-        class::ClassDocumentation, function::FunctionDocumentation, module::ModuleDocumentation,
+        attribute::AttributeDocumentation, class::ClassDocumentation,
+        function::FunctionDocumentation, module::ModuleDocumentation,
+    },
+    render::{
+        anchors::object_link,
+        formats::Renderer,
+        metadata::{PageMetadata, summarize},
     },
-    render::formats::Renderer,
 };
 
 #[derive(Clone, Copy, Debug, Display, ValueEnum, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SSG {
     Markdown,
     Zola,
+    /// A standalone static HTML site, no SSG required
+    Html,
+    /// reStructuredText using the sphinx python domain
+    Rst,
 }
 
-pub fn translate_filename(path: &Path) -> PathBuf {
-    let mut translated = path.with_extension("md");
+/// translates the path of a python file to the path of the page the renderer writes for it
+/// e.g. `foo/bar.py` becomes `foo/bar.md` and `foo/__init__.py` becomes `foo/_index.md`
+/// for markdown output
+pub fn translate_filename<R: Renderer>(path: &Path, renderer: &R) -> PathBuf {
+    let extension = renderer.file_extension();
+    let mut translated = path.with_extension(extension);
     if translated.file_stem() == Some(OsStr::new("__init__")) {
-        translated = translated.with_file_name("_index.md");
+        translated =
+            translated.with_file_name(format!("{}.{}", renderer.index_file_stem(), extension));
     }
 
     translated
@@ -37,21 +56,63 @@ pub fn translate_filename(path: &Path) -> PathBuf {
 
 pub fn render_module<R: Renderer>(mod_doc: ModuleDocumentation, renderer: &R) -> String {
     let mut out = String::new();
-    let maybe_qualifier = match (&mod_doc.prefix, &mod_doc.name) {
-        (None, None) => None,
-        (None, Some(name)) => Some(name.to_string()),
-        (Some(pref), None) => Some(pref.to_string()),
-        (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
-    };
 
-    let front_matter_str = renderer.render_front_matter(maybe_qualifier.as_deref());
+    let front_matter_str = renderer.render_front_matter(&PageMetadata::from(&mod_doc));
     if !front_matter_str.is_empty() {
         out.push_str(&front_matter_str);
     }
 
+    out.push_str(&render_module_body(mod_doc, 1, renderer));
+    out
+}
+
+/// Everything on the page of a module below its title. `header_level` is the level of
+/// that title, the functions, classes and attributes of the module go one level below it.
+pub(crate) fn render_module_body<R: Renderer>(
+    mod_doc: ModuleDocumentation,
+    header_level: usize,
+    renderer: &R,
+) -> String {
+    let mut out = String::new();
+
     if let Some(docstring) = &mod_doc.docstring {
         out.push('\n');
-        out.push_str(docstring.trim());
+        out.push_str(renderer.render_docstring(docstring).trim_end());
+        out.push('\n');
+    }
+
+    let qualifier = match (&mod_doc.prefix, &mod_doc.name) {
+        (None, None) => None,
+        (None, Some(name)) => Some(name.clone()),
+        (Some(pref), None) => Some(pref.clone()),
+        (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
+    };
+    let summary_table = render_summary_table(
+        qualifier.as_deref(),
+        &mod_doc.functions,
+        &mod_doc.classes,
+        &mod_doc.attributes,
+        renderer,
+    );
+    if !summary_table.is_empty() {
+        out.push('\n');
+        out.push_str(summary_table.trim_end());
+        out.push('\n');
+    }
+
+    for diagram in [&mod_doc.class_diagram, &mod_doc.import_graph]
+        .into_iter()
+        .flatten()
+    {
+        out.push('\n');
+        out.push_str(renderer.render_mermaid(diagram).trim_end());
+        out.push('\n');
+    }
+
+    let sub_module_sections = render_sub_module_sections(&mod_doc, renderer);
+    if !sub_module_sections.is_empty() {
+        out.push('\n');
+        out.push_str(sub_module_sections.trim_end());
         out.push('\n');
     }
 
@@ -63,7 +124,9 @@ pub fn render_module<R: Renderer>(mod_doc: ModuleDocumentation, renderer: &R) ->
             (Some(pref), None) => Some(pref.clone()),
             (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
         };
-        out.push_str(render_function_docs(fn_docs, &sub_prefix, 2, renderer).trim_end());
+        out.push_str(
+            render_function_docs(fn_docs, &sub_prefix, header_level + 1, renderer).trim_end(),
+        );
         out.push('\n');
     }
 
@@ -75,13 +138,26 @@ pub fn render_module<R: Renderer>(mod_doc: ModuleDocumentation, renderer: &R) ->
             (Some(pref), Some(name)) => Some(format!("{pref}.{name}")),
         };
 
-        out.push_str(render_class_docs(class_docs, &sub_prefix, 2, &renderer).trim_end());
+        out.push_str(
+            render_class_docs(class_docs, &sub_prefix, header_level + 1, &renderer).trim_end(),
+        );
+        out.push('\n');
+    }
+
+    for attr_docs in &mod_doc.attributes {
+        out.push('\n');
+        out.push_str(&render_attribute_docs(
+            attr_docs,
+            qualifier.as_deref(),
+            header_level + 1,
+            renderer,
+        ));
         out.push('\n');
     }
     out
 }
 
-fn render_class_docs<R: Renderer>(
+pub(crate) fn render_class_docs<R: Renderer>(
     class_docs: ClassDocumentation,
     prefix: &Option<String>,
     header_level: usize,
@@ -92,27 +168,53 @@ fn render_class_docs<R: Renderer>(
     let fully_qualified_class_name = if let Some(p) = prefix {
         format!("{}.{}", p, &class_docs.name)
     } else {
-        format!("{}", &class_docs.name)
+        class_docs.name.clone()
     };
 
-    out.push_str(&renderer.render_header(&fully_qualified_class_name, header_level));
+    out.push_str(&renderer.render_object_header(&fully_qualified_class_name, header_level));
+    out.push_str(&render_class_body(
+        class_docs,
+        &fully_qualified_class_name,
+        header_level,
+        renderer,
+    ));
+    out
+}
+
+/// Everything documenting a class below its header, its members go one level below
+/// `header_level`
+pub(crate) fn render_class_body<R: Renderer>(
+    class_docs: ClassDocumentation,
+    fully_qualified_class_name: &str,
+    header_level: usize,
+    renderer: &R,
+) -> String {
+    let mut out = String::new();
+    if let Some(url) = &class_docs.source_url {
+        out.push('\n');
+        out.push_str(&renderer.render_source_link(url));
+    }
 
-    if let Some(docstring) = class_docs.docstring {
-        let indent = detect_docstring_indent_prefix(&docstring);
-        let docstring_ident_stripped = docstring
-            .split("\n")
-            .map(|s| s.strip_prefix(&indent).unwrap_or(s))
-            .collect::<Vec<_>>()
-            .join("\n");
+    if let Some(docstring) = &class_docs.docstring {
         out.push('\n');
-        out.push_str(docstring_ident_stripped.trim());
+        out.push_str(renderer.render_docstring(docstring).trim_end());
         out.push('\n');
     }
-    let method_prefix = if let Some(p) = prefix {
-        Some(format!("{}.{}", p, class_docs.name))
-    } else {
-        Some(class_docs.name.to_string())
-    };
+    let method_prefix = Some(fully_qualified_class_name.to_string());
+
+    let summary_table = render_summary_table(
+        method_prefix.as_deref(),
+        &class_docs.methods,
+        &[],
+        &class_docs.attributes,
+        renderer,
+    );
+    if !summary_table.is_empty() {
+        out.push('\n');
+        out.push_str(summary_table.trim_end());
+        out.push('\n');
+    }
+
     for fn_docs in class_docs.methods {
         out.push('\n');
         out.push_str(
@@ -120,10 +222,40 @@ fn render_class_docs<R: Renderer>(
         );
         out.push('\n');
     }
+
+    for attr_docs in &class_docs.attributes {
+        out.push('\n');
+        out.push_str(&render_attribute_docs(
+            attr_docs,
+            method_prefix.as_deref(),
+            header_level + 1,
+            renderer,
+        ));
+        out.push('\n');
+    }
     out
 }
 
-fn render_function_docs<R: Renderer>(
+pub(crate) fn render_attribute_docs<R: Renderer>(
+    attr_docs: &AttributeDocumentation,
+    prefix: Option<&str>,
+    header_level: usize,
+    renderer: &R,
+) -> String {
+    let mut out = String::new();
+    out.push_str(&renderer.render_object_header(&qualify(prefix, &attr_docs.name), header_level));
+
+    out.push('\n');
+    out.push_str(&renderer.render_declaration(attr_docs));
+
+    if let Some(docstring) = &attr_docs.docstring {
+        out.push('\n');
+        out.push_str(renderer.render_docstring(docstring).trim_end());
+    }
+    out.trim_end().to_string()
+}
+
+pub(crate) fn render_function_docs<R: Renderer>(
     fn_docs: FunctionDocumentation,
     prefix: &Option<String>,
     header_level: usize,
@@ -136,31 +268,173 @@ fn render_function_docs<R: Renderer>(
     } else {
         fn_docs.name.to_string()
     };
-    out.push_str(&renderer.render_header(&fully_qualified_function_name, header_level));
+    out.push_str(&renderer.render_object_header(&fully_qualified_function_name, header_level));
+    out.push_str(&render_function_body(fn_docs, renderer));
+    out
+}
 
+/// Everything documenting a function below its header
+pub(crate) fn render_function_body<R: Renderer>(
+    fn_docs: FunctionDocumentation,
+    renderer: &R,
+) -> String {
+    let mut out = String::new();
     out.push('\n');
+    out.push_str(&renderer.render_signature(&fn_docs));
+
+    if let Some(url) = &fn_docs.source_url {
+        out.push('\n');
+        out.push_str(&renderer.render_source_link(url));
+    }
+
+    if let Some(docstring) = &fn_docs.docstring {
+        out.push('\n');
+        out.push_str(renderer.render_docstring(docstring).trim_end());
+    }
+    out
+}
+
+/// An autosummary style table with a row for every function, class and attribute linking
+/// to its entry further down the page, together with the first sentence of its docstring.
+/// `prefix` is the qualified name of the module or class the entries belong to.
+pub(crate) fn render_summary_table<R: Renderer>(
+    prefix: Option<&str>,
+    functions: &[FunctionDocumentation],
+    classes: &[ClassDocumentation],
+    attributes: &[AttributeDocumentation],
+    renderer: &R,
+) -> String {
+    render_summary_table_with(
+        prefix,
+        functions,
+        classes,
+        attributes,
+        |qualified_name| object_link("", qualified_name),
+        renderer,
+    )
+}
+
+/// like `render_summary_table` but `target` decides where the entry with the given qualified
+/// name links to, for layouts where not everything is on the same page
+pub(crate) fn render_summary_table_with<R: Renderer, F: Fn(&str) -> String>(
+    prefix: Option<&str>,
+    functions: &[FunctionDocumentation],
+    classes: &[ClassDocumentation],
+    attributes: &[AttributeDocumentation],
+    target: F,
+    renderer: &R,
+) -> String {
+    let rows = functions
+        .iter()
+        .map(|f| (&f.name, &f.docstring))
+        .chain(classes.iter().map(|c| (&c.name, &c.docstring)))
+        .chain(attributes.iter().map(|a| (&a.name, &a.docstring)))
+        .map(|(name, docstring)| {
+            vec![
+                renderer.render_link(name, &target(&qualify(prefix, name))),
+                summarize(docstring.as_deref())
+                    .map(|s| renderer.render_text(&s))
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        return String::new();
+    }
+    renderer.render_table(&["Name", "Summary"], &rows)
+}
+
+pub(crate) fn qualify(prefix: Option<&str>, name: &str) -> String {
+    match prefix {
+        Some(p) => format!("{p}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// The "Subpackages" and "Submodules" sections of a package page, linking to the pages of
+/// its direct children. Empty for modules and packages without children.
+pub(crate) fn render_sub_module_sections<R: Renderer>(
+    mod_doc: &ModuleDocumentation,
+    renderer: &R,
+) -> String {
+    let Some(sub_modules) = &mod_doc.sub_modules else {
+        return String::new();
+    };
+    let (packages, modules): (Vec<_>, Vec<_>) = sub_modules.iter().partition(|s| s.is_package);
+
+    let mut out = String::new();
+    for (title, children) in [("Subpackages", packages), ("Submodules", modules)] {
+        if children.is_empty() {
+            continue;
+        }
+        let items = children
+            .iter()
+            .map(|child| {
+                let link = renderer.render_link(
+                    &child.name,
+                    &link_target(&translate_filename(&child.path, renderer)),
+                );
+                match &child.summary {
+                    Some(summary) => format!("{link}: {}", renderer.render_text(summary)),
+                    None => link,
+                }
+            })
+            .collect::<Vec<_>>();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&renderer.render_header(title, 2));
+        out.push('\n');
+        out.push_str(&renderer.render_list(&items));
+    }
+    out
+}
+
+/// relative links always use `/` as a separator
+pub(crate) fn link_target(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// renders the signature of a function as python source without the `def`, e.g. `foo(a, b=1) -> int`
+pub(crate) fn format_signature(fn_docs: &FunctionDocumentation) -> String {
+    let mut out = String::new();
     out.push_str(&fn_docs.name);
     out.push('(');
-    out.push_str(&render_args(fn_docs.args));
+    out.push_str(&render_parameters(&fn_docs.args));
     out.push(')');
-    if let Some(return_annotation) = fn_docs.return_type {
-        out.push_str(&format!(" -> {}", render_expr(return_annotation)));
+    if let Some(return_annotation) = &fn_docs.return_type {
+        out.push_str(&format!(" -> {return_annotation}"));
     }
-    out.push('\n');
+    out
+}
 
-    if let Some(docstring) = fn_docs.docstring {
-        let indent = detect_docstring_indent_prefix(&docstring);
-        let docstring_ident_stripped = docstring
-            .split("\n")
-            .map(|s| s.strip_prefix(&indent).unwrap_or(s))
-            .collect::<Vec<_>>()
-            .join("\n");
-        out.push('\n');
-        out.push_str(docstring_ident_stripped.trim());
+/// renders an attribute the way it is declared, e.g. `TIMEOUT: int = 30`
+pub(crate) fn format_declaration(attr_docs: &AttributeDocumentation) -> String {
+    let mut out = attr_docs.name.clone();
+    if let Some(annotation) = &attr_docs.annotation {
+        out.push_str(&format!(": {annotation}"));
+    }
+    if let Some(value) = &attr_docs.value {
+        out.push_str(&format!(" = {value}"));
     }
     out
 }
 
+/// Strips the common indentation prefix (see `detect_docstring_indent_prefix`) from every
+/// line of the docstring. Lines that do not start with the prefix are left untouched.
+pub(crate) fn dedent_docstring(docstring: &str) -> String {
+    let indent = detect_docstring_indent_prefix(docstring);
+    docstring
+        .split("\n")
+        .map(|s| s.strip_prefix(&indent).unwrap_or(s))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Detects the common indentation prefix of a Python docstring.
 /// Returns the leading whitespace (spaces/tabs) of the least-indented non-empty line after the first.
 /// This handles both spaces and tabs without normalization.
@@ -188,7 +462,7 @@ mod test {
     use crate::{
         parsing::{module::extract_module_documentation, utils::parse_python_str},
         render::{
-            formats::{md::MdRenderer, zola::ZolaRenderer},
+            formats::{md::MdRenderer, rst::RstRenderer, zola::ZolaRenderer},
             render_module, translate_filename,
         },
     };
@@ -249,17 +523,27 @@ fn expected_module_docs_rendered() -> &'static str {
 
 This is a module that is used to test snakedown.
 
-## snakedown.testing.test_module.foo
+| Name | Summary |
+| --- | --- |
+| [foo](#snakedown.testing.test_module.foo) | this is a docstring for the foo function |
+| [Greeter](#snakedown.testing.test_module.Greeter) | this is a class docstring. |
+
+## snakedown.testing.test_module.foo <a id="snakedown.testing.test_module.foo"></a>
 
 foo(bar: int) -> Dict[str, Any]
 
 this is a docstring for the foo function
 
-## snakedown.testing.test_module.Greeter
+## snakedown.testing.test_module.Greeter <a id="snakedown.testing.test_module.Greeter"></a>
 
 this is a class docstring.
 
-### snakedown.testing.test_module.Greeter.greet
+| Name | Summary |
+| --- | --- |
+| [greet](#snakedown.testing.test_module.Greeter.greet) | Greet the world. |
+| [class\_var](#snakedown.testing.test_module.Greeter.class_var) |  |
+
+### snakedown.testing.test_module.Greeter.greet <a id="snakedown.testing.test_module.Greeter.greet"></a>
 
 greet(self, name, *args, foo: str = "bar", **kwargs) -> Callable[[], None]
 
@@ -274,6 +558,10 @@ fn expected_module_docs_rendered() -> &'static str {
 -------
 Callable[[], None]
     just a random closure to make the types interesting to render.
+
+### snakedown.testing.test_module.Greeter.class_var <a id="snakedown.testing.test_module.Greeter.class_var"></a>
+
+class_var = "whatever"
 "#
     }
 
@@ -298,17 +586,27 @@ fn expected_module_docs_no_prefix_no_name_rendered() -> &'static str {
         r#"
 This is a module that is used to test snakedown.
 
-## foo
+| Name | Summary |
+| --- | --- |
+| [foo](#foo) | this is a docstring for the foo function |
+| [Greeter](#Greeter) | this is a class docstring. |
+
+## foo <a id="foo"></a>
 
 foo(bar: int) -> Dict[str, Any]
 
 this is a docstring for the foo function
 
-## Greeter
+## Greeter <a id="Greeter"></a>
 
 this is a class docstring.
 
-### Greeter.greet
+| Name | Summary |
+| --- | --- |
+| [greet](#Greeter.greet) | Greet the world. |
+| [class\_var](#Greeter.class_var) |  |
+
+### Greeter.greet <a id="Greeter.greet"></a>
 
 greet(self, name, *args, foo: str = "bar", **kwargs) -> Callable[[], None]
 
@@ -323,6 +621,10 @@ fn expected_module_docs_no_prefix_no_name_rendered() -> &'static str {
 -------
 Callable[[], None]
     just a random closure to make the types interesting to render.
+
+### Greeter.class_var <a id="Greeter.class_var"></a>
+
+class_var = "whatever"
 "#
     }
 
@@ -331,17 +633,27 @@ fn expected_module_docs_only_prefix_rendered() -> &'static str {
 
 This is a module that is used to test snakedown.
 
-## snakedown.foo
+| Name | Summary |
+| --- | --- |
+| [foo](#snakedown.foo) | this is a docstring for the foo function |
+| [Greeter](#snakedown.Greeter) | this is a class docstring. |
+
+## snakedown.foo <a id="snakedown.foo"></a>
 
 foo(bar: int) -> Dict[str, Any]
 
 this is a docstring for the foo function
 
-## snakedown.Greeter
+## snakedown.Greeter <a id="snakedown.Greeter"></a>
 
 this is a class docstring.
 
-### snakedown.Greeter.greet
+| Name | Summary |
+| --- | --- |
+| [greet](#snakedown.Greeter.greet) | Greet the world. |
+| [class\_var](#snakedown.Greeter.class_var) |  |
+
+### snakedown.Greeter.greet <a id="snakedown.Greeter.greet"></a>
 
 greet(self, name, *args, foo: str = "bar", **kwargs) -> Callable[[], None]
 
@@ -356,6 +668,10 @@ fn expected_module_docs_only_prefix_rendered() -> &'static str {
 -------
 Callable[[], None]
     just a random closure to make the types interesting to render.
+
+### snakedown.Greeter.class_var <a id="snakedown.Greeter.class_var"></a>
+
+class_var = "whatever"
 "#
     }
 
@@ -393,17 +709,27 @@ fn expected_module_docs_only_name_rendered() -> &'static str {
 
 This is a module that is used to test snakedown.
 
-## snakedown.foo
+| Name | Summary |
+| --- | --- |
+| [foo](#snakedown.foo) | this is a docstring for the foo function |
+| [Greeter](#snakedown.Greeter) | this is a class docstring. |
+
+## snakedown.foo <a id="snakedown.foo"></a>
 
 foo(bar: int) -> Dict[str, Any]
 
 this is a docstring for the foo function
 
-## snakedown.Greeter
+## snakedown.Greeter <a id="snakedown.Greeter"></a>
 
 this is a class docstring.
 
-### snakedown.Greeter.greet
+| Name | Summary |
+| --- | --- |
+| [greet](#snakedown.Greeter.greet) | Greet the world. |
+| [class\_var](#snakedown.Greeter.class_var) |  |
+
+### snakedown.Greeter.greet <a id="snakedown.Greeter.greet"></a>
 
 greet(self, name, *args, foo: str = "bar", **kwargs) -> Callable[[], None]
 
@@ -418,6 +744,10 @@ fn expected_module_docs_only_name_rendered() -> &'static str {
 -------
 Callable[[], None]
     just a random closure to make the types interesting to render.
+
+### snakedown.Greeter.class_var <a id="snakedown.Greeter.class_var"></a>
+
+class_var = "whatever"
 "#
     }
     fn expected_module_docs_zola_rendered() -> &'static str {
@@ -427,17 +757,27 @@ fn expected_module_docs_zola_rendered() -> &'static str {
 
 This is a module that is used to test snakedown.
 
-## snakedown.foo
+| Name | Summary |
+| --- | --- |
+| [foo](#snakedown.foo) | this is a docstring for the foo function |
+| [Greeter](#snakedown.Greeter) | this is a class docstring. |
+
+## snakedown.foo {#snakedown.foo}
 
 foo(bar: int) -> Dict[str, Any]
 
 this is a docstring for the foo function
 
-## snakedown.Greeter
+## snakedown.Greeter {#snakedown.Greeter}
 
 this is a class docstring.
 
-### snakedown.Greeter.greet
+| Name | Summary |
+| --- | --- |
+| [greet](#snakedown.Greeter.greet) | Greet the world. |
+| [class\_var](#snakedown.Greeter.class_var) |  |
+
+### snakedown.Greeter.greet {#snakedown.Greeter.greet}
 
 greet(self, name, *args, foo: str = "bar", **kwargs) -> Callable[[], None]
 
@@ -452,6 +792,10 @@ fn expected_module_docs_zola_rendered() -> &'static str {
 -------
 Callable[[], None]
     just a random closure to make the types interesting to render.
+
+### snakedown.Greeter.class_var {#snakedown.Greeter.class_var}
+
+class_var = "whatever"
 "#
     }
 
@@ -489,18 +833,45 @@ fn render_module_documentation_zola() -> Result<()> {
 
         Ok(())
     }
+    #[test]
+    fn render_module_with_source_links() -> Result<()> {
+        let parsed = parse_python_str("def foo():\n    '''Foo.'''\n")?;
+        let mut mod_documentation =
+            extract_module_documentation(&parsed, Some("mod".to_string()), None, false, false);
+        mod_documentation.functions[0].source_url =
+            Some("https://example.com/mod.py#L1-L2".to_string());
+
+        let rendered = render_module(mod_documentation, &MdRenderer::new());
+
+        assert!(rendered.contains("foo()\n\n[source](https://example.com/mod.py#L1-L2)\n\nFoo."));
+        Ok(())
+    }
+
     #[test]
     fn test_translate_filename_init() -> Result<()> {
         let input = PathBuf::from("foo/bar/__init__.py");
         let expected = PathBuf::from("foo/bar/_index.md");
-        assert_eq!(translate_filename(&input), expected);
+        assert_eq!(translate_filename(&input, &MdRenderer::new()), expected);
         Ok(())
     }
     #[test]
     fn test_translate_filename_module() -> Result<()> {
         let input = PathBuf::from("foo/bar/baz.py");
         let expected = PathBuf::from("foo/bar/baz.md");
-        assert_eq!(translate_filename(&input), expected);
+        assert_eq!(translate_filename(&input, &MdRenderer::new()), expected);
+        Ok(())
+    }
+    #[test]
+    fn test_translate_filename_rst() -> Result<()> {
+        let renderer = RstRenderer::new();
+        assert_eq!(
+            translate_filename(&PathBuf::from("foo/bar/__init__.py"), &renderer),
+            PathBuf::from("foo/bar/index.rst")
+        );
+        assert_eq!(
+            translate_filename(&PathBuf::from("foo/bar/baz.py"), &renderer),
+            PathBuf::from("foo/bar/baz.rst")
+        );
         Ok(())
     }
 }
@@ -0,0 +1,209 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    parsing::{module::ModuleDocumentation, utils::SourceLines},
+    render::link_target,
+};
+
+/// The `[source_links]` section of `snakedown.toml`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLinkConfig {
+    /// template of the link to a definition, `{repo}`, `{rev}`, `{path}`, `{start}` and `{end}`
+    /// are substituted e.g. `https://github.com/{repo}/blob/{rev}/{path}#L{start}-L{end}`
+    pub url: String,
+    pub repo: Option<String>,
+    /// the branch, tag or commit to link to.
+    /// Defaults to the commit checked out in the repository containing the package
+    pub rev: Option<String>,
+}
+
+/// Fills in the `source_url` of documented objects from a `SourceLinkConfig`
+#[derive(Debug)]
+pub struct SourceLinker {
+    template: String,
+    repo: String,
+    rev: String,
+    /// the directory module paths are relative to, relative to the root of the repository
+    base: PathBuf,
+}
+
+impl SourceLinker {
+    pub fn new(config: &SourceLinkConfig, pkg_path: &Path) -> Result<Self> {
+        // module paths start with the name of the root package
+        let pkg_parent = pkg_path
+            .canonicalize()?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let base = match git(&pkg_parent, &["rev-parse", "--show-toplevel"]) {
+            Ok(root) => pkg_parent
+                .strip_prefix(PathBuf::from(root.trim()).canonicalize()?)?
+                .to_path_buf(),
+            Err(e) => {
+                tracing::warn!(
+                    "{e}, source links will be relative to {}",
+                    pkg_parent.display()
+                );
+                PathBuf::new()
+            }
+        };
+        // only ask git for the checked out commit if the links need it
+        let rev = match &config.rev {
+            Some(rev) => rev.clone(),
+            None if config.url.contains("{rev}") => {
+                git(&pkg_parent, &["rev-parse", "HEAD"])?.trim().to_string()
+            }
+            None => String::new(),
+        };
+        let repo = match &config.repo {
+            Some(repo) => repo.clone(),
+            None if config.url.contains("{repo}") => {
+                return Err(eyre!(
+                    "the source link template {} uses {{repo}}, but source_links.repo is not set",
+                    config.url
+                ));
+            }
+            None => String::new(),
+        };
+
+        Ok(Self {
+            template: config.url.clone(),
+            repo,
+            rev,
+            base,
+        })
+    }
+
+    /// `path` is relative to the directory containing the root package, like `ModuleDocumentation::path`
+    pub fn url(&self, path: &Path, lines: SourceLines) -> String {
+        self.template
+            .replace("{repo}", &self.repo)
+            .replace("{rev}", &self.rev)
+            .replace("{path}", &link_target(&self.base.join(path)))
+            .replace("{start}", &lines.start.to_string())
+            .replace("{end}", &lines.end.to_string())
+    }
+
+    /// links every function, class and method of the module whose lines are known
+    pub fn link_module(&self, mod_doc: &mut ModuleDocumentation) {
+        let Some(path) = mod_doc.path.clone() else {
+            return;
+        };
+        let url = |lines: Option<SourceLines>| lines.map(|l| self.url(&path, l));
+
+        for fn_docs in &mut mod_doc.functions {
+            fn_docs.source_url = url(fn_docs.lines);
+        }
+        for class_docs in &mut mod_doc.classes {
+            class_docs.source_url = url(class_docs.lines);
+            for method in &mut class_docs.methods {
+                method.source_url = url(method.lines);
+            }
+        }
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::create_dir_all, path::Path};
+
+    use assert_fs::TempDir;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{SourceLinkConfig, SourceLinker};
+    use crate::parsing::{
+        module::extract_located_module_documentation,
+        utils::{SourceLines, parse_python_str},
+    };
+
+    #[test]
+    fn test_source_url_template() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let pkg_path = tmp_dir.join("pkg");
+        create_dir_all(&pkg_path)?;
+        let config = SourceLinkConfig {
+            url: "https://git.example.com/{repo}/blob/{rev}/{path}#L{start}-L{end}".to_string(),
+            repo: Some("me/pkg".to_string()),
+            rev: Some("v1.0".to_string()),
+        };
+
+        let linker = SourceLinker::new(&config, &pkg_path)?;
+
+        assert_eq!(
+            linker.url(Path::new("pkg/mod.py"), SourceLines { start: 3, end: 7 }),
+            "https://git.example.com/me/pkg/blob/v1.0/pkg/mod.py#L3-L7"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_repo_is_required_by_template() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let pkg_path = tmp_dir.join("pkg");
+        create_dir_all(&pkg_path)?;
+        let config = SourceLinkConfig {
+            url: "https://github.com/{repo}/blob/main/{path}".to_string(),
+            repo: None,
+            rev: None,
+        };
+
+        assert!(SourceLinker::new(&config, &pkg_path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_link_module() -> Result<()> {
+        let source = "def foo():\n    pass\n\nclass Bar:\n    def baz(self):\n        pass\n";
+        let program = parse_python_str(source)?;
+        let mut mod_doc = extract_located_module_documentation(
+            &program,
+            source,
+            Some("mod".to_string()),
+            Some("pkg".to_string()),
+            false,
+            false,
+        );
+        mod_doc.path = Some("pkg/mod.py".into());
+        let tmp_dir = TempDir::new()?;
+        let pkg_path = tmp_dir.join("pkg");
+        create_dir_all(&pkg_path)?;
+        // neither the repo nor the rev are needed, the temp dir isn't a git repository
+        let config = SourceLinkConfig {
+            url: "{path}#L{start}".to_string(),
+            repo: None,
+            rev: None,
+        };
+
+        SourceLinker::new(&config, &pkg_path)?.link_module(&mut mod_doc);
+
+        assert_eq!(
+            mod_doc.functions[0].source_url.as_deref(),
+            Some("pkg/mod.py#L1")
+        );
+        assert_eq!(
+            mod_doc.classes[0].methods[0].source_url.as_deref(),
+            Some("pkg/mod.py#L5")
+        );
+        Ok(())
+    }
+}
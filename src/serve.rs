@@ -0,0 +1,322 @@
+//! A development server for the html output that reloads the pages open in the browser
+//! whenever the package is rebuilt. It speaks just enough HTTP for a browser on localhost,
+//! the reloads are pushed as server sent events on `RELOAD_PATH`.
+
+use std::{
+    net::SocketAddr,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use color_eyre::Result;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::watch,
+};
+
+use crate::watch::{next_changes, source_snapshot};
+
+pub const RELOAD_PATH: &str = "/__snakedown/reload";
+
+pub struct DevServer {
+    listener: TcpListener,
+    site_dir: PathBuf,
+    reloads: watch::Sender<u64>,
+}
+
+impl DevServer {
+    /// Listens on `address`, e.g. `127.0.0.1:8000`, serving the files in `site_dir`
+    pub async fn bind(address: &str, site_dir: &Path) -> Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        let (reloads, _) = watch::channel(0);
+        Ok(Self {
+            listener,
+            site_dir: site_dir.to_path_buf(),
+            reloads,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// every change sent on this reloads the pages open in the browser
+    pub fn reloads(&self) -> watch::Sender<u64> {
+        self.reloads.clone()
+    }
+
+    pub async fn run(self) -> Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let site_dir = self.site_dir.clone();
+            let reloads = self.reloads.subscribe();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &site_dir, reloads).await {
+                    tracing::debug!("dropped connection: {e}");
+                }
+            });
+        }
+    }
+}
+
+/// Calls `rebuild` and reloads the browser whenever the python files in `pkg_path` change,
+/// see `crate::watch::next_changes` for how bursts of changes are handled. `rebuild` runs on
+/// a blocking thread so the server keeps answering in the meantime.
+pub async fn watch_package<F>(
+    pkg_path: &Path,
+    debounce: Duration,
+    reloads: watch::Sender<u64>,
+    rebuild: F,
+) -> Result<()>
+where
+    F: Fn() -> Result<()> + Send + Sync + 'static,
+{
+    let rebuild = Arc::new(rebuild);
+    let mut snapshot = source_snapshot(pkg_path);
+    loop {
+        next_changes(pkg_path, &mut snapshot, debounce).await;
+        tracing::info!("{} changed, rebuilding", pkg_path.display());
+        let rebuild = Arc::clone(&rebuild);
+        match tokio::task::spawn_blocking(move || rebuild()).await? {
+            Ok(()) => {
+                reloads.send_modify(|generation| *generation += 1);
+            }
+            // keep serving the last good build while the sources are being edited
+            Err(e) => tracing::error!("rebuilding failed: {e}"),
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    site_dir: &Path,
+    reloads: watch::Receiver<u64>,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    // none of the headers matter for static files
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return respond(&mut writer, "400 Bad Request", "text/plain", b"bad request").await;
+    };
+    if method != "GET" {
+        return respond(
+            &mut writer,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"method not allowed",
+        )
+        .await;
+    }
+    let url_path = target.split(['?', '#']).next().unwrap_or_default();
+    if url_path == RELOAD_PATH {
+        return stream_reloads(&mut writer, reloads).await;
+    }
+
+    let Some(file) = percent_decode(url_path).and_then(|p| resolve_url_path(site_dir, &p)) else {
+        return respond(&mut writer, "404 Not Found", "text/plain", b"not found").await;
+    };
+    let contents = tokio::fs::read(&file).await?;
+    let content_type = content_type(&file);
+    if content_type.starts_with("text/html") {
+        let page = inject_reload_script(&String::from_utf8_lossy(&contents));
+        respond(&mut writer, "200 OK", content_type, page.as_bytes()).await
+    } else {
+        respond(&mut writer, "200 OK", content_type, &contents).await
+    }
+}
+
+async fn respond<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// keeps the connection open, sending an event every time the site was rebuilt
+async fn stream_reloads<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    mut reloads: watch::Receiver<u64>,
+) -> Result<()> {
+    writer
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\n\r\n",
+        )
+        .await?;
+    writer.flush().await?;
+    while reloads.changed().await.is_ok() {
+        writer.write_all(b"data: reload\n\n").await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+/// Decodes the `%XX` escapes of `url_path`, `None` if they don't make valid utf-8
+fn percent_decode(url_path: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(url_path.len());
+    let mut rest = url_path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = std::str::from_utf8(rest.get(..2)?).ok()?;
+        bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        rest = &rest[2..];
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// The file `url_path` refers to, directories are served by their `index.html`
+fn resolve_url_path(site_dir: &Path, url_path: &str) -> Option<PathBuf> {
+    let relative = Path::new(url_path.trim_start_matches('/'));
+    // nothing outside of the site can be requested
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    let path = site_dir.join(relative);
+    let path = if path.is_dir() {
+        path.join("index.html")
+    } else {
+        path
+    };
+    path.is_file().then_some(path)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("txt" | "md" | "mmd" | "dot") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+fn inject_reload_script(page: &str) -> String {
+    let script = format!(
+        "<script>new EventSource(\"{RELOAD_PATH}\").onmessage = () => location.reload();</script>\n"
+    );
+    match page.rfind("</body>") {
+        Some(end) => format!("{}{script}{}", &page[..end], &page[end..]),
+        None => format!("{page}{script}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use assert_fs::TempDir;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use super::{DevServer, RELOAD_PATH, percent_decode, resolve_url_path};
+
+    async fn get(address: &str, path: &str) -> Result<TcpStream> {
+        let mut stream = TcpStream::connect(address).await?;
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await?;
+        Ok(stream)
+    }
+
+    #[test]
+    fn test_resolve_url_path() -> Result<()> {
+        let site = TempDir::new()?;
+        std::fs::create_dir(site.path().join("sub"))?;
+        std::fs::write(site.path().join("index.html"), "")?;
+        std::fs::write(site.path().join("sub/index.html"), "")?;
+
+        assert_eq!(
+            resolve_url_path(site.path(), "/"),
+            Some(site.path().join("index.html"))
+        );
+        assert_eq!(
+            resolve_url_path(site.path(), "/sub/"),
+            Some(site.path().join("sub/index.html"))
+        );
+        assert_eq!(resolve_url_path(site.path(), "/missing.html"), None);
+        assert_eq!(resolve_url_path(site.path(), "/../index.html"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_percent_decode() -> Result<()> {
+        assert_eq!(
+            percent_decode("/my%20module/caf%C3%A9.html").as_deref(),
+            Some("/my module/café.html")
+        );
+        assert_eq!(
+            percent_decode("/%2e%2e/index.html").as_deref(),
+            Some("/../index.html")
+        );
+        assert_eq!(percent_decode("/broken%2"), None);
+        assert_eq!(percent_decode("/%ff.html"), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serves_pages_and_pushes_reloads() -> Result<()> {
+        let site = TempDir::new()?;
+        std::fs::write(
+            site.path().join("index.html"),
+            "<html><body>hi</body></html>",
+        )?;
+        let server = DevServer::bind("127.0.0.1:0", site.path()).await?;
+        let address = server.local_addr()?.to_string();
+        let reloads = server.reloads();
+        tokio::spawn(server.run());
+
+        let mut page = String::new();
+        get(&address, "/").await?.read_to_string(&mut page).await?;
+        assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(page.contains(&format!("new EventSource(\"{RELOAD_PATH}\")")));
+        assert!(page.ends_with("</script>\n</body></html>"));
+
+        let mut events = get(&address, RELOAD_PATH).await?;
+        let mut buf = vec![0; 1024];
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            let n = events.read(&mut buf).await?;
+            head.push_str(&String::from_utf8_lossy(&buf[..n]));
+        }
+        assert!(head.contains("text/event-stream"));
+
+        reloads.send_modify(|generation| *generation += 1);
+        let n = tokio::time::timeout(Duration::from_secs(5), events.read(&mut buf)).await??;
+        assert_eq!(String::from_utf8_lossy(&buf[..n]), "data: reload\n\n");
+        Ok(())
+    }
+}
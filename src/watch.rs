@@ -0,0 +1,138 @@
+//! Polling the python files of a package for changes. Polling needs no platform specific
+//! file watching and a package is small enough to stat every few milliseconds.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use walkdir::WalkDir;
+
+/// The modification time of every python file in a package
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+pub fn source_snapshot(pkg_path: &Path) -> Snapshot {
+    WalkDir::new(pkg_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "py"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.into_path(), modified))
+        })
+        .collect()
+}
+
+/// The python files that changed between two snapshots, as found by `source_snapshot`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SourceChanges {
+    pub modified: Vec<PathBuf>,
+    pub added: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+impl SourceChanges {
+    pub fn between(old: &Snapshot, new: &Snapshot) -> Self {
+        let mut changes = SourceChanges::default();
+        for (path, modified) in new {
+            match old.get(path) {
+                Some(previous) if previous == modified => (),
+                Some(_) => changes.modified.push(path.clone()),
+                None => changes.added.push(path.clone()),
+            }
+        }
+        changes.removed = old
+            .keys()
+            .filter(|path| !new.contains_key(*path))
+            .cloned()
+            .collect();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modified.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Waits until the package differs from `snapshot`, which is then updated. Changes are only
+/// reported once the package has been left alone for `debounce`, so the burst of writes of
+/// an editor saving (or of a `git checkout`) is handled at once.
+pub async fn next_changes(
+    pkg_path: &Path,
+    snapshot: &mut Snapshot,
+    debounce: Duration,
+) -> SourceChanges {
+    let mut latest = snapshot.clone();
+    loop {
+        tokio::time::sleep(debounce).await;
+        let current = source_snapshot(pkg_path);
+        if current != latest {
+            latest = current;
+            continue;
+        }
+        if latest != *snapshot {
+            break;
+        }
+    }
+    let changes = SourceChanges::between(snapshot, &latest);
+    *snapshot = latest;
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
+
+    use assert_fs::TempDir;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{Snapshot, SourceChanges, next_changes, source_snapshot};
+
+    #[test]
+    fn test_changes_between_snapshots() {
+        let then = SystemTime::UNIX_EPOCH;
+        let now = then + Duration::from_secs(1);
+        let old = Snapshot::from([
+            (PathBuf::from("pkg/a.py"), then),
+            (PathBuf::from("pkg/b.py"), then),
+        ]);
+        let new = Snapshot::from([
+            (PathBuf::from("pkg/a.py"), now),
+            (PathBuf::from("pkg/c.py"), now),
+        ]);
+        assert_eq!(
+            SourceChanges::between(&old, &new),
+            SourceChanges {
+                modified: vec![PathBuf::from("pkg/a.py")],
+                added: vec![PathBuf::from("pkg/c.py")],
+                removed: vec![PathBuf::from("pkg/b.py")],
+            }
+        );
+        assert!(SourceChanges::between(&old, &old).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_next_changes_sees_new_modules() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("__init__.py"), "")?;
+        std::fs::write(temp_dir.path().join("notes.txt"), "")?;
+        let mut snapshot = source_snapshot(temp_dir.path());
+        assert_eq!(snapshot.len(), 1);
+
+        let new_module = temp_dir.path().join("new.py");
+        std::fs::write(&new_module, "")?;
+        let changes = tokio::time::timeout(
+            Duration::from_secs(5),
+            next_changes(temp_dir.path(), &mut snapshot, Duration::from_millis(10)),
+        )
+        .await?;
+        assert_eq!(changes.added, vec![new_module]);
+        assert_eq!(snapshot.len(), 2);
+        Ok(())
+    }
+}
@@ -63,3 +63,190 @@ fn test_cli_with_zola() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_cli_with_html() -> Result<()> {
+    let tempdir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.arg("tests/test_pkg")
+        .arg(tempdir.path())
+        .arg("--skip-undoc")
+        .arg("--skip-private")
+        .arg("-e")
+        .arg("test_pkg/excluded_file.py")
+        .arg("--exclude")
+        .arg("test_pkg/excluded_module")
+        .arg("--ssg")
+        .arg("html");
+    cmd.assert().success();
+
+    assert!(tempdir.path().join("index.html").exists());
+    assert!(tempdir.path().join("snakedown.css").exists());
+    assert!(tempdir.path().join("objects.inv").exists());
+    assert!(tempdir.path().join("sub1/sub2/two.html").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_build_then_clean() -> Result<()> {
+    let tempdir = tempdir()?;
+    let out_dir = tempdir.path().join("api");
+    let options = [
+        "--skip-undoc",
+        "--skip-private",
+        "-e",
+        "test_pkg/excluded_file.py",
+        "--exclude",
+        "test_pkg/excluded_module",
+    ];
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.arg("build")
+        .arg("tests/test_pkg")
+        .arg(&out_dir)
+        .args(options);
+    cmd.assert().success();
+    assert!(!is_different(&out_dir, "tests/rendered_no_private")?);
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.arg("clean")
+        .arg("tests/test_pkg")
+        .arg(&out_dir)
+        .args(options);
+    cmd.assert().success();
+    assert!(!out_dir.exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_init_with_zola_site() -> Result<()> {
+    let tempdir = tempdir()?;
+    std::fs::write(
+        tempdir.path().join("pyproject.toml"),
+        "[project]\nname = \"test-pkg\"\n",
+    )?;
+    std::fs::create_dir_all(tempdir.path().join("src/test_pkg"))?;
+    std::fs::write(tempdir.path().join("src/test_pkg/__init__.py"), "")?;
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.current_dir(tempdir.path())
+        .arg("init")
+        .arg("--site")
+        .arg("zola");
+    cmd.assert().success();
+
+    let config = std::fs::read_to_string(tempdir.path().join("snakedown.toml"))?;
+    assert!(config.contains("pkg_path = \"src/test_pkg\"\n"));
+    assert!(config.contains("output_dir = \"docs/content/api\"\n"));
+    assert!(config.contains("ssg = \"Zola\"\n"));
+    assert!(tempdir.path().join("docs/config.toml").is_file());
+
+    // the configuration is never overwritten by accident
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.current_dir(tempdir.path()).arg("init");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_diff_of_unchanged_package() -> Result<()> {
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.arg("diff")
+        .arg("tests/test_pkg")
+        .arg("tests/test_pkg")
+        .arg("--fail-on-breaking");
+    cmd.assert()
+        .success()
+        .stdout("No changes to the public API.\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_build_writes_build_report() -> Result<()> {
+    let tempdir = tempdir()?;
+    let out_dir = tempdir.path().join("api");
+    let report_path = tempdir.path().join("report.json");
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    cmd.arg("build")
+        .arg("tests/test_pkg")
+        .arg(&out_dir)
+        .arg("--skip-private")
+        .arg("--exclude")
+        .arg("test_pkg/excluded_module")
+        .arg("--build-report")
+        .arg(&report_path);
+    cmd.assert().success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report_path)?)?;
+    let reasons = |reason: &str| {
+        report["skipped"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|skipped| skipped["reason"] == reason)
+            .count()
+    };
+    assert!(!report["modules"].as_array().is_none_or(Vec::is_empty));
+    assert!(!report["outputs"].as_array().is_none_or(Vec::is_empty));
+    assert_eq!(reasons("excluded"), 1);
+    assert!(reasons("private") > 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_fail_on_error() -> Result<()> {
+    let tempdir = tempdir()?;
+    let pkg_dir = tempdir.path().join("broken_pkg");
+    std::fs::create_dir_all(&pkg_dir)?;
+    std::fs::write(pkg_dir.join("__init__.py"), "\"\"\"A package.\"\"\"\n")?;
+    std::fs::write(pkg_dir.join("broken.py"), "def f(:\n    pass\n")?;
+    let out_dir = tempdir.path().join("api");
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    let output = cmd.arg("build").arg(&pkg_dir).arg(&out_dir).output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("1 modules could not be processed:"));
+
+    let mut cmd = Command::cargo_bin("snakedown")?;
+    let output = cmd
+        .arg("build")
+        .arg(&pkg_dir)
+        .arg(&out_dir)
+        .arg("--fail-on-error")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("error[syntax-error]"));
+    assert!(stderr.contains("1 | def f(:\n"));
+    assert!(stderr.contains("broken.py:1:"));
+
+    Ok(())
+}
+
+#[test]
+fn test_load_api_from_json_export() -> Result<()> {
+    let tempdir = tempdir()?;
+    let json_path = tempdir.path().join("api.json");
+    let (package, _) = snakedown::extract_package_documentation(
+        std::path::Path::new("tests/test_pkg"),
+        true,
+        true,
+        vec![],
+        None,
+    )?;
+    snakedown::export_json(&package, &json_path)?;
+
+    let (package, errored): (snakedown::parsing::PackageDocumentation, _) =
+        snakedown::load_api(&json_path, vec![])?;
+    assert!(errored.is_empty());
+    assert_eq!(package.name, "test_pkg");
+
+    Ok(())
+}